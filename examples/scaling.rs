@@ -46,7 +46,8 @@ impl State for GameState {
                 ScalingMode::Fixed => ScalingMode::Stretch,
                 ScalingMode::Stretch => ScalingMode::ShowAll,
                 ScalingMode::ShowAll => ScalingMode::ShowAllPixelPerfect,
-                ScalingMode::ShowAllPixelPerfect => ScalingMode::Crop,
+                ScalingMode::ShowAllPixelPerfect => ScalingMode::ShowAllPixelPerfectEven,
+                ScalingMode::ShowAllPixelPerfectEven => ScalingMode::Crop,
                 ScalingMode::Crop => ScalingMode::CropPixelPerfect,
                 ScalingMode::CropPixelPerfect => ScalingMode::Fixed,
             };
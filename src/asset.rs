@@ -0,0 +1,570 @@
+//! Functions and types relating to loading assets.
+
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, Read};
+use std::marker::PhantomData;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+#[cfg(feature = "audio")]
+use crate::audio::Sound;
+use crate::error::{Result, TetraError};
+use crate::fs;
+#[cfg(feature = "font_ttf")]
+use crate::graphics::text::{Font, VectorFontBuilder};
+use crate::graphics::Texture;
+use crate::Context;
+
+/// A handle to an asset that has been queued in an [`AssetLoader`].
+///
+/// Handles can be used to check whether an individual asset has finished loading, and to
+/// retrieve the asset once it has.
+#[derive(Debug)]
+pub struct AssetHandle<T> {
+    index: usize,
+    _marker: PhantomData<T>,
+}
+
+impl<T> AssetHandle<T> {
+    fn new(index: usize) -> AssetHandle<T> {
+        AssetHandle {
+            index,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> Clone for AssetHandle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for AssetHandle<T> {}
+
+impl<T> PartialEq for AssetHandle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index
+    }
+}
+
+impl<T> Eq for AssetHandle<T> {}
+
+mod sealed {
+    #[cfg(feature = "audio")]
+    use crate::audio::Sound;
+    #[cfg(feature = "font_ttf")]
+    use crate::graphics::text::Font;
+    use crate::graphics::Texture;
+
+    pub trait Sealed: Sized {
+        fn from_loaded(asset: LoadedAsset) -> Option<Self>;
+    }
+
+    pub enum LoadedAsset {
+        Texture(Texture),
+
+        #[cfg(feature = "font_ttf")]
+        Font(Font),
+
+        #[cfg(feature = "audio")]
+        Sound(Sound),
+    }
+}
+
+use sealed::LoadedAsset;
+
+/// Implemented for types that can be loaded via an [`AssetLoader`].
+///
+/// This trait is sealed, and cannot be implemented outside of Tetra.
+pub trait Asset: sealed::Sealed {}
+
+impl Asset for Texture {}
+
+impl sealed::Sealed for Texture {
+    fn from_loaded(asset: LoadedAsset) -> Option<Self> {
+        match asset {
+            LoadedAsset::Texture(texture) => Some(texture),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "font_ttf")]
+impl Asset for Font {}
+
+#[cfg(feature = "font_ttf")]
+impl sealed::Sealed for Font {
+    fn from_loaded(asset: LoadedAsset) -> Option<Self> {
+        match asset {
+            LoadedAsset::Font(font) => Some(font),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "audio")]
+impl Asset for Sound {}
+
+#[cfg(feature = "audio")]
+impl sealed::Sealed for Sound {
+    fn from_loaded(asset: LoadedAsset) -> Option<Self> {
+        match asset {
+            LoadedAsset::Sound(sound) => Some(sound),
+            _ => None,
+        }
+    }
+}
+
+/// A source that assets can be read from, such as the filesystem or a packed archive.
+///
+/// By default, Tetra loads assets from the [`FileSystem`]. Implementing this trait and passing
+/// it to [`set_source`] allows all of Tetra's file-based loading functions (e.g.
+/// [`Texture::new`], [`Font::vector`], [`Sound::new`](crate::audio::Sound::new) and
+/// [`AssetLoader`]) to read from somewhere else instead, without needing to change the
+/// code at each call site.
+///
+/// As assets can be loaded from worker threads, implementations need to be `Send` and `Sync`.
+pub trait AssetSource: Send + Sync {
+    /// Opens the asset at the given path for reading.
+    ///
+    /// If the asset does not exist, an error with the kind [`io::ErrorKind::NotFound`]
+    /// should be returned.
+    fn open(&self, path: &Path) -> io::Result<Box<dyn Read>>;
+}
+
+/// An [`AssetSource`] that reads assets from the filesystem.
+///
+/// This is the source that is used by default.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FileSystem;
+
+impl AssetSource for FileSystem {
+    fn open(&self, path: &Path) -> io::Result<Box<dyn Read>> {
+        Ok(Box::new(File::open(path)?))
+    }
+}
+
+/// Sets the source that assets will be loaded from.
+///
+/// This affects every subsequent load, including ones that are already queued in an
+/// [`AssetLoader`]. Unlike most of Tetra's settings, it is global to the process, rather
+/// than being stored in the [`Context`] - this is because some assets (such as sounds)
+/// can be loaded without a context.
+pub fn set_source<S>(source: S)
+where
+    S: AssetSource + 'static,
+{
+    fs::set_source(Some(Arc::new(source)));
+}
+
+/// Resets the source that assets will be loaded from to the [`FileSystem`].
+pub fn reset_source() {
+    fs::set_source(None);
+}
+
+/// The work that is done on a background thread.
+enum Job {
+    Image(PathBuf),
+
+    #[allow(dead_code)]
+    Bytes(PathBuf),
+
+    /// Blocks the worker for the given duration, to simulate a slow load.
+    #[cfg(test)]
+    Sleep(std::time::Duration),
+}
+
+/// The result of a background job, which still needs finishing on the main thread.
+enum Decoded {
+    Image {
+        width: i32,
+        height: i32,
+        data: Vec<u8>,
+    },
+
+    #[allow(dead_code)]
+    Bytes(Vec<u8>),
+}
+
+/// What should be created on the main thread once a job has been decoded.
+enum Request {
+    Texture,
+
+    #[cfg(feature = "font_ttf")]
+    Font(f32),
+
+    #[cfg(feature = "audio")]
+    Sound,
+}
+
+enum Slot {
+    Pending(Request),
+    Decoded(Request, Decoded),
+    Finished(Result<LoadedAsset>),
+    Taken,
+}
+
+/// Loads assets in the background, reporting progress as it goes.
+///
+/// Files are read and decoded on a pool of worker threads. Any work that needs to happen on the
+/// main thread (such as uploading textures to the GPU) is then spread out over multiple calls to
+/// [`update`](AssetLoader::update), so that you can keep drawing a loading screen while your
+/// assets are being loaded.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tetra::asset::AssetLoader;
+/// # use tetra::graphics::Texture;
+/// # use tetra::Context;
+/// # fn example(ctx: &mut Context) -> tetra::Result {
+/// let mut loader = AssetLoader::new();
+/// let player = loader.load_texture("./player.png");
+///
+/// // Then, in your `update` method:
+/// loader.update(ctx);
+///
+/// if loader.is_finished() {
+///     let player: Texture = loader.take(player).unwrap()?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct AssetLoader {
+    job_sender: Option<Sender<(usize, Job)>>,
+    result_receiver: Receiver<(usize, Result<Decoded>)>,
+    workers: Vec<thread::JoinHandle<()>>,
+    cancelled: Arc<AtomicBool>,
+
+    slots: Vec<Slot>,
+    ready: VecDeque<usize>,
+    finished: usize,
+    uploads_per_update: usize,
+}
+
+impl AssetLoader {
+    /// Creates a new asset loader, with four worker threads.
+    pub fn new() -> AssetLoader {
+        AssetLoader::with_workers(4)
+    }
+
+    /// Creates a new asset loader, with the specified number of worker threads.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `workers` is zero.
+    pub fn with_workers(workers: usize) -> AssetLoader {
+        assert!(workers > 0, "an asset loader needs at least one worker");
+
+        let (job_sender, job_receiver) = mpsc::channel::<(usize, Job)>();
+        let (result_sender, result_receiver) = mpsc::channel();
+
+        let job_receiver = Arc::new(Mutex::new(job_receiver));
+        let cancelled = Arc::new(AtomicBool::new(false));
+
+        let workers = (0..workers)
+            .map(|_| {
+                let job_receiver = Arc::clone(&job_receiver);
+                let result_sender = result_sender.clone();
+                let cancelled = Arc::clone(&cancelled);
+
+                thread::spawn(move || loop {
+                    let next = job_receiver.lock().ok().and_then(|r| r.recv().ok());
+
+                    let (index, job) = match next {
+                        Some(next) => next,
+                        None => break,
+                    };
+
+                    // Jobs that are still queued when the loader is dropped are skipped.
+                    if cancelled.load(Ordering::Relaxed) {
+                        break;
+                    }
+
+                    // If decoding panics, the loader would otherwise wait for the result
+                    // forever - report it as an error instead, and keep the worker alive.
+                    let result = panic::catch_unwind(AssertUnwindSafe(|| decode(job)))
+                        .unwrap_or_else(|_| {
+                            Err(TetraError::PlatformError(
+                                "a worker thread panicked while loading an asset".into(),
+                            ))
+                        });
+
+                    if result_sender.send((index, result)).is_err() {
+                        break;
+                    }
+                })
+            })
+            .collect();
+
+        AssetLoader {
+            job_sender: Some(job_sender),
+            result_receiver,
+            workers,
+            cancelled,
+
+            slots: Vec::new(),
+            ready: VecDeque::new(),
+            finished: 0,
+            uploads_per_update: 1,
+        }
+    }
+
+    /// Queues a texture to be loaded from the given file.
+    ///
+    /// The supported formats are the same as [`Texture::new`].
+    pub fn load_texture<P>(&mut self, path: P) -> AssetHandle<Texture>
+    where
+        P: AsRef<Path>,
+    {
+        self.queue(Request::Texture, Job::Image(path.as_ref().to_owned()))
+    }
+
+    /// Queues a vector font to be loaded from the given file, at the given size.
+    ///
+    /// The supported formats are the same as [`Font::vector`].
+    #[cfg(feature = "font_ttf")]
+    pub fn load_font<P>(&mut self, path: P, size: f32) -> AssetHandle<Font>
+    where
+        P: AsRef<Path>,
+    {
+        self.queue(Request::Font(size), Job::Bytes(path.as_ref().to_owned()))
+    }
+
+    /// Queues a sound to be loaded from the given file.
+    ///
+    /// The supported formats are the same as [`Sound::new`].
+    #[cfg(feature = "audio")]
+    pub fn load_sound<P>(&mut self, path: P) -> AssetHandle<Sound>
+    where
+        P: AsRef<Path>,
+    {
+        self.queue(Request::Sound, Job::Bytes(path.as_ref().to_owned()))
+    }
+
+    fn queue<T>(&mut self, request: Request, job: Job) -> AssetHandle<T> {
+        let index = self.slots.len();
+
+        let sent = match &self.job_sender {
+            Some(sender) => sender.send((index, job)).is_ok(),
+            None => false,
+        };
+
+        if sent {
+            self.slots.push(Slot::Pending(request));
+        } else {
+            // If the workers have shut down, nothing will ever pick up the job.
+            self.slots
+                .push(Slot::Finished(Err(TetraError::PlatformError(
+                    "the asset loader's worker threads have shut down".into(),
+                ))));
+            self.finished += 1;
+        }
+
+        AssetHandle::new(index)
+    }
+
+    /// Finishes loading any assets that have been decoded by the worker threads.
+    ///
+    /// This should be called once per update (e.g. in [`State::update`](crate::State::update))
+    /// while assets are loading. To avoid stalling the game loop, only a limited number of assets
+    /// will be finished per call - see [`set_uploads_per_update`](AssetLoader::set_uploads_per_update).
+    pub fn update(&mut self, ctx: &mut Context) {
+        while let Ok((index, decoded)) = self.result_receiver.try_recv() {
+            let slot = std::mem::replace(&mut self.slots[index], Slot::Taken);
+
+            self.slots[index] = match (slot, decoded) {
+                (Slot::Pending(request), Ok(decoded)) => {
+                    self.ready.push_back(index);
+                    Slot::Decoded(request, decoded)
+                }
+                (_, Err(e)) => {
+                    self.finished += 1;
+                    Slot::Finished(Err(e))
+                }
+                (slot, _) => slot,
+            };
+        }
+
+        for _ in 0..self.uploads_per_update {
+            let index = match self.ready.pop_front() {
+                Some(index) => index,
+                None => break,
+            };
+
+            if let Slot::Decoded(request, decoded) =
+                std::mem::replace(&mut self.slots[index], Slot::Taken)
+            {
+                self.slots[index] = Slot::Finished(finish(ctx, request, decoded));
+                self.finished += 1;
+            }
+        }
+    }
+
+    /// Returns a value between 0.0 and 1.0, representing how many of the queued assets have
+    /// finished loading (successfully or otherwise).
+    ///
+    /// If no assets have been queued, this will return 1.0.
+    pub fn progress(&self) -> f32 {
+        if self.slots.is_empty() {
+            1.0
+        } else {
+            self.finished as f32 / self.slots.len() as f32
+        }
+    }
+
+    /// Returns `true` if all of the queued assets have finished loading (successfully or otherwise).
+    pub fn is_finished(&self) -> bool {
+        self.finished == self.slots.len()
+    }
+
+    /// Returns `true` if the specified asset has finished loading (successfully or otherwise).
+    pub fn is_loaded<T>(&self, handle: AssetHandle<T>) -> bool {
+        matches!(
+            self.slots.get(handle.index),
+            Some(Slot::Finished(_)) | Some(Slot::Taken)
+        )
+    }
+
+    /// Takes the specified asset out of the loader.
+    ///
+    /// Returns [`None`] if the asset has not finished loading yet, or if it has already been taken.
+    ///
+    /// # Errors
+    ///
+    /// If the asset failed to load, the error that occurred will be returned. This will be the same
+    /// error that would have been returned by the equivalent synchronous constructor.
+    pub fn take<T>(&mut self, handle: AssetHandle<T>) -> Option<Result<T>>
+    where
+        T: Asset,
+    {
+        let slot = self.slots.get_mut(handle.index)?;
+
+        match std::mem::replace(slot, Slot::Taken) {
+            Slot::Finished(result) => Some(result.map(|asset| {
+                T::from_loaded(asset).expect("asset handle should match the type of the asset")
+            })),
+            other => {
+                *slot = other;
+                None
+            }
+        }
+    }
+
+    /// Returns the maximum number of assets that will be finished per call to
+    /// [`update`](AssetLoader::update).
+    ///
+    /// Defaults to `1`.
+    pub fn uploads_per_update(&self) -> usize {
+        self.uploads_per_update
+    }
+
+    /// Sets the maximum number of assets that will be finished per call to
+    /// [`update`](AssetLoader::update).
+    ///
+    /// Higher values will make loading complete faster, at the cost of longer frames while
+    /// loading.
+    pub fn set_uploads_per_update(&mut self, uploads_per_update: usize) {
+        self.uploads_per_update = uploads_per_update.max(1);
+    }
+}
+
+impl Default for AssetLoader {
+    fn default() -> Self {
+        AssetLoader::new()
+    }
+}
+
+impl Drop for AssetLoader {
+    fn drop(&mut self) {
+        // The channel will keep handing out queued jobs even after the sender is dropped,
+        // so the workers also need to be told to skip them. Closing the channel then wakes
+        // up any workers that are waiting for a job, so that they can exit.
+        self.cancelled.store(true, Ordering::Relaxed);
+        self.job_sender = None;
+
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+fn decode(job: Job) -> Result<Decoded> {
+    match job {
+        Job::Image(path) => {
+            let image = fs::read_to_image(path)?.into_rgba8();
+            let (width, height) = image.dimensions();
+
+            Ok(Decoded::Image {
+                width: width as i32,
+                height: height as i32,
+                data: image.into_raw(),
+            })
+        }
+
+        Job::Bytes(path) => Ok(Decoded::Bytes(fs::read(path)?)),
+
+        #[cfg(test)]
+        Job::Sleep(duration) => {
+            thread::sleep(duration);
+            Ok(Decoded::Bytes(Vec::new()))
+        }
+    }
+}
+
+fn finish(ctx: &mut Context, request: Request, decoded: Decoded) -> Result<LoadedAsset> {
+    match (request, decoded) {
+        (
+            Request::Texture,
+            Decoded::Image {
+                width,
+                height,
+                data,
+            },
+        ) => Ok(LoadedAsset::Texture(Texture::from_rgba(
+            ctx, width, height, &data,
+        )?)),
+
+        #[cfg(feature = "font_ttf")]
+        (Request::Font(size), Decoded::Bytes(data)) => Ok(LoadedAsset::Font(
+            VectorFontBuilder::from_vec(data)?.with_size(ctx, size)?,
+        )),
+
+        #[cfg(feature = "audio")]
+        (Request::Sound, Decoded::Bytes(data)) => {
+            Ok(LoadedAsset::Sound(Sound::from_file_data(&data)))
+        }
+
+        _ => unreachable!("asset request did not match the decoded data"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::{AssetLoader, Job, Request};
+
+    #[test]
+    fn dropping_loader_skips_queued_jobs() {
+        let mut loader = AssetLoader::with_workers(2);
+
+        for _ in 0..100 {
+            loader.queue::<()>(Request::Texture, Job::Sleep(Duration::from_millis(50)));
+        }
+
+        // Loading everything would take 2.5 seconds - only the jobs that are already
+        // in progress should need to finish.
+        let start = Instant::now();
+        drop(loader);
+
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+}
@@ -1,5 +1,7 @@
 //! Functions and types relating to audio playback.
 
+use std::collections::VecDeque;
+use std::f64::consts::PI;
 use std::io::Cursor;
 use std::path::Path;
 use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
@@ -7,7 +9,7 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use rodio::source::{Buffered, Empty};
-use rodio::{Decoder, Device as RodioDevice, Sample, Source};
+use rodio::{Decoder, Device as RodioDevice, DeviceTrait, Sample, Source};
 
 use crate::error::{Result, TetraError};
 use crate::fs;
@@ -265,6 +267,119 @@ pub fn get_master_volume(ctx: &mut Context) -> f32 {
     ctx.audio.master_volume()
 }
 
+/// Suspends or resumes all audio playback.
+///
+/// While audio is suspended, all sounds will output silence without advancing, and will
+/// continue from where they left off once audio is resumed. The individual
+/// [`SoundState`] of each [`SoundInstance`] is not affected.
+///
+/// This can be combined with `time::set_paused` to stop your game from using resources while
+/// it is paused or in the background.
+pub fn set_suspended(ctx: &mut Context, suspended: bool) {
+    ctx.audio.set_suspended(suspended);
+}
+
+/// Returns whether or not audio playback is currently suspended.
+pub fn is_suspended(ctx: &Context) -> bool {
+    ctx.audio.suspended()
+}
+
+/// Returns the names of the audio output devices that are connected to the system.
+///
+/// # Errors
+///
+/// * [`TetraError::PlatformError`] will be returned if the devices could not be queried.
+pub fn get_devices(_ctx: &Context) -> Result<Vec<String>> {
+    let devices = rodio::output_devices().map_err(|e| TetraError::PlatformError(e.to_string()))?;
+
+    Ok(devices.filter_map(|d| d.name().ok()).collect())
+}
+
+/// Returns the name of the audio device that is currently being used for playback, if
+/// there is one.
+pub fn get_device(ctx: &Context) -> Option<String> {
+    ctx.audio.device_name()
+}
+
+/// Sets the audio device that should be used for playback.
+///
+/// The name should match one of the names returned by [`get_devices`]. Once a device has been
+/// selected via this function, Tetra will no longer automatically switch devices when the
+/// system's default device changes - use [`reset_device`] to return to that behaviour.
+///
+/// Note that sounds that are already playing will continue to play on the previous device.
+///
+/// # Errors
+///
+/// * [`TetraError::PlatformError`] will be returned if the devices could not be queried.
+/// * [`TetraError::NoAudioDevice`] will be returned if no device with the given name
+/// is connected.
+pub fn set_device(ctx: &mut Context, name: &str) -> Result {
+    ctx.audio.set_device(name)
+}
+
+/// Switches playback back to the system's default audio device.
+///
+/// Tetra will also start to automatically switch devices when the system's default device
+/// changes (e.g. when headphones are plugged in), which is the default behaviour.
+///
+/// Note that sounds that are already playing will continue to play on the previous device.
+pub fn reset_device(ctx: &mut Context) {
+    ctx.audio.reset_device();
+}
+
+/// Returns the sample rate that audio is being output at, if an audio device is available.
+///
+/// This is the native sample rate of the current audio device. Sounds with a different
+/// sample rate (or that are being played at a different speed) will be resampled to this
+/// rate, using the current [`ResampleQuality`].
+pub fn get_sample_rate(ctx: &Context) -> Option<u32> {
+    ctx.audio.sample_rate
+}
+
+/// Returns the quality of resampling that will be used for newly played sounds.
+pub fn get_resample_quality(ctx: &Context) -> ResampleQuality {
+    ctx.audio.resample_quality
+}
+
+/// Sets the quality of resampling that will be used for newly played sounds.
+///
+/// Sounds that are already playing will not be affected.
+pub fn set_resample_quality(ctx: &mut Context, resample_quality: ResampleQuality) {
+    ctx.audio.resample_quality = resample_quality;
+}
+
+/// The algorithms that can be used to resample audio.
+///
+/// Resampling is required when a sound's sample rate does not match the
+/// [output sample rate](get_sample_rate), or when it is played back at a different speed.
+/// Higher quality resampling reduces aliasing (which can make pitch-shifted sounds harsh
+/// or metallic), at the cost of more CPU time on the audio thread.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ResampleQuality {
+    /// Each output sample is taken from the nearest input sample.
+    ///
+    /// This is the fastest method, but also has the most aliasing.
+    Nearest,
+
+    /// Each output sample is linearly interpolated between the two nearest input samples.
+    ///
+    /// This is the default.
+    Linear,
+
+    /// Each output sample is interpolated from the surrounding input samples via a
+    /// windowed sinc filter.
+    ///
+    /// This is the slowest method, but has the least aliasing.
+    Sinc,
+}
+
+impl Default for ResampleQuality {
+    fn default() -> ResampleQuality {
+        ResampleQuality::Linear
+    }
+}
+
 #[derive(Debug)]
 struct AudioControls {
     playing: AtomicBool,
@@ -319,20 +434,70 @@ impl AudioControls {
 
 pub(crate) struct AudioDevice {
     device: Option<RodioDevice>,
+    sample_rate: Option<u32>,
+    use_default_device: bool,
+    resample_quality: ResampleQuality,
     master_volume: Arc<AtomicU32>,
+    suspended: Arc<AtomicBool>,
 }
 
 impl AudioDevice {
     pub(crate) fn new() -> AudioDevice {
-        let device = rodio::default_output_device();
+        let mut audio = AudioDevice {
+            device: None,
+            sample_rate: None,
+            use_default_device: true,
+            resample_quality: ResampleQuality::default(),
+            master_volume: Arc::new(AtomicU32::new(1.0f32.to_bits())),
+            suspended: Arc::new(AtomicBool::new(false)),
+        };
+
+        audio.set_active_device(rodio::default_output_device());
+        audio
+    }
+
+    fn set_active_device(&mut self, device: Option<RodioDevice>) {
+        self.sample_rate = device
+            .as_ref()
+            .and_then(|d| d.default_output_format().ok())
+            .map(|f| f.sample_rate.0);
+
+        self.device = open_device(device);
+    }
+
+    fn device_name(&self) -> Option<String> {
+        self.device.as_ref().and_then(|d| d.name().ok())
+    }
+
+    fn set_device(&mut self, name: &str) -> Result {
+        let device = rodio::output_devices()
+            .map_err(|e| TetraError::PlatformError(e.to_string()))?
+            .find(|d| matches!(d.name(), Ok(n) if n == name))
+            .ok_or(TetraError::NoAudioDevice)?;
+
+        self.set_active_device(Some(device));
+        self.use_default_device = false;
+
+        Ok(())
+    }
+
+    fn reset_device(&mut self) {
+        self.set_active_device(rodio::default_output_device());
+        self.use_default_device = true;
+    }
 
-        if let Some(active_device) = &device {
-            rodio::play_raw(&active_device, Empty::new());
+    /// Should be called when an audio device is connected or disconnected, so that playback
+    /// can follow the system's default device.
+    pub(crate) fn handle_devices_changed(&mut self) {
+        if !self.use_default_device {
+            return;
         }
 
-        AudioDevice {
-            device,
-            master_volume: Arc::new(AtomicU32::new(1.0f32.to_bits())),
+        let default_device = rodio::default_output_device();
+        let default_name = default_device.as_ref().and_then(|d| d.name().ok());
+
+        if default_name != self.device_name() {
+            self.set_active_device(default_device);
         }
     }
 
@@ -344,6 +509,14 @@ impl AudioDevice {
         self.master_volume.store(volume.to_bits(), Ordering::SeqCst);
     }
 
+    fn suspended(&self) -> bool {
+        self.suspended.load(Ordering::SeqCst)
+    }
+
+    fn set_suspended(&self, suspended: bool) {
+        self.suspended.store(suspended, Ordering::SeqCst);
+    }
+
     fn play_sound(
         &self,
         data: Arc<[u8]>,
@@ -371,6 +544,7 @@ impl AudioDevice {
             data,
 
             remote_master_volume: Arc::clone(&self.master_volume),
+            remote_suspended: Arc::clone(&self.suspended),
             remote_controls: Arc::clone(&controls),
             time_till_update: 220,
 
@@ -378,20 +552,36 @@ impl AudioDevice {
             playing,
             repeating,
             rewind: false,
+            suspended: self.suspended(),
             master_volume,
             volume,
             speed,
         };
 
-        rodio::play_raw(
-            self.device.as_ref().ok_or(TetraError::NoAudioDevice)?,
-            source.convert_samples(),
-        );
+        let device = self.device.as_ref().ok_or(TetraError::NoAudioDevice)?;
+
+        match self.sample_rate {
+            Some(output_rate) => rodio::play_raw(
+                device,
+                Resampler::new(source, output_rate, self.resample_quality).convert_samples(),
+            ),
+            None => rodio::play_raw(device, source.convert_samples()),
+        }
 
         Ok(controls)
     }
 }
 
+/// Starts up the audio engine for the given device, to avoid a delay when the first sound
+/// is played.
+fn open_device(device: Option<RodioDevice>) -> Option<RodioDevice> {
+    if let Some(active_device) = &device {
+        rodio::play_raw(active_device, Empty::new());
+    }
+
+    device
+}
+
 type TetraSourceData = Buffered<Decoder<Cursor<Arc<[u8]>>>>;
 
 struct TetraSource {
@@ -399,6 +589,7 @@ struct TetraSource {
     repeat_source: TetraSourceData,
 
     remote_master_volume: Arc<AtomicU32>,
+    remote_suspended: Arc<AtomicBool>,
     remote_controls: Arc<AudioControls>,
     time_till_update: u32,
 
@@ -406,6 +597,7 @@ struct TetraSource {
     playing: bool,
     repeating: bool,
     rewind: bool,
+    suspended: bool,
     master_volume: f32,
     volume: f32,
     speed: f32,
@@ -424,6 +616,7 @@ impl Iterator for TetraSource {
 
         if self.time_till_update == 0 {
             self.master_volume = f32::from_bits(self.remote_master_volume.load(Ordering::SeqCst));
+            self.suspended = self.remote_suspended.load(Ordering::SeqCst);
             self.playing = self.remote_controls.playing.load(Ordering::SeqCst);
 
             // If we're not playing, we don't really care about updating the rest of the state.
@@ -447,6 +640,10 @@ impl Iterator for TetraSource {
             return if self.detached { None } else { Some(0) };
         }
 
+        if self.suspended {
+            return Some(0);
+        }
+
         if self.rewind {
             self.data = self.repeat_source.clone();
             self.rewind = false;
@@ -519,3 +716,278 @@ impl Source for TetraSource {
         None
     }
 }
+
+/// The number of input frames on each side of the output position that are used for
+/// sinc interpolation.
+const SINC_HALF_WIDTH: usize = 4;
+
+/// Converts a source to a fixed output sample rate, so that Rodio's built-in conversion
+/// (which only supports linear interpolation) is bypassed.
+///
+/// The input sample rate is re-checked for every output frame, so changes in playback
+/// speed take effect immediately.
+struct Resampler<S> {
+    source: S,
+    quality: ResampleQuality,
+    output_rate: u32,
+    channels: u16,
+
+    /// A sliding window of input frames. The frame at `half_width - 1` is the one
+    /// at (or immediately before) the current output position.
+    frames: VecDeque<Vec<f32>>,
+    half_width: usize,
+
+    /// How far the output position is past the current input frame, from `0.0` to `1.0`.
+    position: f64,
+
+    /// The number of silent frames that have been added to the window since the source
+    /// ended.
+    trailing_frames: usize,
+
+    output: Vec<i16>,
+    output_index: usize,
+}
+
+impl<S> Resampler<S>
+where
+    S: Source<Item = i16>,
+{
+    fn new(source: S, output_rate: u32, quality: ResampleQuality) -> Resampler<S> {
+        let channels = source.channels();
+
+        let half_width = match quality {
+            ResampleQuality::Nearest | ResampleQuality::Linear => 1,
+            ResampleQuality::Sinc => SINC_HALF_WIDTH,
+        };
+
+        let mut resampler = Resampler {
+            source,
+            quality,
+            output_rate,
+            channels,
+
+            frames: VecDeque::with_capacity(half_width * 2),
+            half_width,
+            position: 0.0,
+            trailing_frames: 0,
+
+            output: vec![0; channels as usize],
+            output_index: channels as usize,
+        };
+
+        // The frames before the start of the sound are treated as silence.
+        for _ in 0..half_width - 1 {
+            resampler.frames.push_back(vec![0.0; channels as usize]);
+        }
+
+        for _ in 0..half_width + 1 {
+            let mut frame = vec![0.0; channels as usize];
+            resampler.read_frame(&mut frame);
+            resampler.frames.push_back(frame);
+        }
+
+        resampler
+    }
+
+    /// Reads the next frame from the source, padding with silence if it has ended.
+    fn read_frame(&mut self, frame: &mut [f32]) {
+        let mut ended = false;
+
+        for (i, sample) in frame.iter_mut().enumerate() {
+            *sample = match self.source.next() {
+                Some(value) => f32::from(value),
+                None => {
+                    ended |= i == 0;
+                    0.0
+                }
+            };
+        }
+
+        if ended {
+            self.trailing_frames += 1;
+        }
+    }
+
+    /// Moves the window forward by one input frame.
+    fn advance(&mut self) {
+        let mut frame = self
+            .frames
+            .pop_front()
+            .expect("window should never be empty");
+
+        self.read_frame(&mut frame);
+        self.frames.push_back(frame);
+    }
+
+    /// Generates the next output frame, returning false if the source has ended.
+    fn generate_frame(&mut self) -> bool {
+        let current = self.half_width - 1;
+
+        // Once the current frame is padding, there's nothing left to play.
+        if self.trailing_frames > self.half_width {
+            return false;
+        }
+
+        let t = self.position;
+
+        for c in 0..self.channels as usize {
+            let value = match self.quality {
+                ResampleQuality::Nearest => {
+                    let index = if t < 0.5 { current } else { current + 1 };
+                    self.frames[index][c] as f64
+                }
+
+                ResampleQuality::Linear => {
+                    let a = self.frames[current][c] as f64;
+                    let b = self.frames[current + 1][c] as f64;
+
+                    a + (b - a) * t
+                }
+
+                ResampleQuality::Sinc => {
+                    // When the input rate is higher than the output rate, the cutoff of the
+                    // filter is lowered to avoid aliasing.
+                    let step = self.step();
+                    let cutoff = if step > 1.0 { 1.0 / step } else { 1.0 };
+
+                    let mut sum = 0.0;
+                    let mut weight_sum = 0.0;
+
+                    for (k, frame) in self.frames.iter().enumerate() {
+                        let x = (k as f64 - current as f64) - t;
+                        let weight =
+                            cutoff * sinc(cutoff * x) * lanczos_window(x, self.half_width as f64);
+
+                        sum += frame[c] as f64 * weight;
+                        weight_sum += weight;
+                    }
+
+                    if weight_sum.abs() > f64::EPSILON {
+                        sum / weight_sum
+                    } else {
+                        0.0
+                    }
+                }
+            };
+
+            self.output[c] = value.round().max(i16::MIN as f64).min(i16::MAX as f64) as i16;
+        }
+
+        self.position += self.step();
+
+        while self.position >= 1.0 {
+            self.position -= 1.0;
+            self.advance();
+        }
+
+        true
+    }
+
+    /// The number of input frames that pass for each output frame.
+    fn step(&self) -> f64 {
+        self.source.sample_rate() as f64 / self.output_rate as f64
+    }
+}
+
+impl<S> Iterator for Resampler<S>
+where
+    S: Source<Item = i16>,
+{
+    type Item = i16;
+
+    #[inline]
+    fn next(&mut self) -> Option<i16> {
+        if self.output_index >= self.output.len() {
+            if !self.generate_frame() {
+                return None;
+            }
+
+            self.output_index = 0;
+        }
+
+        let sample = self.output[self.output_index];
+        self.output_index += 1;
+
+        Some(sample)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, None)
+    }
+}
+
+impl<S> Source for Resampler<S>
+where
+    S: Source<Item = i16>,
+{
+    #[inline]
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    #[inline]
+    fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> u32 {
+        self.output_rate
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+fn sinc(x: f64) -> f64 {
+    if x.abs() < f64::EPSILON {
+        1.0
+    } else {
+        (PI * x).sin() / (PI * x)
+    }
+}
+
+fn lanczos_window(x: f64, width: f64) -> f64 {
+    if x.abs() < width {
+        sinc(x / width)
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rodio::buffer::SamplesBuffer;
+
+    use super::{ResampleQuality, Resampler};
+
+    #[test]
+    fn resampling_at_same_rate_is_lossless() {
+        let input: Vec<i16> = (0..64).map(|i| i * 100 - 3200).collect();
+
+        for &quality in &[
+            ResampleQuality::Nearest,
+            ResampleQuality::Linear,
+            ResampleQuality::Sinc,
+        ] {
+            let source = SamplesBuffer::new(1, 44100, input.clone());
+            let output: Vec<i16> = Resampler::new(source, 44100, quality).collect();
+
+            assert_eq!(input, output, "{:?}", quality);
+        }
+    }
+
+    #[test]
+    fn resampling_changes_length() {
+        let input = vec![1000i16; 2 * 100];
+
+        let source = SamplesBuffer::new(2, 22050, input);
+        let output: Vec<i16> = Resampler::new(source, 44100, ResampleQuality::Linear).collect();
+
+        assert_eq!(output.len(), 2 * 200);
+        assert!(output[..2 * 198].iter().all(|&s| s == 1000));
+    }
+}
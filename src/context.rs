@@ -1,7 +1,9 @@
+use std::path::PathBuf;
 use std::result;
 use std::thread;
 use std::time::{Duration, Instant};
 
+use crate::fs;
 use crate::graphics::{self, GraphicsContext};
 use crate::input::{self, InputContext};
 use crate::platform::{self, GraphicsDevice, Window};
@@ -11,18 +13,39 @@ use crate::{Result, State, TetraError};
 #[cfg(feature = "audio")]
 use crate::audio::AudioDevice;
 
+/// How long to sleep between frames while the game loop is paused (roughly 30 FPS).
+const PAUSED_FRAME_MILLIS: u64 = 33;
+
 /// A struct containing all of the 'global' state within the framework.
+///
+/// # Resource Cleanup
+///
+/// Graphics resources (such as [`Texture`](crate::graphics::Texture)s and
+/// [`Canvas`](crate::graphics::Canvas)es) release their GPU memory as soon as the last
+/// clone of them is dropped. If you want to free a resource at a specific point (e.g.
+/// when unloading a level, or before hot-reloading assets), drop all of your handles
+/// to it.
+///
+/// It is safe for resources to outlive the `Context` - for example, if they are stored
+/// somewhere that gets dropped after the game loop exits. Once the `Context` has been
+/// dropped, the underlying OpenGL context no longer exists (and its memory has already
+/// been released), so dropping these resources will not make any further calls to the
+/// graphics API.
 pub struct Context {
-    pub(crate) window: Window,
+    // NOTE: Fields are dropped in declaration order. The graphics resources need to be
+    // released before the device, and the device before the window (which owns the
+    // OpenGL context), so don't reorder these without good reason!
+    pub(crate) graphics: GraphicsContext,
     pub(crate) device: GraphicsDevice,
+    pub(crate) window: Window,
     #[cfg(feature = "audio")]
     pub(crate) audio: AudioDevice,
-    pub(crate) graphics: GraphicsContext,
     pub(crate) input: InputContext,
     pub(crate) time: TimeContext,
 
     pub(crate) running: bool,
     pub(crate) quit_on_escape: bool,
+    pub(crate) frame_delay: Duration,
 }
 
 impl Context {
@@ -31,8 +54,17 @@ impl Context {
         #[cfg(feature = "audio")]
         let audio = AudioDevice::new();
 
+        if settings.gl_version < (3, 2) {
+            return Err(TetraError::PlatformError(format!(
+                "OpenGL {}.{} was requested, but Tetra requires OpenGL 3.2 or higher",
+                settings.gl_version.0, settings.gl_version.1
+            )));
+        }
+
+        fs::set_base_path(settings.assets_dir.clone());
+
         let (window, gl_context, window_width, window_height) = Window::new(settings)?;
-        let mut device = GraphicsDevice::new(gl_context)?;
+        let mut device = GraphicsDevice::new(gl_context, settings.srgb)?;
 
         if settings.debug_info {
             println!("OpenGL Vendor: {}", device.get_vendor());
@@ -41,7 +73,8 @@ impl Context {
             println!("GLSL Version: {}", device.get_shading_language_version());
         }
 
-        let graphics = GraphicsContext::new(&mut device, window_width, window_height)?;
+        let graphics =
+            GraphicsContext::new(&mut device, window_width, window_height, settings.srgb)?;
         let input = InputContext::new();
         let time = TimeContext::new(settings.timestep);
 
@@ -57,6 +90,7 @@ impl Context {
 
             running: false,
             quit_on_escape: settings.quit_on_escape,
+            frame_delay: Duration::from_secs(0),
         })
     }
 
@@ -109,10 +143,7 @@ impl Context {
     {
         let state = &mut init(self)?;
 
-        time::reset(self);
-
-        self.running = true;
-        self.window.set_visible(true);
+        self.start();
 
         let mut output = Ok(());
 
@@ -120,8 +151,7 @@ impl Context {
             output = Err(e);
         }
 
-        self.running = false;
-        self.window.set_visible(false);
+        self.stop();
 
         output
     }
@@ -131,54 +161,171 @@ impl Context {
         S: State<E>,
         E: From<TetraError>,
     {
-        let mut last_time = Instant::now();
-
         while self.running {
-            let curr_time = Instant::now();
-            let diff_time = curr_time - last_time;
-            last_time = curr_time;
+            self.poll_events(state)?;
+            self.tick(state)?;
+            self.present();
+
+            if self.time.paused {
+                // There's no need to render at full speed while nothing is updating.
+                thread::sleep(Duration::from_millis(PAUSED_FRAME_MILLIS));
+            } else {
+                // This provides a sensible FPS limit when running without vsync, and
+                // avoids CPU usage skyrocketing on some systems. If a frame delay has
+                // been set, it replaces this (see `window::set_frame_delay`).
+                thread::sleep(self.frame_delay.max(Duration::from_millis(1)));
+            }
+        }
 
-            // Since we fill the buffer when we create the context, we can cycle it
-            // here and it shouldn't reallocate.
-            self.time.fps_tracker.pop_front();
-            self.time.fps_tracker.push_back(diff_time.as_secs_f64());
+        Ok(())
+    }
 
-            platform::handle_events(self, state)?;
+    /// Prepares the game to be driven manually, showing the window and resetting the timer.
+    ///
+    /// This only needs to be called if you are running your own game loop via
+    /// [`poll_events`](Self::poll_events), [`tick`](Self::tick) and [`present`](Self::present),
+    /// rather than using [`run`](Self::run).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use tetra::{ContextBuilder, State};
+    ///
+    /// struct GameState;
+    ///
+    /// impl State for GameState { }
+    ///
+    /// fn main() -> tetra::Result {
+    ///     let mut ctx = ContextBuilder::new("Hello, world!", 1280, 720).build()?;
+    ///     let mut state = GameState;
+    ///
+    ///     ctx.start();
+    ///
+    ///     while ctx.is_running() {
+    ///         ctx.poll_events(&mut state)?;
+    ///         ctx.tick(&mut state)?;
+    ///         ctx.present();
+    ///     }
+    ///
+    ///     ctx.stop();
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn start(&mut self) {
+        time::reset(self);
 
-            match self.time.tick_rate {
-                Some(tick_rate) => {
-                    self.time.delta_time = tick_rate;
-                    self.time.accumulator = (self.time.accumulator + diff_time).min(tick_rate * 8);
+        self.running = true;
+        self.window.set_visible(true);
+    }
 
-                    while self.time.accumulator >= tick_rate {
-                        state.update(self)?;
-                        input::clear(self);
+    /// Stops the game, hiding the window.
+    ///
+    /// This is the counterpart to [`start`](Self::start). To request that the game quits
+    /// from inside your game logic, use [`window::quit`](crate::window::quit) instead.
+    pub fn stop(&mut self) {
+        self.running = false;
+        self.window.set_visible(false);
+    }
 
-                        self.time.accumulator -= tick_rate;
-                    }
+    /// Returns whether or not the game is currently running.
+    ///
+    /// This will become `false` if the game is quit (e.g. by closing the window, or by
+    /// calling [`window::quit`](crate::window::quit)), and can be used as the condition
+    /// for a manually driven game loop.
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
 
-                    self.time.delta_time = diff_time;
-                }
+    /// Processes any events that have been received from the platform since the last call,
+    /// passing them to the [`State`]'s [`event`](State::event) method and updating the
+    /// input state.
+    ///
+    /// # Errors
+    ///
+    /// If the [`State`] returns an error from [`event`](State::event), processing will stop
+    /// and this method will return the error.
+    pub fn poll_events<S, E>(&mut self, state: &mut S) -> result::Result<(), E>
+    where
+        S: State<E>,
+        E: From<TetraError>,
+    {
+        platform::handle_events(self, state)
+    }
 
-                None => {
-                    self.time.delta_time = diff_time;
+    /// Advances the game by a single frame, running as many updates as are needed to catch
+    /// up with the amount of time that has passed since the last tick, and then drawing.
+    ///
+    /// The configured [`Timestep`] is respected, so with a fixed timestep this may run
+    /// [`update`](State::update) zero or more times. If the game loop is paused (see
+    /// [`time::set_paused`]), only [`draw`](State::draw) will be called.
+    ///
+    /// This does not poll for events or present the frame to the screen - use
+    /// [`poll_events`](Self::poll_events) and [`present`](Self::present) for that.
+    ///
+    /// # Errors
+    ///
+    /// If the [`State`] returns an error from [`update`](State::update) or
+    /// [`draw`](State::draw), this method will return the error.
+    pub fn tick<S, E>(&mut self, state: &mut S) -> result::Result<(), E>
+    where
+        S: State<E>,
+        E: From<TetraError>,
+    {
+        let curr_time = Instant::now();
+        let diff_time = curr_time - self.time.last_time;
+        self.time.last_time = curr_time;
+
+        // Since we fill the buffer when we create the context, we can cycle it
+        // here and it shouldn't reallocate.
+        self.time.fps_tracker.pop_front();
+        self.time.fps_tracker.push_back(diff_time.as_secs_f64());
+
+        if self.time.paused {
+            self.time.delta_time = Duration::from_secs(0);
 
+            state.draw(self)?;
+
+            // Input that arrives while paused should not be seen by the next update.
+            input::clear(self);
+
+            return Ok(());
+        }
+
+        match self.time.tick_rate {
+            Some(tick_rate) => {
+                self.time.delta_time = tick_rate;
+                self.time.accumulator = (self.time.accumulator + diff_time).min(tick_rate * 8);
+
+                while self.time.accumulator >= tick_rate {
                     state.update(self)?;
                     input::clear(self);
+
+                    self.time.accumulator -= tick_rate;
                 }
-            }
 
-            state.draw(self)?;
+                self.time.delta_time = diff_time;
+            }
 
-            graphics::present(self);
+            None => {
+                self.time.delta_time = diff_time;
 
-            // This provides a sensible FPS limit when running without vsync, and
-            // avoids CPU usage skyrocketing on some systems.
-            thread::sleep(Duration::from_millis(1));
+                state.update(self)?;
+                input::clear(self);
+            }
         }
 
+        state.draw(self)?;
+
         Ok(())
     }
+
+    /// Presents the current frame to the screen.
+    ///
+    /// This is a shortcut for [`graphics::present`].
+    pub fn present(&mut self) {
+        graphics::present(self);
+    }
 }
 
 /// Settings that can be configured when starting up a game.
@@ -202,6 +349,8 @@ pub struct ContextBuilder {
     pub(crate) title: String,
     pub(crate) window_width: i32,
     pub(crate) window_height: i32,
+    pub(crate) window_position: Option<(i32, i32)>,
+    pub(crate) window_monitor: i32,
     pub(crate) vsync: bool,
     pub(crate) timestep: Timestep,
     pub(crate) fullscreen: bool,
@@ -209,7 +358,13 @@ pub struct ContextBuilder {
     pub(crate) minimized: bool,
     pub(crate) resizable: bool,
     pub(crate) borderless: bool,
+    pub(crate) always_on_top: bool,
     pub(crate) multisampling: u8,
+    pub(crate) gl_version: (u8, u8),
+    pub(crate) gl_profile: GlProfile,
+    pub(crate) srgb: bool,
+    pub(crate) depth_buffer: bool,
+    pub(crate) stencil_buffer: bool,
     pub(crate) high_dpi: bool,
     pub(crate) screen_saver_enabled: bool,
     pub(crate) key_repeat: bool,
@@ -218,6 +373,7 @@ pub struct ContextBuilder {
     pub(crate) relative_mouse_mode: bool,
     pub(crate) quit_on_escape: bool,
     pub(crate) debug_info: bool,
+    pub(crate) assets_dir: Option<PathBuf>,
 }
 
 impl ContextBuilder {
@@ -311,11 +467,52 @@ impl ContextBuilder {
         self
     }
 
+    /// Sets the position that the window should be opened at, in screen coordinates.
+    ///
+    /// When multiple monitors are connected, the coordinates are relative to the
+    /// top left of the primary monitor, so other monitors may have negative coordinates.
+    /// This can be combined with [`window::get_position`](crate::window::get_position) to
+    /// restore the window to where it was in a previous session.
+    ///
+    /// Defaults to the window being centered on the first monitor.
+    pub fn position(&mut self, x: i32, y: i32) -> &mut ContextBuilder {
+        self.window_position = Some((x, y));
+        self
+    }
+
+    /// Sets the window to be centered on the specified monitor when it opens.
+    ///
+    /// Monitor indexes match those used by the functions in the [`window`](crate::window)
+    /// module (e.g. [`window::get_monitor_name`](crate::window::get_monitor_name)).
+    ///
+    /// Defaults to the window being centered on the first monitor.
+    pub fn centered(&mut self, monitor_index: i32) -> &mut ContextBuilder {
+        self.window_position = None;
+        self.window_monitor = monitor_index;
+        self
+    }
+
+    /// Sets whether or not the window should always be displayed above other windows.
+    ///
+    /// This can be useful for overlays and desktop widgets. Note that not all platforms
+    /// support this setting, and that it cannot currently be changed after the window
+    /// has been created.
+    ///
+    /// Defaults to `false`.
+    pub fn always_on_top(&mut self, always_on_top: bool) -> &mut ContextBuilder {
+        self.always_on_top = always_on_top;
+        self
+    }
+
     /// Sets the number of samples that should be used for multisample anti-aliasing.
     ///
     /// The number of samples that can be used varies between graphics cards - `2`, `4` and `8` are reasonably
     /// well supported. Setting the number of samples to `0` will disable multisampling.
     ///
+    /// If the requested number of samples is not supported, Tetra will fall back to
+    /// progressively lower values (halving each time), and will disable multisampling
+    /// entirely if none of them can be used.
+    ///
     /// Note that this setting only applies to the main backbuffer - multisampled canvases can
     /// be created via [`Canvas::multisampled`](crate::graphics::Canvas::multisampled).
     ///
@@ -325,6 +522,70 @@ impl ContextBuilder {
         self
     }
 
+    /// Sets the version of OpenGL that should be requested when creating the graphics context.
+    ///
+    /// Tetra requires OpenGL 3.2 or higher - requesting an older version will cause an
+    /// error when the context is built. Requesting a newer version may be useful if you
+    /// want to use more advanced features in your shaders, but will prevent your game from
+    /// running on older hardware.
+    ///
+    /// Defaults to `(3, 2)`.
+    pub fn gl_version(&mut self, major: u8, minor: u8) -> &mut ContextBuilder {
+        self.gl_version = (major, minor);
+        self
+    }
+
+    /// Sets which OpenGL profile should be requested when creating the graphics context.
+    ///
+    /// Note that some platforms (most notably MacOS) do not support the compatibility
+    /// profile for OpenGL 3.2 and above.
+    ///
+    /// Defaults to [`GlProfile::Core`].
+    pub fn gl_profile(&mut self, gl_profile: GlProfile) -> &mut ContextBuilder {
+        self.gl_profile = gl_profile;
+        self
+    }
+
+    /// Sets whether or not rendering should be gamma-correct.
+    ///
+    /// When this is enabled, an sRGB-capable backbuffer will be requested, and newly created
+    /// textures and canvases will default to [`TextureFormat::Srgba8`](crate::graphics::TextureFormat::Srgba8).
+    /// This means that colors will be converted to linear space when they are sampled, blending
+    /// will take place in linear space, and the results will be converted back to sRGB when they
+    /// are written to the screen.
+    ///
+    /// Note that this also means that vertex colors, clear colors and the output of your shaders
+    /// will be treated as linear values.
+    ///
+    /// Defaults to `false`.
+    pub fn srgb(&mut self, srgb: bool) -> &mut ContextBuilder {
+        self.srgb = srgb;
+        self
+    }
+
+    /// Sets whether or not a 24-bit depth buffer should be requested for the window.
+    ///
+    /// Tetra's built-in rendering does not currently write to the depth buffer, but its
+    /// contents can be read back via [`graphics::read_depth`](crate::graphics::read_depth).
+    ///
+    /// Defaults to `false`.
+    pub fn depth_buffer(&mut self, depth_buffer: bool) -> &mut ContextBuilder {
+        self.depth_buffer = depth_buffer;
+        self
+    }
+
+    /// Sets whether or not an 8-bit stencil buffer should be requested for the window.
+    ///
+    /// The stencil buffer can be written to and tested against via
+    /// [`graphics::set_stencil_state`](crate::graphics::set_stencil_state), and its contents
+    /// can be read back via [`graphics::read_stencil`](crate::graphics::read_stencil).
+    ///
+    /// Defaults to `false`.
+    pub fn stencil_buffer(&mut self, stencil_buffer: bool) -> &mut ContextBuilder {
+        self.stencil_buffer = stencil_buffer;
+        self
+    }
+
     /// Sets whether or not the window should use a high-DPI backbuffer, on platforms
     /// that support it (e.g. MacOS with a retina display).
     ///
@@ -343,6 +604,13 @@ impl ContextBuilder {
     /// * On Mac, set [`NSHighResolutionCapable`](https://developer.apple.com/documentation/bundleresources/information_property_list/nshighresolutioncapable)
     ///   to `true` in your Info.plist. This is the default on Catalina and higher.
     ///
+    /// Enabling this does not change the co-ordinate system that your game uses - the window size,
+    /// mouse position and rendering will all still be measured in logical pixels, and the
+    /// additional resolution will be used to render more crisply. Use
+    /// [`window::get_dpi_scale`](crate::window::get_dpi_scale) and
+    /// [`window::get_physical_size`](crate::window::get_physical_size) if you need to know the
+    /// size of the backbuffer in physical pixels.
+    ///
     /// Defaults to `false`.
     pub fn high_dpi(&mut self, high_dpi: bool) -> &mut ContextBuilder {
         self.high_dpi = high_dpi;
@@ -419,6 +687,40 @@ impl ContextBuilder {
         self
     }
 
+    /// Sets the directory that relative asset paths will be resolved against.
+    ///
+    /// By default, relative paths are resolved against the current working directory,
+    /// which depends on how the game was launched (e.g. via `cargo run`, an IDE, or by
+    /// double-clicking the executable). Setting this makes loading consistent regardless.
+    /// Absolute paths are not affected.
+    ///
+    /// This applies to all of Tetra's file-based loading functions, including ones that do not
+    /// take a [`Context`] (such as [`Sound::new`](crate::audio::Sound::new)). If a custom
+    /// [`AssetSource`](crate::asset::AssetSource) is set, the resolved paths will be passed to it.
+    ///
+    /// # Examples
+    ///
+    /// Loading assets from a directory next to the executable:
+    ///
+    /// ```no_run
+    /// # use tetra::ContextBuilder;
+    /// # fn main() -> tetra::Result {
+    /// let exe = std::env::current_exe().unwrap();
+    ///
+    /// let ctx = ContextBuilder::new("Hello, world!", 1280, 720)
+    ///     .assets_dir(exe.parent().unwrap().join("assets"))
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn assets_dir<P>(&mut self, path: P) -> &mut ContextBuilder
+    where
+        P: Into<PathBuf>,
+    {
+        self.assets_dir = Some(path.into());
+        self
+    }
+
     /// Builds the context.
     ///
     /// # Errors
@@ -429,12 +731,35 @@ impl ContextBuilder {
     }
 }
 
+/// The different OpenGL profiles that can be requested when creating a [`Context`].
+///
+/// # Serde
+///
+/// Serialization and deserialization of this type (via [Serde](https://serde.rs/))
+/// can be enabled via the `serde_support` feature.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde_support",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum GlProfile {
+    /// The core profile, in which deprecated functionality is not available.
+    Core,
+
+    /// The compatibility profile, in which deprecated functionality is still available.
+    ///
+    /// This is mainly useful for interop with other libraries that rely on legacy OpenGL.
+    Compatibility,
+}
+
 impl Default for ContextBuilder {
     fn default() -> ContextBuilder {
         ContextBuilder {
             title: "Tetra".into(),
             window_width: 1280,
             window_height: 720,
+            window_position: None,
+            window_monitor: 0,
             vsync: true,
             timestep: Timestep::Fixed(60.0),
             fullscreen: false,
@@ -442,7 +767,13 @@ impl Default for ContextBuilder {
             minimized: false,
             resizable: false,
             borderless: false,
+            always_on_top: false,
             multisampling: 0,
+            gl_version: (3, 2),
+            gl_profile: GlProfile::Core,
+            srgb: false,
+            depth_buffer: false,
+            stencil_buffer: false,
             high_dpi: false,
             screen_saver_enabled: false,
             key_repeat: false,
@@ -451,6 +782,7 @@ impl Default for ContextBuilder {
             relative_mouse_mode: false,
             quit_on_escape: false,
             debug_info: false,
+            assets_dir: None,
         }
     }
 }
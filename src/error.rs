@@ -10,6 +10,8 @@ use image::ImageError;
 
 use lyon_tessellation::TessellationError;
 
+use crate::graphics::ShaderStage;
+
 #[cfg(feature = "audio")]
 use rodio::decoder::DecoderError;
 
@@ -43,9 +45,22 @@ pub enum TetraError {
     /// Returned when a texture's data is invalid.
     InvalidTexture(ImageError),
 
-    /// Returned when a shader fails to compile.
+    /// Returned when a shader's source code is invalid, before it is passed to the
+    /// graphics hardware (e.g. due to a malformed `#include` directive).
     InvalidShader(String),
 
+    /// Returned when a shader fails to compile or link.
+    ShaderCompilation {
+        /// The stage of the shader pipeline that failed.
+        stage: ShaderStage,
+
+        /// The log output by the graphics driver.
+        ///
+        /// Where possible, each message will be followed by the line of source code
+        /// that it refers to.
+        log: String,
+    },
+
     /// Returned when a font could not be read.
     InvalidFont,
 
@@ -85,6 +100,10 @@ impl Display for TetraError {
             TetraError::InvalidColor => write!(f, "Invalid color"),
             TetraError::InvalidTexture(_) => write!(f, "Invalid texture data"),
             TetraError::InvalidShader(_) => write!(f, "Invalid shader source"),
+            TetraError::ShaderCompilation { stage, log } => match stage {
+                ShaderStage::Linking => write!(f, "Failed to link shader program:\n{}", log),
+                _ => write!(f, "Failed to compile {} shader:\n{}", stage, log),
+            },
             TetraError::InvalidFont => write!(f, "Invalid font data"),
             #[cfg(feature = "audio")]
             TetraError::InvalidSound(_) => write!(f, "Invalid sound data"),
@@ -110,6 +129,7 @@ impl Error for TetraError {
             TetraError::InvalidColor => None,
             TetraError::InvalidTexture(reason) => Some(reason),
             TetraError::InvalidShader(_) => None,
+            TetraError::ShaderCompilation { .. } => None,
             TetraError::InvalidFont => None,
             #[cfg(feature = "audio")]
             TetraError::InvalidSound(reason) => Some(reason),
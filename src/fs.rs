@@ -1,25 +1,66 @@
 //! Internal utilities for reading files while retaining context about file paths.
+//!
+//! All asset loading goes through these functions, so that relative paths can be resolved
+//! against the configured assets directory, and so that the files can be read from
+//! the [`AssetSource`](crate::asset::AssetSource) that the user has configured.
 
 // To avoid warnings in the rare case where all features are disabled at the same time:
 #![allow(unused)]
 
-use std::fs;
-use std::path::Path;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
 
-use image::{self, DynamicImage, ImageError};
+use image::{self, DynamicImage, ImageFormat};
 
+use crate::asset::{AssetSource, FileSystem};
 use crate::error::{Result, TetraError};
 
+/// The source that assets are currently being loaded from. If this is `None`, they will
+/// be loaded from the filesystem.
+static SOURCE: RwLock<Option<Arc<dyn AssetSource>>> = RwLock::new(None);
+
+/// The directory that relative asset paths are resolved against. If this is `None`, they
+/// will be resolved against the current working directory.
+static BASE_PATH: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+pub(crate) fn set_source(source: Option<Arc<dyn AssetSource>>) {
+    *SOURCE.write().unwrap_or_else(|e| e.into_inner()) = source;
+}
+
+pub(crate) fn set_base_path(path: Option<PathBuf>) {
+    *BASE_PATH.write().unwrap_or_else(|e| e.into_inner()) = path;
+}
+
+fn resolve(path: &Path) -> PathBuf {
+    match &*BASE_PATH.read().unwrap_or_else(|e| e.into_inner()) {
+        // Joining an absolute path replaces the base, so those are left as they are.
+        Some(base) => base.join(path),
+        None => path.to_owned(),
+    }
+}
+
+fn open(path: &Path) -> io::Result<Box<dyn Read>> {
+    let source = SOURCE.read().unwrap_or_else(|e| e.into_inner()).clone();
+
+    match source {
+        Some(source) => source.open(path),
+        None => FileSystem.open(path),
+    }
+}
+
 pub(crate) fn read<P>(path: P) -> Result<Vec<u8>>
 where
     P: AsRef<Path>,
 {
-    let path_ref = path.as_ref();
+    let path = resolve(path.as_ref());
+    let mut data = Vec::new();
 
-    fs::read(path_ref).map_err(|e| TetraError::FailedToLoadAsset {
-        reason: e,
-        path: path_ref.to_owned(),
-    })
+    open(&path)
+        .and_then(|mut reader| reader.read_to_end(&mut data))
+        .map_err(|e| TetraError::FailedToLoadAsset { reason: e, path })?;
+
+    Ok(data)
 }
 
 pub(crate) fn read_to_image<P>(path: P) -> Result<DynamicImage>
@@ -28,23 +69,71 @@ where
 {
     let path_ref = path.as_ref();
 
-    image::open(path_ref).map_err(|e| match e {
-        ImageError::IoError(inner) => TetraError::FailedToLoadAsset {
-            reason: inner,
-            path: path_ref.to_owned(),
-        },
-        _ => TetraError::InvalidTexture(e),
-    })
+    let format = ImageFormat::from_path(path_ref).map_err(TetraError::InvalidTexture)?;
+    let data = read(path_ref)?;
+
+    image::load_from_memory_with_format(&data, format).map_err(TetraError::InvalidTexture)
 }
 
 pub(crate) fn read_to_string<P>(path: P) -> Result<String>
 where
     P: AsRef<Path>,
 {
-    let path_ref = path.as_ref();
+    let path = resolve(path.as_ref());
+    let mut data = String::new();
+
+    open(&path)
+        .and_then(|mut reader| reader.read_to_string(&mut data))
+        .map_err(|e| TetraError::FailedToLoadAsset { reason: e, path })?;
+
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, Cursor, Read};
+    use std::path::{Path, PathBuf};
+    use std::sync::Arc;
+
+    use super::{read, read_to_string, resolve, set_base_path, set_source};
+    use crate::asset::{AssetSource, FileSystem};
+    use crate::TetraError;
+
+    struct MemorySource;
+
+    impl AssetSource for MemorySource {
+        fn open(&self, path: &Path) -> io::Result<Box<dyn Read>> {
+            if path == Path::new("memory/hello.txt") {
+                Ok(Box::new(Cursor::new(b"hello".to_vec())))
+            } else {
+                FileSystem.open(path)
+            }
+        }
+    }
+
+    #[test]
+    fn assets_are_read_from_configured_location() {
+        set_source(Some(Arc::new(MemorySource)));
+
+        let text = read_to_string("memory/hello.txt");
+        let missing = read("memory/missing.txt");
+
+        set_source(None);
+
+        assert_eq!("hello", text.unwrap());
+        assert!(matches!(missing, Err(TetraError::FailedToLoadAsset { .. })));
+        assert!(read("memory/hello.txt").is_err());
+
+        // This is tested here, rather than in its own test, because the base path and
+        // the source are global, and tests run in parallel.
+        set_base_path(Some(PathBuf::from("assets")));
+
+        let relative = resolve(Path::new("player.png"));
+        let absolute = resolve(Path::new("/player.png"));
+
+        set_base_path(None);
 
-    fs::read_to_string(path_ref).map_err(|e| TetraError::FailedToLoadAsset {
-        reason: e,
-        path: path_ref.to_owned(),
-    })
+        assert_eq!(Path::new("assets/player.png"), relative);
+        assert_eq!(Path::new("/player.png"), absolute);
+    }
 }
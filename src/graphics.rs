@@ -9,29 +9,40 @@ pub mod animation;
 mod camera;
 mod canvas;
 mod color;
+pub mod debug;
 mod drawparams;
+mod fence;
 pub mod mesh;
+mod postprocess;
 mod rectangle;
+mod sampler;
 pub mod scaling;
 mod shader;
 pub mod text;
 mod texture;
+mod texture_3d;
+mod texture_array;
 
 pub use camera::*;
 pub use canvas::*;
 pub use color::*;
 pub use drawparams::*;
+pub use fence::*;
+pub use postprocess::*;
 pub use rectangle::*;
+pub use sampler::*;
 pub use shader::*;
 pub use texture::*;
+pub use texture_3d::*;
+pub use texture_array::*;
 
 use crate::error::Result;
-use crate::math::{FrustumPlanes, Mat4, Vec2};
+use crate::math::{FrustumPlanes, Mat4, Vec2, Vec3};
 use crate::platform::{GraphicsDevice, RawFramebuffer, RawIndexBuffer, RawVertexBuffer};
 use crate::window;
 use crate::Context;
 
-use self::mesh::{BufferUsage, Vertex, VertexWinding};
+use self::mesh::{BufferUsage, CullMode, Vertex, VertexWinding};
 
 const MAX_SPRITES: usize = 2048;
 const MAX_VERTICES: usize = MAX_SPRITES * 4; // Cannot be greater than 32767!
@@ -44,13 +55,13 @@ pub(crate) enum ActiveTexture {
     User(Texture),
 }
 
-#[derive(PartialEq)]
+#[derive(Clone, PartialEq)]
 pub(crate) enum ActiveShader {
     Default,
     User(Shader),
 }
 
-#[derive(PartialEq)]
+#[derive(Clone, PartialEq)]
 pub(crate) enum ActiveCanvas {
     Window,
     User(Canvas),
@@ -62,7 +73,10 @@ pub(crate) struct GraphicsContext {
 
     texture: ActiveTexture,
     default_texture: Texture,
+    white_texture: Texture,
     default_filter_mode: FilterMode,
+    default_texture_format: TextureFormat,
+    sampler: Option<Sampler>,
 
     shader: ActiveShader,
     default_shader: Shader,
@@ -71,6 +85,7 @@ pub(crate) struct GraphicsContext {
     resolve_framebuffer: Option<RawFramebuffer>,
 
     winding: VertexWinding,
+    projection_mode: ProjectionMode,
     projection_matrix: Mat4<f32>,
     transform_matrix: Mat4<f32>,
 
@@ -78,6 +93,10 @@ pub(crate) struct GraphicsContext {
     element_count: usize,
 
     blend_mode: BlendMode,
+    color_blend: ColorBlend,
+    stencil_state: StencilState,
+
+    debug: debug::DebugContext,
 }
 
 impl GraphicsContext {
@@ -85,6 +104,7 @@ impl GraphicsContext {
         device: &mut GraphicsDevice,
         window_width: i32,
         window_height: i32,
+        srgb: bool,
     ) -> Result<GraphicsContext> {
         let vertex_buffer = device.new_vertex_buffer(MAX_VERTICES, 8, BufferUsage::Dynamic)?;
         let index_buffer = device.new_index_buffer(MAX_INDICES, BufferUsage::Static)?;
@@ -99,11 +119,34 @@ impl GraphicsContext {
 
         device.set_index_buffer_data(&index_buffer, &indices, 0);
 
-        let default_texture =
-            Texture::with_device(device, 1, 1, &[255, 255, 255, 255], FilterMode::Nearest)?;
+        let default_texture = Texture::with_device(
+            device,
+            1,
+            1,
+            &[255, 255, 255, 255],
+            FilterMode::Nearest,
+            TextureFormat::Rgba8,
+        )?;
+
+        // This is kept separate from the default texture, so that if the user modifies
+        // the texture returned by `white_texture`, Tetra's own rendering isn't affected.
+        let white_texture = Texture::with_device(
+            device,
+            1,
+            1,
+            &[255, 255, 255, 255],
+            FilterMode::Nearest,
+            TextureFormat::Rgba8,
+        )?;
 
         let default_filter_mode = FilterMode::Nearest;
 
+        let default_texture_format = if srgb {
+            TextureFormat::Srgba8
+        } else {
+            TextureFormat::Rgba8
+        };
+
         let default_shader = Shader::with_device(
             device,
             shader::DEFAULT_VERTEX_SHADER,
@@ -116,7 +159,10 @@ impl GraphicsContext {
 
             texture: ActiveTexture::Default,
             default_texture,
+            white_texture,
             default_filter_mode,
+            default_texture_format,
+            sampler: None,
 
             shader: ActiveShader::Default,
             default_shader,
@@ -125,6 +171,7 @@ impl GraphicsContext {
             resolve_framebuffer: None,
 
             winding: VertexWinding::CounterClockwise,
+            projection_mode: ProjectionMode::TopLeft,
             projection_matrix: ortho(window_width as f32, window_height as f32, false),
             transform_matrix: Mat4::identity(),
 
@@ -132,15 +179,75 @@ impl GraphicsContext {
             element_count: 0,
 
             blend_mode: BlendMode::default(),
+            color_blend: ColorBlend::default(),
+            stencil_state: StencilState::disabled(),
+
+            debug: debug::DebugContext::new(),
         })
     }
 }
 
 /// Clears the screen (or a canvas, if one is enabled) to the specified color.
+///
+/// Tetra does not clear the screen between frames, so you are in control of when (and if)
+/// that takes place. If you want the screen to be filled with a background color every frame,
+/// call this at the start of your [`State::draw`](crate::State::draw) implementation.
+///
+/// Tetra will only clear on its own in the following cases:
+///
+/// * When a [`Canvas`] is created, it is cleared to transparent black.
+/// * [`apply_shader_to_canvas`] (and therefore [`PostProcess::apply`]) clears the destination
+///   canvas to transparent black before drawing to it.
+///
+/// Note, however, that the contents of the screen are not guaranteed to be preserved
+/// after [`present`] is called - depending on the platform and graphics driver, the
+/// next frame may start with the previous frame's image, an older frame, or garbage.
+/// If you want to build up an image over multiple frames (e.g. for trail effects),
+/// draw to a [`Canvas`] that you do not clear, and then draw that canvas to the
+/// screen each frame.
 pub fn clear(ctx: &mut Context, color: Color) {
+    invalidate_canvas_alpha_mask(ctx);
     ctx.device.clear(color.r, color.g, color.b, color.a);
 }
 
+/// Queues a textured quad to be drawn, as part of the current batch.
+///
+/// This is the same operation that is used internally to draw [`Texture`]s and
+/// [`Text`](text::Text), and it can be used to build your own rendering on top of Tetra's
+/// batching (e.g. a custom tilemap or particle renderer).
+///
+/// `position` is the rectangle that the quad should cover, before `params` are applied. `uv`
+/// is the region of the texture that should be drawn onto it, in normalized texture
+/// co-ordinates (from `0.0` to `1.0`) - these can be found via [`TextQuad`](text::TextQuad)
+/// or calculated from a region in pixels by dividing by the texture's size.
+///
+/// Quads that use the same texture will be batched together. If the texture is different
+/// from the one used by the previous draw, this will trigger a [`flush`] to the graphics
+/// hardware.
+pub fn draw_quad<P>(
+    ctx: &mut Context,
+    texture: &Texture,
+    position: Rectangle,
+    uv: Rectangle,
+    params: P,
+) where
+    P: Into<DrawParams>,
+{
+    set_texture(ctx, texture);
+    push_quad(
+        ctx,
+        position.x,
+        position.y,
+        position.right(),
+        position.bottom(),
+        uv.x,
+        uv.y,
+        uv.right(),
+        uv.bottom(),
+        &params.into(),
+    );
+}
+
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn push_quad(
     ctx: &mut Context,
@@ -164,10 +271,18 @@ pub(crate) fn push_quad(
         flush(ctx);
     }
 
-    let mut fx = (x1 - params.origin.x) * params.scale.x;
-    let mut fy = (y1 - params.origin.y) * params.scale.y;
-    let mut fx2 = (x2 - params.origin.x) * params.scale.x;
-    let mut fy2 = (y2 - params.origin.y) * params.scale.y;
+    // The color blend is applied via a uniform, so it can't change mid-batch.
+    if params.color_blend != ctx.graphics.color_blend {
+        flush(ctx);
+        ctx.graphics.color_blend = params.color_blend;
+    }
+
+    let origin = params.resolve_origin(Rectangle::new(x1, y1, x2 - x1, y2 - y1));
+
+    let mut fx = (x1 - origin.x) * params.scale.x;
+    let mut fy = (y1 - origin.y) * params.scale.y;
+    let mut fx2 = (x2 - origin.x) * params.scale.x;
+    let mut fy2 = (y2 - origin.y) * params.scale.y;
 
     if fx2 < fx {
         std::mem::swap(&mut fx, &mut fx2);
@@ -227,6 +342,57 @@ pub(crate) fn set_texture_ex(ctx: &mut Context, texture: ActiveTexture) {
     }
 }
 
+/// Sets the sampler that will be used for future drawing operations.
+///
+/// While a sampler is active, it overrides the [filter mode](Texture::set_filter_mode) and
+/// [wrap mode](Texture::set_wrap_mode) of any textures, canvases, text or meshes that are
+/// drawn. The textures themselves are not modified, so the same texture can be drawn with
+/// different samplers within a single frame.
+///
+/// Textures that are passed to a shader via [`Shader::set_uniform`] are not affected.
+///
+/// If the sampler is different from the one that is currently in use, this will trigger a
+/// [`flush`] to the graphics hardware.
+pub fn set_sampler(ctx: &mut Context, sampler: Sampler) {
+    set_sampler_ex(ctx, Some(sampler));
+}
+
+/// Resets the sampler, so that textures will be drawn using their own settings.
+pub fn reset_sampler(ctx: &mut Context) {
+    set_sampler_ex(ctx, None);
+}
+
+fn set_sampler_ex(ctx: &mut Context, sampler: Option<Sampler>) {
+    if sampler != ctx.graphics.sampler {
+        flush(ctx);
+        ctx.graphics.sampler = sampler;
+    }
+}
+
+/// Applies the active sampler (if there is one) to a texture, before it is drawn.
+pub(crate) fn apply_sampler(
+    device: &mut GraphicsDevice,
+    sampler: Option<Sampler>,
+    texture: &Texture,
+) {
+    if let Some(sampler) = sampler {
+        device.set_texture_filter_mode(&texture.data.handle, sampler.filter_mode);
+        device.set_texture_wrap_mode(&texture.data.handle, sampler.wrap_mode);
+    }
+}
+
+/// Restores a texture's own settings, after it has been drawn with the active sampler.
+pub(crate) fn restore_sampler(
+    device: &mut GraphicsDevice,
+    sampler: Option<Sampler>,
+    texture: &Texture,
+) {
+    if sampler.is_some() {
+        device.set_texture_filter_mode(&texture.data.handle, texture.filter_mode());
+        device.set_texture_wrap_mode(&texture.data.handle, texture.wrap_mode());
+    }
+}
+
 /// Sets the blend mode used for future drawing operations.
 ///
 /// The blend mode will be used to determine how drawn content will be blended
@@ -258,6 +424,28 @@ pub fn reset_shader(ctx: &mut Context) {
     set_shader_ex(ctx, ActiveShader::Default);
 }
 
+/// Runs the given closure with the specified shader active, and then restores whichever
+/// shader was active beforehand.
+///
+/// This is a more convenient (and less error-prone) alternative to calling [`set_shader`]
+/// and then manually resetting it afterwards, and is useful for applying a shader to a
+/// specific set of draw calls. Any value returned from the closure will be passed through.
+///
+/// As with [`set_shader`], a [`flush`] will be triggered when the shader changes, both
+/// before and after the closure is run.
+pub fn with_shader<F, R>(ctx: &mut Context, shader: &Shader, f: F) -> R
+where
+    F: FnOnce(&mut Context) -> R,
+{
+    let previous = ctx.graphics.shader.clone();
+
+    set_shader_ex(ctx, ActiveShader::User(shader.clone()));
+    let result = f(ctx);
+    set_shader_ex(ctx, previous);
+
+    result
+}
+
 pub(crate) fn set_shader_ex(ctx: &mut Context, shader: ActiveShader) {
     if shader != ctx.graphics.shader {
         flush(ctx);
@@ -288,51 +476,74 @@ pub(crate) fn set_canvas_ex(ctx: &mut Context, canvas: ActiveCanvas) {
         match &ctx.graphics.canvas {
             ActiveCanvas::Window => {
                 let (width, height) = window::get_size(ctx);
+                let (pixel_width, pixel_height) = window::get_physical_size(ctx);
 
-                ctx.graphics.projection_matrix = ortho(width as f32, height as f32, false);
+                ctx.graphics.projection_matrix =
+                    projection(ctx.graphics.projection_mode, width, height, false);
 
                 ctx.device.bind_framebuffer(None);
-                ctx.device.front_face(ctx.graphics.winding);
-                ctx.device.viewport(0, 0, width, height);
+                ctx.device
+                    .front_face(front_face_winding(ctx, ctx.graphics.winding));
+                ctx.device.viewport(0, 0, pixel_width, pixel_height);
             }
             ActiveCanvas::User(r) => {
                 let (width, height) = r.size();
 
-                ctx.graphics.projection_matrix = ortho(width as f32, height as f32, true);
+                r.texture.data.invalidate_alpha_mask();
+
+                ctx.graphics.projection_matrix =
+                    projection(ctx.graphics.projection_mode, width, height, true);
 
                 ctx.device.bind_framebuffer(Some(&r.framebuffer));
-                ctx.device.front_face(ctx.graphics.winding.flipped());
+                ctx.device
+                    .front_face(front_face_winding(ctx, ctx.graphics.winding));
                 ctx.device.viewport(0, 0, width, height);
             }
         }
     }
 }
 
+/// Discards the cached alpha mask of the active canvas (if there is one), as its contents
+/// are about to change.
+pub(crate) fn invalidate_canvas_alpha_mask(ctx: &Context) {
+    if let ActiveCanvas::User(c) = &ctx.graphics.canvas {
+        c.texture.data.invalidate_alpha_mask();
+    }
+}
+
 fn resolve_canvas(ctx: &mut Context) {
     if let ActiveCanvas::User(c) = &ctx.graphics.canvas {
-        if c.multisample.is_some() {
-            // This is lazily initialized, to avoid overhead for people not using MSAA.
-            if ctx.graphics.resolve_framebuffer.is_none() {
-                ctx.graphics.resolve_framebuffer =
-                    Some(ctx.device.new_framebuffer().expect("TODO"));
-            }
+        c.texture.data.invalidate_alpha_mask();
 
-            let resolve_framebuffer = ctx.graphics.resolve_framebuffer.as_ref().unwrap();
+        if c.multisample.is_some() {
+            let c = c.clone();
+            blit_to_texture(ctx, &c.framebuffer, &c.texture);
+        }
+    }
+}
 
-            ctx.device.attach_texture_to_framebuffer(
-                &resolve_framebuffer,
-                &c.texture.data.handle,
-                false,
-            );
+/// Copies the contents of a framebuffer into a texture, resolving any multisampling.
+///
+/// The caller is responsible for rebinding the active framebuffer afterwards, if needed.
+pub(crate) fn blit_to_texture(ctx: &mut Context, framebuffer: &RawFramebuffer, target: &Texture) {
+    target.data.invalidate_alpha_mask();
 
-            ctx.device.blit_framebuffer(
-                &c.framebuffer,
-                &resolve_framebuffer,
-                c.width(),
-                c.height(),
-            );
-        }
+    // This is lazily initialized, to avoid overhead for people not using MSAA.
+    if ctx.graphics.resolve_framebuffer.is_none() {
+        ctx.graphics.resolve_framebuffer = Some(ctx.device.new_framebuffer().expect("TODO"));
     }
+
+    let resolve_framebuffer = ctx.graphics.resolve_framebuffer.as_ref().unwrap();
+
+    ctx.device
+        .attach_texture_to_framebuffer(&resolve_framebuffer, &target.data.handle, false);
+
+    ctx.device.blit_framebuffer(
+        framebuffer,
+        &resolve_framebuffer,
+        target.width(),
+        target.height(),
+    );
 }
 
 /// Sends queued data to the graphics hardware.
@@ -341,6 +552,11 @@ fn resolve_canvas(ctx: &mut Context) {
 /// automatically flush when necessary. Try to keep flushing to a minimum,
 /// as this will reduce the number of draw calls made to the
 /// graphics device.
+///
+/// However, calling this explicitly can be useful if you need to control the boundaries
+/// of a batch - for example, before changing graphics state outside of Tetra, or before
+/// reading back the contents of a canvas. If nothing has been queued since the last
+/// flush, this function does nothing.
 pub fn flush(ctx: &mut Context) {
     if !ctx.graphics.vertex_data.is_empty() {
         let texture = match &ctx.graphics.texture {
@@ -359,16 +575,13 @@ pub fn flush(ctx: &mut Context) {
             &mut ctx.device,
             ctx.graphics.projection_matrix * ctx.graphics.transform_matrix,
             Color::WHITE,
+            ctx.graphics.color_blend,
         );
 
-        ctx.device.cull_face(true);
+        ctx.device.cull_face(CullMode::Back);
 
-        // Because canvas rendering is effectively done upside-down, the winding order is the opposite
-        // of what you'd expect in that case.
-        ctx.device.front_face(match &ctx.graphics.canvas {
-            ActiveCanvas::Window => VertexWinding::CounterClockwise,
-            ActiveCanvas::User(_) => VertexWinding::Clockwise,
-        });
+        ctx.device
+            .front_face(front_face_winding(ctx, VertexWinding::CounterClockwise));
 
         ctx.device.set_vertex_buffer_data(
             &ctx.graphics.vertex_buffer,
@@ -376,6 +589,10 @@ pub fn flush(ctx: &mut Context) {
             0,
         );
 
+        apply_sampler(&mut ctx.device, ctx.graphics.sampler, texture);
+
+        invalidate_canvas_alpha_mask(ctx);
+
         ctx.device.draw_elements(
             &ctx.graphics.vertex_buffer,
             &ctx.graphics.index_buffer,
@@ -385,11 +602,41 @@ pub fn flush(ctx: &mut Context) {
             ctx.graphics.element_count,
         );
 
+        restore_sampler(&mut ctx.device, ctx.graphics.sampler, texture);
+
         ctx.graphics.vertex_data.clear();
         ctx.graphics.element_count = 0;
     }
 }
 
+/// Blocks until the graphics hardware has finished all of the work that has been sent to it.
+///
+/// This will [`flush`] any queued drawing operations first. It can be useful for benchmarking
+/// (e.g. to measure how long the GPU takes to render a frame), but it stalls the CPU until
+/// the GPU catches up, so it should not be called in normal gameplay code. Consider using
+/// a [`Fence`] if you need to check for completion without blocking.
+pub fn finish(ctx: &mut Context) {
+    flush(ctx);
+    ctx.device.finish();
+}
+
+/// Inserts a [`Fence`] into the stream of commands sent to the graphics hardware.
+///
+/// This will [`flush`] any queued drawing operations first, so that the fence will be
+/// signaled once they have been completed.
+///
+/// # Errors
+///
+/// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned
+/// if the underlying graphics API encounters an error.
+pub fn fence(ctx: &mut Context) -> Result<Fence> {
+    flush(ctx);
+
+    let handle = ctx.device.new_fence()?;
+
+    Ok(Fence { handle })
+}
+
 /// Presents the result of drawing commands to the screen.
 ///
 /// If any custom shaders/canvases are set, this function will unset them -
@@ -397,12 +644,33 @@ pub fn flush(ctx: &mut Context) {
 ///
 /// You usually will not have to call this manually, as it is called for you at the end of every
 /// frame. Note that calling it will trigger a [`flush`] to the graphics hardware.
+///
+/// Any shapes queued via the [`debug`] module will be drawn on top of the frame
+/// before it is presented.
+///
+/// The screen will not be cleared after presenting, but its contents are undefined
+/// from this point on - see [`clear`] for more details.
 pub fn present(ctx: &mut Context) {
+    debug::draw_queued(ctx);
     flush(ctx);
 
     ctx.window.swap_buffers();
 }
 
+/// Returns a 1x1 white texture.
+///
+/// This can be stretched and tinted via [`DrawParams`] to draw solid colored rectangles.
+/// Unlike a [`Mesh`](mesh::Mesh), these draws can be batched together with any other
+/// draws that use the same texture.
+///
+/// The texture is created when the context starts up, so calling this function is cheap.
+/// It is separate from the texture that Tetra uses internally for untextured drawing, so
+/// changing its data or settings will not affect Tetra's own rendering - but it will
+/// affect everything else that uses it.
+pub fn white_texture(ctx: &Context) -> &Texture {
+    &ctx.graphics.white_texture
+}
+
 /// Returns the filter mode that will be used by newly created textures and canvases.
 pub fn get_default_filter_mode(ctx: &Context) -> FilterMode {
     ctx.graphics.default_filter_mode
@@ -413,6 +681,102 @@ pub fn set_default_filter_mode(ctx: &mut Context, filter_mode: FilterMode) {
     ctx.graphics.default_filter_mode = filter_mode;
 }
 
+/// Sets the stencil state used for future drawing operations.
+///
+/// The stencil state determines how drawing interacts with the window's stencil buffer -
+/// for example, you can use [`StencilState::write`] to write an ID into the buffer wherever
+/// an object is drawn, and then read it back with [`read_stencil`] for pixel-perfect picking.
+///
+/// Only the window has a stencil buffer, and one must have been requested via
+/// [`ContextBuilder::stencil_buffer`](crate::ContextBuilder::stencil_buffer). While a
+/// [`Canvas`] is active, the stencil test will always pass.
+pub fn set_stencil_state(ctx: &mut Context, stencil_state: StencilState) {
+    if stencil_state != ctx.graphics.stencil_state {
+        flush(ctx);
+        ctx.graphics.stencil_state = stencil_state;
+    }
+    ctx.device.set_stencil_state(stencil_state);
+}
+
+/// Resets the stencil state to the default, which disables the stencil test.
+pub fn reset_stencil_state(ctx: &mut Context) {
+    set_stencil_state(ctx, StencilState::disabled());
+}
+
+/// Clears the window's stencil buffer to the specified value.
+///
+/// This is not affected by the [`write_mask`](StencilState::write_mask) of the active
+/// stencil state - the whole buffer will always be cleared.
+pub fn clear_stencil(ctx: &mut Context, value: u8) {
+    flush(ctx);
+    ctx.device.clear_stencil(value);
+
+    // Clearing requires the write mask to be reset, so the active state needs re-applying.
+    ctx.device.set_stencil_state(ctx.graphics.stencil_state);
+}
+
+/// Reads the value of the window's stencil buffer at the given position.
+///
+/// The position is measured in logical pixels, relative to the top left of the window (the
+/// same co-ordinate space as [`get_mouse_position`](crate::input::get_mouse_position)).
+///
+/// A stencil buffer must have been requested via
+/// [`ContextBuilder::stencil_buffer`](crate::ContextBuilder::stencil_buffer) - otherwise,
+/// the result will always be zero. Values can be written to the buffer via
+/// [`set_stencil_state`] and [`clear_stencil`].
+///
+/// Note that calling this function will trigger a [`flush`] to the graphics hardware, and
+/// will stall until all pending rendering has completed. It should not be called many times
+/// per frame.
+pub fn read_stencil(ctx: &mut Context, x: i32, y: i32) -> u8 {
+    flush(ctx);
+
+    let (x, y) = window_to_pixel(ctx, x, y);
+    ctx.device.read_window_stencil(x, y)
+}
+
+/// Reads the value of the window's depth buffer at the given position, in the range
+/// `0.0` to `1.0`.
+///
+/// The position is measured in logical pixels, relative to the top left of the window (the
+/// same co-ordinate space as [`get_mouse_position`](crate::input::get_mouse_position)).
+///
+/// A depth buffer must have been requested via
+/// [`ContextBuilder::depth_buffer`](crate::ContextBuilder::depth_buffer) - otherwise,
+/// the result will be undefined.
+///
+/// Note that calling this function will trigger a [`flush`] to the graphics hardware, and
+/// will stall until all pending rendering has completed. It should not be called many times
+/// per frame.
+pub fn read_depth(ctx: &mut Context, x: i32, y: i32) -> f32 {
+    flush(ctx);
+
+    let (x, y) = window_to_pixel(ctx, x, y);
+    ctx.device.read_window_depth(x, y)
+}
+
+/// Converts a logical window position to a physical position in the backbuffer, which
+/// has its origin at the bottom left.
+fn window_to_pixel(ctx: &Context, x: i32, y: i32) -> (i32, i32) {
+    let scale = window::get_dpi_scale(ctx);
+    let physical_height = window::get_physical_height(ctx);
+
+    let x = (x as f32 * scale) as i32;
+    let y = (y as f32 * scale) as i32;
+
+    (x, physical_height - 1 - y)
+}
+
+/// Returns the format that will be used by newly created textures and canvases.
+pub fn get_default_texture_format(ctx: &Context) -> TextureFormat {
+    ctx.graphics.default_texture_format
+}
+
+/// Sets the format that will be used by newly created textures and canvases.
+pub fn set_default_texture_format(ctx: &mut Context, format: TextureFormat) {
+    ctx.graphics.default_texture_format = format;
+}
+
 /// Information about the device currently being used to render graphics.
 #[derive(Debug, Clone)]
 pub struct GraphicsDeviceInfo {
@@ -428,6 +792,12 @@ pub struct GraphicsDeviceInfo {
 
     /// The version of GLSL that is being used.
     pub glsl_version: String,
+
+    /// The maximum level of anisotropic filtering supported by the device, or
+    /// `None` if anisotropic filtering is not supported.
+    ///
+    /// See [`Texture::set_anisotropy`](crate::graphics::Texture::set_anisotropy) for more details.
+    pub max_anisotropy: Option<f32>,
 }
 
 /// Retrieves information about the device currently being used to render graphics.
@@ -439,9 +809,38 @@ pub fn get_device_info(ctx: &Context) -> GraphicsDeviceInfo {
         renderer: ctx.device.get_renderer(),
         opengl_version: ctx.device.get_version(),
         glsl_version: ctx.device.get_shading_language_version(),
+        max_anisotropy: ctx.device.get_max_anisotropy(),
     }
 }
 
+/// Returns an estimate of how much GPU memory is being used by textures, in bytes.
+///
+/// This includes all live [`Texture`]s, [`TextureArray`]s and [`Texture3D`]s (including
+/// the ones used internally by Tetra, such as font atlases), but not [`Canvas`]es - see
+/// [`get_canvas_memory_usage`] for those. Textures that share data via cloning are
+/// only counted once.
+///
+/// The value is calculated from the size and format of each texture, so it does not
+/// account for any padding or other overhead that the graphics driver adds. It is
+/// mainly useful for tracking how your usage changes over time (e.g. to find leaks),
+/// rather than as an exact figure.
+pub fn get_texture_memory_usage(ctx: &Context) -> usize {
+    ctx.device.get_texture_memory_usage()
+}
+
+/// Returns an estimate of how much GPU memory is being used by canvases, in bytes.
+///
+/// This includes the texture that backs each [`Canvas`], plus the extra buffer used by
+/// [multisampled](Canvas::multisampled) canvases (which takes up one copy of the canvas'
+/// pixels per sample). Canvases created via [`Canvas::from_texture_array`] are counted
+/// as part of the texture array, in [`get_texture_memory_usage`].
+///
+/// As with [`get_texture_memory_usage`], this does not account for any overhead added
+/// by the graphics driver.
+pub fn get_canvas_memory_usage(ctx: &Context) -> usize {
+    ctx.device.get_canvas_memory_usage()
+}
+
 /// Returns the current transform matrix.
 pub fn get_transform_matrix(ctx: &Context) -> Mat4<f32> {
     ctx.graphics.transform_matrix
@@ -463,6 +862,85 @@ pub fn reset_transform_matrix(ctx: &mut Context) {
     set_transform_matrix(ctx, Mat4::identity());
 }
 
+/// The coordinate systems that can be used for rendering.
+///
+/// This determines how the positions of the things you draw map to the screen (or to a
+/// [`Canvas`], if one is active). It is applied before the
+/// [transform matrix](set_transform_matrix), so it can be combined with a [`Camera`].
+///
+/// Note that this does not affect the coordinates reported by other parts of Tetra (e.g.
+/// [`input::get_mouse_position`](crate::input::get_mouse_position)), which always use
+/// [`TopLeft`](ProjectionMode::TopLeft).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProjectionMode {
+    /// The origin is at the top left, and the Y axis points down. Units are pixels.
+    ///
+    /// This is the default.
+    TopLeft,
+
+    /// The origin is at the bottom left, and the Y axis points up. Units are pixels.
+    BottomLeft,
+
+    /// The origin is in the center, and the Y axis points down. Units are pixels.
+    Center,
+
+    /// The origin is in the center, and the Y axis points up. Units are pixels.
+    CenterYUp,
+
+    /// A custom projection matrix, which should map your coordinates to OpenGL's
+    /// normalized device coordinates (where the Y axis points up).
+    ///
+    /// When rendering to a canvas, the Y axis will be flipped automatically, so the same
+    /// matrix can be used for both the window and canvases.
+    Custom(Mat4<f32>),
+}
+
+/// Returns the current projection mode.
+pub fn get_projection_mode(ctx: &Context) -> ProjectionMode {
+    ctx.graphics.projection_mode
+}
+
+/// Sets the projection mode, which determines the coordinate system used for rendering.
+pub fn set_projection_mode(ctx: &mut Context, projection_mode: ProjectionMode) {
+    flush(ctx);
+
+    ctx.graphics.projection_mode = projection_mode;
+
+    ctx.graphics.projection_matrix = match &ctx.graphics.canvas {
+        ActiveCanvas::Window => {
+            let (width, height) = window::get_size(ctx);
+            projection(projection_mode, width, height, false)
+        }
+        ActiveCanvas::User(c) => {
+            let (width, height) = c.size();
+            projection(projection_mode, width, height, true)
+        }
+    };
+}
+
+/// Resets the projection mode to the default.
+///
+/// This is a shortcut for calling [`graphics::set_projection_mode(ctx, ProjectionMode::TopLeft)`](set_projection_mode).
+pub fn reset_projection_mode(ctx: &mut Context) {
+    set_projection_mode(ctx, ProjectionMode::TopLeft);
+}
+
+/// Returns the winding order that should be considered front-facing, given the winding of
+/// the geometry and the current projection.
+///
+/// If the projection flips one of the axes (as it does when rendering to a canvas, or
+/// with a Y-up projection), the winding of the geometry will be flipped on screen too.
+pub(crate) fn front_face_winding(ctx: &Context, winding: VertexWinding) -> VertexWinding {
+    let m = &ctx.graphics.projection_matrix;
+    let determinant = m.cols.x.x * m.cols.y.y - m.cols.y.x * m.cols.x.y;
+
+    if determinant < 0.0 {
+        winding
+    } else {
+        winding.flipped()
+    }
+}
+
 pub(crate) fn set_viewport_size(
     ctx: &mut Context,
     width: i32,
@@ -471,11 +949,45 @@ pub(crate) fn set_viewport_size(
     pixel_height: i32,
 ) {
     if let ActiveCanvas::Window = ctx.graphics.canvas {
-        ctx.graphics.projection_matrix = ortho(width as f32, height as f32, false);
+        ctx.graphics.projection_matrix =
+            projection(ctx.graphics.projection_mode, width, height, false);
         ctx.device.viewport(0, 0, pixel_width, pixel_height);
     }
 }
 
+fn projection(mode: ProjectionMode, width: i32, height: i32, flipped: bool) -> Mat4<f32> {
+    let width = width as f32;
+    let height = height as f32;
+
+    let matrix = match mode {
+        ProjectionMode::TopLeft => return ortho(width, height, flipped),
+        ProjectionMode::BottomLeft => ortho(width, height, true),
+        ProjectionMode::Center => Mat4::orthographic_rh_no(FrustumPlanes {
+            left: -width / 2.0,
+            right: width / 2.0,
+            bottom: height / 2.0,
+            top: -height / 2.0,
+            near: -1.0,
+            far: 1.0,
+        }),
+        ProjectionMode::CenterYUp => Mat4::orthographic_rh_no(FrustumPlanes {
+            left: -width / 2.0,
+            right: width / 2.0,
+            bottom: -height / 2.0,
+            top: height / 2.0,
+            near: -1.0,
+            far: 1.0,
+        }),
+        ProjectionMode::Custom(matrix) => matrix,
+    };
+
+    if flipped {
+        Mat4::<f32>::scaling_3d(Vec3::new(1.0, -1.0, 1.0)) * matrix
+    } else {
+        matrix
+    }
+}
+
 pub(crate) fn ortho(width: f32, height: f32, flipped: bool) -> Mat4<f32> {
     Mat4::orthographic_rh_no(FrustumPlanes {
         left: 0.0,
@@ -547,3 +1059,169 @@ impl Default for BlendAlphaMode {
         BlendAlphaMode::Multiply
     }
 }
+
+/// A comparison between the stencil state's reference value and the value in the
+/// stencil buffer, which determines whether a pixel will be drawn.
+///
+/// Both values are combined with the [`read_mask`](StencilState::read_mask) before
+/// being compared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StencilTest {
+    /// The test will never pass.
+    Never,
+
+    /// The test will pass if the reference value is less than the buffer's value.
+    LessThan,
+
+    /// The test will pass if the reference value is less than or equal to the buffer's value.
+    LessThanOrEqualTo,
+
+    /// The test will pass if the reference value is equal to the buffer's value.
+    EqualTo,
+
+    /// The test will pass if the reference value is not equal to the buffer's value.
+    NotEqualTo,
+
+    /// The test will pass if the reference value is greater than the buffer's value.
+    GreaterThan,
+
+    /// The test will pass if the reference value is greater than or equal to the buffer's value.
+    GreaterThanOrEqualTo,
+
+    /// The test will always pass.
+    Always,
+}
+
+/// How the value in the stencil buffer will be modified when a pixel is drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StencilAction {
+    /// The value will be left unchanged.
+    Keep,
+
+    /// The value will be set to zero.
+    Zero,
+
+    /// The value will be replaced with the stencil state's reference value.
+    Replace,
+
+    /// The value will be incremented, stopping at 255.
+    Increment,
+
+    /// The value will be incremented, wrapping around to zero after 255.
+    IncrementWrap,
+
+    /// The value will be decremented, stopping at zero.
+    Decrement,
+
+    /// The value will be decremented, wrapping around to 255 after zero.
+    DecrementWrap,
+
+    /// The bits of the value will be inverted.
+    Invert,
+}
+
+/// How drawing interacts with the window's stencil buffer.
+///
+/// This can be set via [`set_stencil_state`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StencilState {
+    /// Whether the stencil test is enabled. If this is `false`, the other settings
+    /// will be ignored, and the stencil buffer will not be modified.
+    pub enabled: bool,
+
+    /// How the stencil buffer will be modified when a pixel passes the test.
+    pub action: StencilAction,
+
+    /// The test that determines whether a pixel will be drawn.
+    pub test: StencilTest,
+
+    /// The value that will be compared against (and possibly written to) the stencil buffer.
+    pub reference_value: u8,
+
+    /// A mask that determines which bits of the stencil buffer can be modified.
+    pub write_mask: u8,
+
+    /// A mask that is applied to both the reference value and the buffer's value before
+    /// they are compared.
+    pub read_mask: u8,
+}
+
+impl StencilState {
+    /// Creates a stencil state that disables the stencil test.
+    ///
+    /// This is the default state.
+    pub fn disabled() -> StencilState {
+        StencilState {
+            enabled: false,
+            action: StencilAction::Keep,
+            test: StencilTest::Always,
+            reference_value: 0,
+            write_mask: 0x00,
+            read_mask: 0x00,
+        }
+    }
+
+    /// Creates a stencil state that will apply the specified action to every pixel
+    /// that gets drawn, without affecting what is drawn.
+    ///
+    /// For example, `StencilState::write(StencilAction::Replace, 5)` will write `5`
+    /// into the stencil buffer wherever something is drawn.
+    pub fn write(action: StencilAction, reference_value: u8) -> StencilState {
+        StencilState {
+            enabled: true,
+            action,
+            test: StencilTest::Always,
+            reference_value,
+            write_mask: 0xFF,
+            read_mask: 0xFF,
+        }
+    }
+
+    /// Creates a stencil state that will only draw pixels that pass the specified test,
+    /// without modifying the stencil buffer.
+    pub fn read(test: StencilTest, reference_value: u8) -> StencilState {
+        StencilState {
+            enabled: true,
+            action: StencilAction::Keep,
+            test,
+            reference_value,
+            write_mask: 0x00,
+            read_mask: 0xFF,
+        }
+    }
+}
+
+impl Default for StencilState {
+    fn default() -> StencilState {
+        StencilState::disabled()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::Vec4;
+
+    fn project(matrix: Mat4<f32>, x: f32, y: f32) -> Vec2<f32> {
+        (matrix * Vec4::new(x, y, 0.0, 1.0)).xy()
+    }
+
+    #[test]
+    fn canvas_projection_matches_texture_orientation() {
+        // On the window, the top of the screen is at the top of clip space. On a canvas,
+        // it must be at the bottom instead, as that is where the first row of the
+        // framebuffer's texture lives (which is what a V co-ordinate of 0 will sample).
+        let window = projection(ProjectionMode::TopLeft, 64, 32, false);
+        let canvas = projection(ProjectionMode::TopLeft, 64, 32, true);
+
+        assert_eq!(Vec2::new(-1.0, 1.0), project(window, 0.0, 0.0));
+        assert_eq!(Vec2::new(-1.0, -1.0), project(canvas, 0.0, 0.0));
+        assert_eq!(Vec2::new(1.0, 1.0), project(canvas, 64.0, 32.0));
+
+        let window = projection(ProjectionMode::Center, 64, 32, false);
+        let canvas = projection(ProjectionMode::Center, 64, 32, true);
+
+        assert_eq!(Vec2::new(-1.0, 1.0), project(window, -32.0, -16.0));
+        assert_eq!(Vec2::new(-1.0, -1.0), project(canvas, -32.0, -16.0));
+    }
+}
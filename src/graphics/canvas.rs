@@ -1,7 +1,12 @@
+use std::cell::Cell;
 use std::rc::Rc;
 
 use crate::error::Result;
-use crate::graphics::{DrawParams, FilterMode, Texture};
+use crate::graphics::{
+    self, ActiveCanvas, BlendMode, DrawParams, FilterMode, Rectangle, Texture, TextureArray,
+    TextureFormat,
+};
+use crate::math::Vec2;
 use crate::platform::{GraphicsDevice, RawFramebuffer, RawRenderbuffer};
 use crate::Context;
 
@@ -14,6 +19,16 @@ use crate::Context;
 /// (e.g. a static background), or if you want to apply transformations/shaders to multiple
 /// things simultaneously.
 ///
+/// # Orientation
+///
+/// OpenGL framebuffers have their origin at the bottom left, but Tetra automatically flips
+/// the projection when rendering to a canvas to account for this. This means that a canvas'
+/// contents are always stored the same way up as a [`Texture`] loaded from an image -
+/// drawing a canvas (via [`draw`](Self::draw), [`draw_region`](Self::draw_region) or its
+/// [`texture`](Self::texture)), reading its data back via [`Texture::get_data`], or
+/// sampling it in a shader will all give you the image the same way up as it was
+/// rendered. You should never need to flip a canvas manually.
+///
 /// # Performance
 ///
 /// Creating a `Canvas` is a relatively expensive operation. If you can, store them in your
@@ -34,11 +49,14 @@ pub struct Canvas {
     pub(crate) framebuffer: Rc<RawFramebuffer>,
     pub(crate) texture: Texture,
     pub(crate) multisample: Option<Rc<RawRenderbuffer>>,
+    pub(crate) layer: Option<Rc<Cell<i32>>>,
 }
 
 impl Canvas {
     /// Creates a new canvas.
     ///
+    /// The canvas will initially be cleared to transparent black.
+    ///
     /// # Errors
     ///
     /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the underlying
@@ -49,6 +67,7 @@ impl Canvas {
             width,
             height,
             ctx.graphics.default_filter_mode,
+            ctx.graphics.default_texture_format,
             0,
         )
     }
@@ -58,6 +77,8 @@ impl Canvas {
     /// The number of samples that can be used varies between graphics cards - `2`, `4` and `8` are reasonably
     /// well supported.
     ///
+    /// The canvas will initially be cleared to transparent black.
+    ///
     /// # Errors
     ///
     /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the underlying
@@ -68,25 +89,66 @@ impl Canvas {
             width,
             height,
             ctx.graphics.default_filter_mode,
+            ctx.graphics.default_texture_format,
             samples,
         )
     }
 
+    /// Creates a new canvas that renders into the layers of a texture array.
+    ///
+    /// The canvas will initially render into layer `0` - this can be changed via
+    /// [`set_layer`](Self::set_layer). This allows you to render multiple passes (e.g. the
+    /// masks for a lighting system) without having to create a separate canvas for each one.
+    ///
+    /// As texture arrays can't be drawn directly, layered canvases can't either - the
+    /// results should be sampled from the [`TextureArray`] via a custom shader instead.
+    /// For the same reason, [`texture`](Self::texture), [`set_data`](Self::set_data) and
+    /// [`replace_data`](Self::replace_data) will panic if called on a layered canvas.
+    ///
+    /// The contents of the texture array will not be cleared.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the underlying
+    /// graphics API encounters an error.
+    pub fn from_texture_array(ctx: &mut Context, texture_array: &TextureArray) -> Result<Canvas> {
+        let texture = texture_array.texture.clone();
+
+        let framebuffer = ctx.device.new_framebuffer()?;
+
+        ctx.device
+            .attach_texture_layer_to_framebuffer(&framebuffer, &texture.data.handle, 0, true);
+
+        Ok(Canvas {
+            framebuffer: Rc::new(framebuffer),
+            texture,
+            multisample: None,
+            layer: Some(Rc::new(Cell::new(0))),
+        })
+    }
+
     pub(crate) fn with_device(
         device: &mut GraphicsDevice,
         width: i32,
         height: i32,
         filter_mode: FilterMode,
+        format: TextureFormat,
         samples: u8,
     ) -> Result<Canvas> {
-        let texture = Texture::with_device_empty(device, width, height, filter_mode)?;
+        let texture = Texture::with_device_empty(device, width, height, filter_mode, format)?;
+        device.mark_texture_as_canvas(&texture.data.handle);
 
         let framebuffer = device.new_framebuffer()?;
 
         let multisample = if samples > 0 {
-            let multisample = device.new_renderbuffer(width, height, samples)?;
+            let multisample = device.new_renderbuffer(width, height, samples, format)?;
             device.attach_renderbuffer_to_framebuffer(&framebuffer, &multisample, true);
 
+            // The texture only gets written to when the multisample buffer is resolved,
+            // so it needs to be initialized separately.
+            let zeroed = vec![0; (width * height * 4) as usize];
+            device.set_texture_data(&texture.data.handle, &zeroed, 0, 0, width, height)?;
+
             Some(Rc::new(multisample))
         } else {
             device.attach_texture_to_framebuffer(&framebuffer, &texture.data.handle, true);
@@ -94,21 +156,236 @@ impl Canvas {
             None
         };
 
+        device.clear_framebuffer(&framebuffer, 0.0, 0.0, 0.0, 0.0);
+
         Ok(Canvas {
             framebuffer: Rc::new(framebuffer),
             texture,
             multisample,
+            layer: None,
         })
     }
 
     /// Draws the canvas to the screen (or to another canvas, if one is enabled).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the canvas was created via [`from_texture_array`](Self::from_texture_array),
+    /// as texture arrays can't be drawn directly.
     pub fn draw<P>(&self, ctx: &mut Context, params: P)
     where
         P: Into<DrawParams>,
     {
+        assert!(
+            self.layer.is_none(),
+            "canvases that render into a texture array cannot be drawn directly"
+        );
+
         self.texture.draw(ctx, params)
     }
 
+    /// Draws a region of the canvas to the screen (or to another canvas, if one is enabled).
+    ///
+    /// The region is specified in the canvas' own co-ordinate space, with `(0.0, 0.0)`
+    /// at the top left - the same way it would be for a [`Texture`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the canvas was created via [`from_texture_array`](Self::from_texture_array),
+    /// as texture arrays can't be drawn directly.
+    pub fn draw_region<P>(&self, ctx: &mut Context, region: Rectangle, params: P)
+    where
+        P: Into<DrawParams>,
+    {
+        assert!(
+            self.layer.is_none(),
+            "canvases that render into a texture array cannot be drawn directly"
+        );
+
+        self.texture.draw_region(ctx, region, params)
+    }
+
+    /// Draws the canvas to the screen (or to another canvas, if one is enabled), stretched to
+    /// fill the given rectangle.
+    ///
+    /// See [`Texture::draw_to`] for details of how the `params` are applied.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the canvas was created via [`from_texture_array`](Self::from_texture_array),
+    /// as texture arrays can't be drawn directly.
+    pub fn draw_to<P>(&self, ctx: &mut Context, dest: Rectangle, params: P)
+    where
+        P: Into<DrawParams>,
+    {
+        assert!(
+            self.layer.is_none(),
+            "canvases that render into a texture array cannot be drawn directly"
+        );
+
+        self.texture.draw_to(ctx, dest, params)
+    }
+
+    /// Resolves the contents of the canvas into the given texture.
+    ///
+    /// For a [multisampled](Self::multisampled) canvas, this performs the same
+    /// anti-aliasing resolve that normally happens when you switch away from the canvas,
+    /// but writes the result into a texture of your choosing. This can be used to feed
+    /// the canvas into a chain of post-processing passes (e.g. ping-ponging between two
+    /// textures) without allocating extra canvases. For a canvas without multisampling,
+    /// this is a plain copy.
+    ///
+    /// If the canvas is currently active, any queued drawing will be [flushed](graphics::flush)
+    /// first. The canvas' own texture is not updated by this method.
+    ///
+    /// The target should use the same [`TextureFormat`] as the
+    /// canvas - some platforms do not support resolving between different formats.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the target texture is not the same size as the canvas.
+    pub fn resolve_to(&self, ctx: &mut Context, target: &Texture) {
+        assert_eq!(
+            self.size(),
+            target.size(),
+            "resolve target must be the same size as the canvas"
+        );
+
+        if let ActiveCanvas::User(active) = &ctx.graphics.canvas {
+            if active == self {
+                graphics::flush(ctx);
+            }
+        }
+
+        if self.multisample.is_none() && *target == self.texture {
+            return;
+        }
+
+        graphics::blit_to_texture(ctx, &self.framebuffer, target);
+
+        match &ctx.graphics.canvas {
+            ActiveCanvas::Window => ctx.device.bind_framebuffer(None),
+            ActiveCanvas::User(c) => ctx.device.bind_framebuffer(Some(&c.framebuffer)),
+        }
+    }
+
+    /// Draws a texture onto the canvas, stretched to fill the `dest` rectangle and blended
+    /// using the given blend mode.
+    ///
+    /// This is a shortcut for switching to the canvas, setting the blend mode, drawing the
+    /// texture via [`Texture::draw_to`], and then switching everything back - which makes it
+    /// handy for 'stamping' decals or brush strokes onto a persistent render target. The
+    /// texture is always drawn using the default shader, ignoring any shader that has been
+    /// set via [`graphics::set_shader`]. The active canvas, shader, blend mode and transform
+    /// matrix are all restored afterwards, so this can be called at any point while drawing.
+    ///
+    /// The `dest` rectangle is in the canvas' own co-ordinate space, and the
+    /// [transform matrix](graphics::set_transform_matrix) is not applied to it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `source` is the canvas' own texture, as a canvas cannot be drawn into itself.
+    pub fn blit_from(
+        &self,
+        ctx: &mut Context,
+        source: &Texture,
+        dest: Rectangle,
+        blend_mode: BlendMode,
+    ) {
+        assert!(
+            *source != self.texture,
+            "a canvas cannot be blitted into itself"
+        );
+
+        let previous_canvas = ctx.graphics.canvas.clone();
+        let previous_shader = ctx.graphics.shader.clone();
+        let previous_blend_mode = ctx.graphics.blend_mode;
+        let previous_transform_matrix = ctx.graphics.transform_matrix;
+
+        graphics::set_canvas(ctx, self);
+        graphics::reset_shader(ctx);
+        graphics::set_blend_mode(ctx, blend_mode);
+        graphics::reset_transform_matrix(ctx);
+
+        source.draw_to(ctx, dest, DrawParams::new());
+
+        graphics::set_transform_matrix(ctx, previous_transform_matrix);
+        graphics::set_blend_mode(ctx, previous_blend_mode);
+        graphics::set_shader_ex(ctx, previous_shader);
+        graphics::set_canvas_ex(ctx, previous_canvas);
+    }
+
+    /// Maps a position back into the canvas' co-ordinate space, given the params that the canvas
+    /// was drawn with.
+    ///
+    /// This is the inverse of the transformation applied by [`draw`](Self::draw), and can be used
+    /// to work out where the mouse is on a canvas that has been scaled, rotated or moved. The
+    /// position should be in the co-ordinate space that the canvas was drawn into (e.g.
+    /// [`input::get_mouse_position`](crate::input::get_mouse_position) if it was drawn directly
+    /// to the window).
+    ///
+    /// Returns `None` if the position is outside of the canvas, or if the params cannot be inverted.
+    pub fn canvas_coordinates<P>(&self, screen_position: Vec2<f32>, params: P) -> Option<Vec2<f32>>
+    where
+        P: Into<DrawParams>,
+    {
+        let (width, height) = self.size();
+        let bounds = Rectangle::new(0.0, 0.0, width as f32, height as f32);
+
+        params
+            .into()
+            .to_local(screen_position, bounds)
+            .filter(|p| p.x >= 0.0 && p.y >= 0.0 && p.x < bounds.width && p.y < bounds.height)
+    }
+
+    /// Returns the layer of the texture array that the canvas is currently rendering into.
+    ///
+    /// Returns `None` if the canvas was not created via
+    /// [`from_texture_array`](Self::from_texture_array).
+    pub fn layer(&self) -> Option<i32> {
+        self.layer.as_ref().map(|layer| layer.get())
+    }
+
+    /// Sets the layer of the texture array that the canvas should render into.
+    ///
+    /// If the canvas is currently active, this will trigger a [`flush`](graphics::flush)
+    /// to the graphics hardware, so that anything drawn before the call ends up in the
+    /// previous layer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the canvas was not created via [`from_texture_array`](Self::from_texture_array),
+    /// or if the layer does not exist.
+    pub fn set_layer(&self, ctx: &mut Context, layer: i32) {
+        let current_layer = self
+            .layer
+            .as_ref()
+            .expect("only canvases that render into a texture array have layers");
+
+        assert!(
+            layer >= 0 && layer < self.texture.data.handle.depth(),
+            "layer {} does not exist",
+            layer
+        );
+
+        if current_layer.get() == layer {
+            return;
+        }
+
+        if matches!(&ctx.graphics.canvas, ActiveCanvas::User(c) if c == self) {
+            graphics::flush(ctx);
+        }
+
+        ctx.device.attach_texture_layer_to_framebuffer(
+            &self.framebuffer,
+            &self.texture.data.handle,
+            layer,
+            true,
+        );
+
+        current_layer.set(layer);
+    }
+
     /// Returns the width of the canvas.
     pub fn width(&self) -> i32 {
         self.texture.width()
@@ -134,6 +411,11 @@ impl Canvas {
         self.texture.set_filter_mode(ctx, filter_mode);
     }
 
+    /// Returns the format that the canvas' data is stored in.
+    pub fn format(&self) -> TextureFormat {
+        self.texture.format()
+    }
+
     /// Writes RGBA pixel data to a specified region of the canvas.
     ///
     /// This method requires you to provide enough data to fill the target rectangle.
@@ -152,6 +434,9 @@ impl Canvas {
     /// # Panics
     ///
     /// Panics if any part of the target rectangle is outside the bounds of the canvas.
+    ///
+    /// Panics if the canvas was created via [`from_texture_array`](Self::from_texture_array),
+    /// as its data should be accessed via the [`TextureArray`] instead.
     pub fn set_data(
         &self,
         ctx: &mut Context,
@@ -161,6 +446,11 @@ impl Canvas {
         height: i32,
         data: &[u8],
     ) -> Result {
+        assert!(
+            self.layer.is_none(),
+            "canvases that render into a texture array cannot be accessed as a texture"
+        );
+
         self.texture.set_data(ctx, x, y, width, height, data)
     }
 
@@ -178,12 +468,34 @@ impl Canvas {
     /// * [`TetraError::NotEnoughData`](crate::TetraError::NotEnoughData) will be returned
     /// if not enough data is provided to fill the target rectangle. This is to prevent
     /// the graphics API from trying to read uninitialized memory.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the canvas was created via [`from_texture_array`](Self::from_texture_array),
+    /// as its data should be accessed via the [`TextureArray`] instead.
     pub fn replace_data(&self, ctx: &mut Context, data: &[u8]) -> Result {
+        assert!(
+            self.layer.is_none(),
+            "canvases that render into a texture array cannot be accessed as a texture"
+        );
+
         self.texture.replace_data(ctx, data)
     }
 
     /// Returns a reference to the canvas' underlying texture.
+    ///
+    /// This can be used to read the canvas' contents back via [`Texture::get_data`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the canvas was created via [`from_texture_array`](Self::from_texture_array),
+    /// as its data should be accessed via the [`TextureArray`] instead.
     pub fn texture(&self) -> &Texture {
+        assert!(
+            self.layer.is_none(),
+            "canvases that render into a texture array cannot be accessed as a texture"
+        );
+
         &self.texture
     }
 }
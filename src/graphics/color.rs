@@ -15,6 +15,10 @@ use crate::math::Vec4;
 /// add/subtract/multiply/divide colors. These are implemented as saturating
 /// operations (i.e. the values will always remain between `0.0` and `1.0`).
 ///
+/// A set of common colors are provided as constants (e.g. [`Color::RED`]). With the
+/// exception of [`Color::TRANSPARENT`], these are fully opaque, and their values are
+/// in sRGB space, like any other color you pass to Tetra.
+///
 /// # Serde
 ///
 /// Serialization and deserialization of this type (via [Serde](https://serde.rs/))
@@ -157,8 +161,23 @@ impl Color {
     /// Shortcut for [`Color::rgb(0.0, 1.0, 0.0)`](Self::rgb).
     pub const GREEN: Color = Color::rgb(0.0, 1.0, 0.0);
 
-    /// Shortcut for Color::rgb(0.0, 0.0, 1.0)`](Self::rgb).
+    /// Shortcut for [`Color::rgb(0.0, 0.0, 1.0)`](Self::rgb).
     pub const BLUE: Color = Color::rgb(0.0, 0.0, 1.0);
+
+    /// Shortcut for [`Color::rgb(1.0, 1.0, 0.0)`](Self::rgb).
+    pub const YELLOW: Color = Color::rgb(1.0, 1.0, 0.0);
+
+    /// Shortcut for [`Color::rgb(0.0, 1.0, 1.0)`](Self::rgb).
+    pub const CYAN: Color = Color::rgb(0.0, 1.0, 1.0);
+
+    /// Shortcut for [`Color::rgb(1.0, 0.0, 1.0)`](Self::rgb).
+    pub const MAGENTA: Color = Color::rgb(1.0, 0.0, 1.0);
+
+    /// Shortcut for [`Color::rgb(0.5, 0.5, 0.5)`](Self::rgb).
+    pub const GRAY: Color = Color::rgb(0.5, 0.5, 0.5);
+
+    /// Shortcut for [`Color::rgba(0.0, 0.0, 0.0, 0.0)`](Self::rgba).
+    pub const TRANSPARENT: Color = Color::rgba(0.0, 0.0, 0.0, 0.0);
 }
 
 impl From<Color> for Vec4<f32> {
@@ -173,6 +192,30 @@ impl From<Vec4<f32>> for Color {
     }
 }
 
+impl From<(f32, f32, f32, f32)> for Color {
+    fn from((r, g, b, a): (f32, f32, f32, f32)) -> Color {
+        Color::rgba(r, g, b, a)
+    }
+}
+
+impl From<Color> for (f32, f32, f32, f32) {
+    fn from(color: Color) -> (f32, f32, f32, f32) {
+        (color.r, color.g, color.b, color.a)
+    }
+}
+
+impl From<[f32; 4]> for Color {
+    fn from([r, g, b, a]: [f32; 4]) -> Color {
+        Color::rgba(r, g, b, a)
+    }
+}
+
+impl From<Color> for [f32; 4] {
+    fn from(color: Color) -> [f32; 4] {
+        [color.r, color.g, color.b, color.a]
+    }
+}
+
 impl Add for Color {
     type Output = Color;
 
@@ -299,6 +342,17 @@ mod tests {
         assert!(Color::try_hex("ZZZZZZ").is_err());
     }
 
+    #[test]
+    fn conversions() {
+        let color = Color::rgba(0.1, 0.2, 0.3, 0.4);
+
+        assert_eq!(color, Color::from((0.1, 0.2, 0.3, 0.4)));
+        assert_eq!(color, Color::from([0.1, 0.2, 0.3, 0.4]));
+
+        assert_eq!((0.1, 0.2, 0.3, 0.4), color.into());
+        assert_eq!([0.1, 0.2, 0.3, 0.4], <[f32; 4]>::from(color));
+    }
+
     #[test]
     fn ops() {
         assert_eq!(
@@ -0,0 +1,356 @@
+//! Functions for drawing simple shapes and text, for debugging purposes.
+//!
+//! Unlike the rest of the graphics API, these functions do not draw anything straight away.
+//! Instead, the shapes are queued up and then drawn on top of everything else when the
+//! frame is [presented](crate::graphics::present). They are always drawn directly to the
+//! window, in screen space, regardless of the active canvas, shader, blend mode, transform
+//! matrix or projection mode.
+//!
+//! The queue is cleared after each frame, so you will need to call these functions every
+//! frame for the shapes to stay visible.
+//!
+//! # Performance
+//!
+//! These functions are designed for convenience rather than speed - they're fine for
+//! visualizing hitboxes or printing a few values to the screen, but they're not a good fit
+//! for drawing your actual game.
+
+#[cfg(feature = "embedded_font")]
+use crate::graphics::text::{Font, Text};
+use crate::graphics::{self, ActiveCanvas, ActiveShader, BlendMode, Color, DrawParams, Rectangle};
+use crate::math::Vec2;
+use crate::Context;
+
+/// The size of the font used by [`draw_text`], in pixels.
+#[cfg(feature = "embedded_font")]
+const DEBUG_FONT_SIZE: f32 = 16.0;
+
+/// The size of the squares drawn by [`draw_point`], in pixels.
+const POINT_SIZE: f32 = 3.0;
+
+/// The height of the graph drawn by [`draw_frame_stats`], in pixels.
+const FRAME_GRAPH_HEIGHT: f32 = 50.0;
+
+/// The frame time (in seconds) that reaches the top of the graph drawn by [`draw_frame_stats`].
+const FRAME_GRAPH_MAX_TIME: f64 = 1.0 / 20.0;
+
+enum DebugCommand {
+    Line {
+        from: Vec2<f32>,
+        to: Vec2<f32>,
+        color: Color,
+    },
+    Rectangle {
+        rectangle: Rectangle,
+        color: Color,
+    },
+    Point {
+        position: Vec2<f32>,
+        color: Color,
+    },
+    FilledRectangle {
+        rectangle: Rectangle,
+        color: Color,
+    },
+    #[cfg(feature = "embedded_font")]
+    Text {
+        content: String,
+        position: Vec2<f32>,
+        color: Color,
+    },
+}
+
+/// The state of the text object used to draw debug text.
+#[cfg(feature = "embedded_font")]
+enum DebugText {
+    /// No text has been drawn yet, so the font has not been loaded.
+    Uninitialized,
+
+    /// The font was loaded successfully.
+    Ready(Text),
+
+    /// The font failed to load - this is remembered so that loading isn't retried every frame.
+    Failed,
+}
+
+pub(crate) struct DebugContext {
+    commands: Vec<DebugCommand>,
+
+    /// Reused for every piece of queued text, so that the font (and its glyph cache) only
+    /// needs to be created once.
+    #[cfg(feature = "embedded_font")]
+    text: DebugText,
+}
+
+impl DebugContext {
+    pub(crate) fn new() -> DebugContext {
+        DebugContext {
+            commands: Vec::new(),
+
+            #[cfg(feature = "embedded_font")]
+            text: DebugText::Uninitialized,
+        }
+    }
+}
+
+/// Queues a one pixel wide line between two points, in screen space.
+pub fn draw_line(ctx: &mut Context, from: Vec2<f32>, to: Vec2<f32>, color: Color) {
+    ctx.graphics
+        .debug
+        .commands
+        .push(DebugCommand::Line { from, to, color });
+}
+
+/// Queues the one pixel wide outline of a rectangle, in screen space.
+///
+/// The outline is drawn inside the bounds of the rectangle.
+pub fn draw_rect(ctx: &mut Context, rectangle: Rectangle, color: Color) {
+    ctx.graphics
+        .debug
+        .commands
+        .push(DebugCommand::Rectangle { rectangle, color });
+}
+
+/// Queues a small square, centered on the given point, in screen space.
+pub fn draw_point(ctx: &mut Context, position: Vec2<f32>, color: Color) {
+    ctx.graphics
+        .debug
+        .commands
+        .push(DebugCommand::Point { position, color });
+}
+
+/// Queues a piece of text, in screen space.
+///
+/// The position is the top left of the text. The text is drawn using the
+/// [default font](Font::default), so you do not need to load a font yourself. Newlines are
+/// supported.
+///
+/// This function is only available if the `embedded_font` feature is enabled.
+#[cfg(feature = "embedded_font")]
+pub fn draw_text<C>(ctx: &mut Context, content: C, position: Vec2<f32>, color: Color)
+where
+    C: Into<String>,
+{
+    ctx.graphics.debug.commands.push(DebugCommand::Text {
+        content: content.into(),
+        position,
+        color,
+    });
+}
+
+/// Queues an overlay showing the current frame rate, and a graph of recent frame times,
+/// in screen space.
+///
+/// The position is the top left of the overlay. Each bar of the graph represents a
+/// single frame, with the most recent frame on the right. Frames that took longer than
+/// 1/60th of a second are drawn in yellow, and frames that took longer than 1/30th of a
+/// second are drawn in red. The line across the graph marks 1/60th of a second.
+///
+/// If the `embedded_font` feature is enabled, the frame rate and the average frame time will be
+/// displayed as text above the graph, using the font that is embedded in Tetra.
+pub fn draw_frame_stats(ctx: &mut Context, position: Vec2<f32>) {
+    let frame_times: Vec<f64> = ctx.time.fps_tracker.iter().copied().collect();
+
+    let graph_width = frame_times.len() as f32;
+    let text_height = if cfg!(feature = "embedded_font") {
+        20.0
+    } else {
+        0.0
+    };
+
+    draw_filled_rect(
+        ctx,
+        Rectangle::new(
+            position.x,
+            position.y,
+            graph_width,
+            text_height + FRAME_GRAPH_HEIGHT,
+        ),
+        Color::rgba(0.0, 0.0, 0.0, 0.75),
+    );
+
+    #[cfg(feature = "embedded_font")]
+    {
+        let fps = crate::time::get_fps(ctx);
+
+        draw_text(
+            ctx,
+            format!("{:.0} FPS ({:.2} ms)", fps, 1000.0 / fps),
+            position + Vec2::new(4.0, 0.0),
+            Color::WHITE,
+        );
+    }
+
+    let bottom = position.y + text_height + FRAME_GRAPH_HEIGHT;
+
+    for (i, &frame_time) in frame_times.iter().enumerate() {
+        let height = (frame_time / FRAME_GRAPH_MAX_TIME).min(1.0) as f32 * FRAME_GRAPH_HEIGHT;
+
+        let color = if frame_time > 1.0 / 30.0 {
+            Color::RED
+        } else if frame_time > 1.0 / 60.0 {
+            Color::rgb(1.0, 1.0, 0.0)
+        } else {
+            Color::GREEN
+        };
+
+        draw_filled_rect(
+            ctx,
+            Rectangle::new(position.x + i as f32, bottom - height, 1.0, height),
+            color,
+        );
+    }
+
+    let target_y = bottom - (1.0 / 60.0 / FRAME_GRAPH_MAX_TIME) as f32 * FRAME_GRAPH_HEIGHT;
+
+    draw_line(
+        ctx,
+        Vec2::new(position.x, target_y),
+        Vec2::new(position.x + graph_width, target_y),
+        Color::rgba(1.0, 1.0, 1.0, 0.5),
+    );
+}
+
+fn draw_filled_rect(ctx: &mut Context, rectangle: Rectangle, color: Color) {
+    ctx.graphics
+        .debug
+        .commands
+        .push(DebugCommand::FilledRectangle { rectangle, color });
+}
+
+/// Draws any queued debug shapes to the window, and then clears the queue.
+pub(crate) fn draw_queued(ctx: &mut Context) {
+    if ctx.graphics.debug.commands.is_empty() {
+        return;
+    }
+
+    let mut commands = std::mem::take(&mut ctx.graphics.debug.commands);
+
+    let previous_canvas = ctx.graphics.canvas.clone();
+    let previous_shader = ctx.graphics.shader.clone();
+    let previous_blend_mode = ctx.graphics.blend_mode;
+    let previous_transform = ctx.graphics.transform_matrix;
+    let previous_projection_mode = ctx.graphics.projection_mode;
+
+    graphics::set_canvas_ex(ctx, ActiveCanvas::Window);
+    graphics::set_shader_ex(ctx, ActiveShader::Default);
+    graphics::set_blend_mode(ctx, BlendMode::default());
+    graphics::reset_transform_matrix(ctx);
+    graphics::reset_projection_mode(ctx);
+
+    for command in commands.drain(..) {
+        match command {
+            DebugCommand::Line { from, to, color } => {
+                let delta = to - from;
+
+                push_rectangle(
+                    ctx,
+                    DrawParams::new()
+                        .position(from)
+                        .origin(Vec2::new(0.0, 0.5))
+                        .scale(Vec2::new(delta.magnitude(), 1.0))
+                        .rotation(delta.y.atan2(delta.x))
+                        .color(color),
+                );
+            }
+
+            DebugCommand::Rectangle { rectangle, color } => {
+                let Rectangle {
+                    x,
+                    y,
+                    width,
+                    height,
+                } = rectangle;
+
+                let edges = [
+                    (x, y, width, 1.0),
+                    (x, y + height - 1.0, width, 1.0),
+                    (x, y + 1.0, 1.0, height - 2.0),
+                    (x + width - 1.0, y + 1.0, 1.0, height - 2.0),
+                ];
+
+                for &(x, y, width, height) in &edges {
+                    push_rectangle(
+                        ctx,
+                        DrawParams::new()
+                            .position(Vec2::new(x, y))
+                            .scale(Vec2::new(width, height.max(0.0)))
+                            .color(color),
+                    );
+                }
+            }
+
+            DebugCommand::Point { position, color } => {
+                push_rectangle(
+                    ctx,
+                    DrawParams::new()
+                        .position(position)
+                        .origin(Vec2::new(0.5, 0.5))
+                        .scale(Vec2::new(POINT_SIZE, POINT_SIZE))
+                        .color(color),
+                );
+            }
+
+            DebugCommand::FilledRectangle { rectangle, color } => {
+                push_rectangle(
+                    ctx,
+                    DrawParams::new()
+                        .position(Vec2::new(rectangle.x, rectangle.y))
+                        .scale(Vec2::new(rectangle.width, rectangle.height))
+                        .color(color),
+                );
+            }
+
+            #[cfg(feature = "embedded_font")]
+            DebugCommand::Text {
+                content,
+                position,
+                color,
+            } => {
+                // The text is taken out of the context while it's drawn, as drawing
+                // needs mutable access to the context.
+                let text = std::mem::replace(&mut ctx.graphics.debug.text, DebugText::Failed);
+
+                let text = match text {
+                    DebugText::Uninitialized => match Font::default(ctx, DEBUG_FONT_SIZE) {
+                        Ok(font) => DebugText::Ready(Text::new("", font)),
+                        Err(e) => {
+                            eprintln!(
+                                "Failed to load the debug font, text will not be drawn: {}",
+                                e
+                            );
+                            DebugText::Failed
+                        }
+                    },
+                    text => text,
+                };
+
+                if let DebugText::Ready(mut text) = text {
+                    text.set_content(content);
+                    text.draw(ctx, DrawParams::new().position(position).color(color));
+
+                    ctx.graphics.debug.text = DebugText::Ready(text);
+                }
+            }
+        }
+    }
+
+    graphics::flush(ctx);
+
+    graphics::set_projection_mode(ctx, previous_projection_mode);
+    graphics::set_transform_matrix(ctx, previous_transform);
+    graphics::set_blend_mode(ctx, previous_blend_mode);
+    graphics::set_shader_ex(ctx, previous_shader);
+    graphics::set_canvas_ex(ctx, previous_canvas);
+
+    // Hand the (now empty) buffer back, so that its allocation can be reused next frame.
+    ctx.graphics.debug.commands = commands;
+}
+
+/// Draws a 1x1 white quad, positioned and stretched via the given parameters.
+fn push_rectangle(ctx: &mut Context, params: DrawParams) {
+    let texture = ctx.graphics.default_texture.clone();
+
+    graphics::set_texture(ctx, &texture);
+    graphics::push_quad(ctx, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0, 1.0, &params);
+}
@@ -1,11 +1,66 @@
-use crate::graphics::Color;
-use crate::math::{Mat4, Vec2, Vec3};
+use crate::graphics::{Color, Rectangle};
+use crate::math::{Mat4, Transform2D, Vec2, Vec3};
+
+/// The ways in which the [`color`](DrawParams::color) of a [`DrawParams`] can be combined
+/// with the color of the graphic being drawn.
+///
+/// # Serde
+///
+/// Serialization and deserialization of this type (via [Serde](https://serde.rs/))
+/// can be enabled via the `serde_support` feature.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(
+    feature = "serde_support",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum ColorBlend {
+    /// The graphic's color will be multiplied by the color.
+    ///
+    /// This is the default behaviour, and is useful for tinting a graphic.
+    #[default]
+    Multiply,
+
+    /// The color will be added to the graphic's color, scaled by the color's alpha.
+    ///
+    /// The alpha of the graphic will be left unchanged. This is useful for flash or glow effects.
+    Add,
+
+    /// The graphic's color will be mixed with the color, by the given amount (between
+    /// `0.0` and `1.0`).
+    ///
+    /// The alpha of the graphic will be multiplied by the color's alpha. For example,
+    /// `ColorBlend::Mix(1.0)` with [`Color::WHITE`] will draw a white silhouette of the graphic.
+    Mix(f32),
+}
+
+impl ColorBlend {
+    /// Returns the representation of the blend that is passed to the shader.
+    pub(crate) fn to_uniform(self) -> Vec2<f32> {
+        match self {
+            ColorBlend::Multiply => Vec2::new(0.0, 0.0),
+            ColorBlend::Add => Vec2::new(1.0, 0.0),
+            ColorBlend::Mix(amount) => Vec2::new(2.0, amount),
+        }
+    }
+}
 
 /// Parameters that can be manipulated when drawing an object.
 ///
 /// You can either use this as a builder by calling [`DrawParams::new`] and then chaining methods, or
 /// construct it manually - whichever you find more pleasant to write.
+///
+/// # Serde
+///
+/// Serialization and deserialization of this type (via [Serde](https://serde.rs/))
+/// can be enabled via the `serde_support` feature.
+///
+/// When deserializing, any fields that are missing will be set to their default values.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde_support",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 pub struct DrawParams {
     /// The position that the graphic should be drawn at. Defaults to `(0.0, 0.0)`.
     pub position: Vec2<f32>,
@@ -22,11 +77,27 @@ pub struct DrawParams {
     /// relative to the center of the image.
     pub origin: Vec2<f32>,
 
+    /// The origin of the graphic, expressed as a fraction of its size. Defaults to `None`.
+    ///
+    /// If this is set, it will take precedence over `origin`. For example, an origin of
+    /// `[0.5, 0.5]` will be the center of the graphic, regardless of how big it is.
+    ///
+    /// This is resolved against the size of the graphic at the time it is drawn, so it is
+    /// not taken into account by [`to_matrix`](Self::to_matrix), as that does not have a known
+    /// size. When drawing a [`Mesh`](crate::graphics::mesh::Mesh), it is resolved against
+    /// the mesh's [bounds](crate::graphics::mesh::Mesh::bounds).
+    pub origin_normalized: Option<Vec2<f32>>,
+
     /// The rotation of the graphic, in radians. Defaults to `0.0`.
     pub rotation: f32,
 
     /// A color to multiply the graphic by. Defaults to [`Color::WHITE`].
+    ///
+    /// The way in which this is combined with the graphic can be changed via `color_blend`.
     pub color: Color,
+
+    /// How `color` should be combined with the graphic. Defaults to [`ColorBlend::Multiply`].
+    pub color_blend: ColorBlend,
 }
 
 impl DrawParams {
@@ -53,21 +124,46 @@ impl DrawParams {
         self
     }
 
+    /// Sets the origin of the graphic, as a fraction of its size.
+    ///
+    /// `[0.0, 0.0]` is the top left of the graphic, and `[1.0, 1.0]` is the bottom right.
+    pub fn origin_normalized(mut self, origin: Vec2<f32>) -> DrawParams {
+        self.origin_normalized = Some(origin);
+        self
+    }
+
     /// Sets the rotation of the graphic, in radians.
+    ///
+    /// If you would rather work in degrees, use
+    /// [`rotation_degrees`](Self::rotation_degrees) instead.
     pub fn rotation(mut self, rotation: f32) -> DrawParams {
         self.rotation = rotation;
         self
     }
 
+    /// Sets the rotation of the graphic, in degrees.
+    ///
+    /// The angle is converted to radians before being stored in the `rotation` field.
+    pub fn rotation_degrees(mut self, rotation: f32) -> DrawParams {
+        self.rotation = rotation.to_radians();
+        self
+    }
+
     /// Sets the color to multiply the graphic by.
     pub fn color(mut self, color: Color) -> DrawParams {
         self.color = color;
         self
     }
 
+    /// Sets how the color should be combined with the graphic.
+    pub fn color_blend(mut self, color_blend: ColorBlend) -> DrawParams {
+        self.color_blend = color_blend;
+        self
+    }
+
     /// Creates a new transformation matrix equivalent to this set of params.
     ///
-    /// This method does not take into account `color`, as it cannot
+    /// This method does not take into account `color` or `color_blend`, as they cannot
     /// be represented via a matrix.
     pub fn to_matrix(&self) -> Mat4<f32> {
         let mut matrix = Mat4::translation_2d(-self.origin);
@@ -76,6 +172,66 @@ impl DrawParams {
         matrix.translate_2d(self.position);
         matrix
     }
+
+    /// Returns the origin in pixels, resolving `origin_normalized` (if set) against
+    /// the given bounds.
+    pub(crate) fn resolve_origin(&self, bounds: Rectangle) -> Vec2<f32> {
+        match self.origin_normalized {
+            Some(origin) => Vec2::new(
+                bounds.x + bounds.width * origin.x,
+                bounds.y + bounds.height * origin.y,
+            ),
+            None => self.origin,
+        }
+    }
+
+    /// Maps a point from the graphic's own co-ordinate space through these params. This is
+    /// the inverse of [`to_local`](Self::to_local).
+    pub(crate) fn to_world(&self, point: Vec2<f32>, bounds: Rectangle) -> Vec2<f32> {
+        let origin = self.resolve_origin(bounds);
+        let offset = (point - origin) * self.scale;
+
+        let (sin, cos) = self.rotation.sin_cos();
+
+        let rotated = Vec2::new(
+            offset.x * cos - offset.y * sin,
+            offset.x * sin + offset.y * cos,
+        );
+
+        rotated + self.position
+    }
+
+    /// Maps a point back through the inverse of these params, giving its position relative
+    /// to the graphic's own co-ordinate space (i.e. the top left of the graphic will be `[0.0, 0.0]`).
+    ///
+    /// Returns `None` if the transformation cannot be inverted (e.g. if the scale is zero).
+    pub(crate) fn to_local(&self, point: Vec2<f32>, bounds: Rectangle) -> Option<Vec2<f32>> {
+        if self.scale.x == 0.0 || self.scale.y == 0.0 {
+            return None;
+        }
+
+        let origin = self.resolve_origin(bounds);
+        let offset = point - self.position;
+
+        let (sin, cos) = self.rotation.sin_cos();
+
+        let unrotated = Vec2::new(
+            offset.x * cos + offset.y * sin,
+            offset.y * cos - offset.x * sin,
+        );
+
+        Some(unrotated / self.scale + origin)
+    }
+
+    /// Converts `origin_normalized` (if set) into a pixel origin, using the given bounds.
+    ///
+    /// This is needed when a single graphic is made up of multiple quads, as otherwise the
+    /// origin would be resolved against each individual quad.
+    pub(crate) fn with_resolved_origin(mut self, bounds: Rectangle) -> DrawParams {
+        self.origin = self.resolve_origin(bounds);
+        self.origin_normalized = None;
+        self
+    }
 }
 
 impl Default for DrawParams {
@@ -84,8 +240,10 @@ impl Default for DrawParams {
             position: Vec2::new(0.0, 0.0),
             scale: Vec2::new(1.0, 1.0),
             origin: Vec2::new(0.0, 0.0),
+            origin_normalized: None,
             rotation: 0.0,
             color: Color::WHITE,
+            color_blend: ColorBlend::Multiply,
         }
     }
 }
@@ -99,8 +257,68 @@ impl From<Vec2<f32>> for DrawParams {
     }
 }
 
+impl From<Transform2D> for DrawParams {
+    fn from(transform: Transform2D) -> DrawParams {
+        DrawParams {
+            position: transform.position,
+            rotation: transform.rotation,
+            scale: transform.scale,
+            ..DrawParams::default()
+        }
+    }
+}
+
 impl From<DrawParams> for Mat4<f32> {
     fn from(params: DrawParams) -> Self {
         params.to_matrix()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::DrawParams;
+    use crate::graphics::Rectangle;
+    use crate::math::{Vec2, Vec4};
+
+    #[test]
+    fn to_local_inverts_matrix() {
+        let params = DrawParams::new()
+            .position(Vec2::new(100.0, 50.0))
+            .scale(Vec2::new(2.0, 3.0))
+            .origin(Vec2::new(8.0, 4.0))
+            .rotation(0.5);
+
+        let bounds = Rectangle::new(0.0, 0.0, 16.0, 16.0);
+
+        let local = Vec2::new(3.0, 12.0);
+        let world = params.to_matrix() * Vec4::new(local.x, local.y, 0.0, 1.0);
+        let result = params.to_local(world.xy(), bounds).unwrap();
+
+        assert!((result - local).magnitude() < 0.001);
+    }
+
+    #[test]
+    fn to_world_matches_matrix() {
+        let params = DrawParams::new()
+            .position(Vec2::new(100.0, 50.0))
+            .scale(Vec2::new(2.0, 3.0))
+            .origin(Vec2::new(8.0, 4.0))
+            .rotation(0.5);
+
+        let bounds = Rectangle::new(0.0, 0.0, 16.0, 16.0);
+
+        let local = Vec2::new(3.0, 12.0);
+        let expected = params.to_matrix() * Vec4::new(local.x, local.y, 0.0, 1.0);
+        let result = params.to_world(local, bounds);
+
+        assert!((result - expected.xy()).magnitude() < 0.001);
+    }
+
+    #[test]
+    fn to_local_with_zero_scale() {
+        let params = DrawParams::new().scale(Vec2::new(0.0, 1.0));
+        let bounds = Rectangle::new(0.0, 0.0, 16.0, 16.0);
+
+        assert_eq!(None, params.to_local(Vec2::zero(), bounds));
+    }
+}
@@ -0,0 +1,28 @@
+use crate::platform::RawFence;
+use crate::Context;
+
+/// A marker in the stream of commands sent to the graphics hardware, which can be used to
+/// check whether the GPU has finished all of the work that was queued before it.
+///
+/// Fences are created via [`graphics::fence`](crate::graphics::fence). Unlike
+/// [`graphics::finish`](crate::graphics::finish), checking a fence does not block, so it can
+/// be polled each frame - for example, to measure how long the GPU took to render a frame,
+/// or to avoid reading back data before it has been written.
+#[derive(Debug)]
+pub struct Fence {
+    pub(crate) handle: RawFence,
+}
+
+impl Fence {
+    /// Returns whether the GPU has finished all of the work that was queued before this
+    /// fence was created.
+    pub fn is_signaled(&self, ctx: &mut Context) -> bool {
+        ctx.device.is_fence_signaled(&self.handle)
+    }
+}
+
+impl PartialEq for Fence {
+    fn eq(&self, other: &Fence) -> bool {
+        self.handle == other.handle
+    }
+}
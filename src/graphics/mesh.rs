@@ -2,19 +2,22 @@
 
 pub use lyon_tessellation::path::builder::BorderRadii;
 
+use std::cell::Cell;
 use std::rc::Rc;
 
 use bytemuck::{Pod, Zeroable};
 use lyon_tessellation::geom::euclid::{Point2D, Size2D};
 use lyon_tessellation::math::{Angle, Point, Rect, Vector};
-use lyon_tessellation::path::builder::{Build, PathBuilder};
-use lyon_tessellation::path::{Polygon, Winding};
+use lyon_tessellation::path::builder::PathBuilder;
+use lyon_tessellation::path::iterator::PathIterator;
+use lyon_tessellation::path::{Path, PathEvent, Polygon, Winding};
 use lyon_tessellation::{
-    BuffersBuilder, FillOptions, FillTessellator, FillVertex, FillVertexConstructor, StrokeOptions,
-    StrokeTessellator, StrokeVertex, StrokeVertexConstructor, VertexBuffers,
+    BuffersBuilder, FillOptions, FillTessellator, FillVertex, FillVertexConstructor, Side,
+    StrokeOptions, StrokeTessellator, StrokeVertex, StrokeVertexConstructor, TessellationError,
+    VertexBuffers,
 };
 
-use crate::graphics::{self, ActiveCanvas, ActiveShader, Color, DrawParams, Rectangle, Texture};
+use crate::graphics::{self, ActiveShader, Color, DrawParams, Rectangle, Texture};
 use crate::math::Vec2;
 use crate::platform::{RawIndexBuffer, RawVertexBuffer};
 use crate::Context;
@@ -53,6 +56,107 @@ impl Vertex {
     }
 }
 
+/// The data types that can be used for a [`VertexAttribute`].
+///
+/// All of the components are 32-bit floating point numbers.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum VertexFormat {
+    /// A single `float`.
+    Float,
+
+    /// A `vec2`.
+    Vec2,
+
+    /// A `vec3`.
+    Vec3,
+
+    /// A `vec4`.
+    Vec4,
+}
+
+impl VertexFormat {
+    /// Returns the number of components in this format.
+    pub fn components(self) -> usize {
+        match self {
+            VertexFormat::Float => 1,
+            VertexFormat::Vec2 => 2,
+            VertexFormat::Vec3 => 3,
+            VertexFormat::Vec4 => 4,
+        }
+    }
+}
+
+/// An individual attribute within a [`VertexLayout`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VertexAttribute {
+    /// The name of the attribute, as it appears in the vertex shader (e.g. `a_position`).
+    pub name: String,
+
+    /// The data type of the attribute.
+    pub format: VertexFormat,
+}
+
+/// Describes the structure of custom vertex data.
+///
+/// By default, meshes use the layout of the [`Vertex`] type (a `vec2` position, a `vec2` texture
+/// co-ordinate and a `vec4` color). If your [`Shader`](crate::graphics::Shader) needs
+/// different per-vertex data, you can describe it with a `VertexLayout`, and then create a
+/// [`VertexBuffer`] from raw data via [`VertexBuffer::with_layout`].
+///
+/// Each vertex is made up of the attributes in the order they were added, tightly packed.
+/// Attributes are matched up with the inputs of the vertex shader by name, and any that the
+/// shader doesn't use will be skipped. You can check that a layout provides everything a
+/// shader needs via [`Shader::validate_vertex_layout`](crate::graphics::Shader::validate_vertex_layout).
+///
+/// # Examples
+///
+/// ```
+/// # use tetra::graphics::mesh::{VertexFormat, VertexLayout};
+/// let layout = VertexLayout::new()
+///     .with_attribute("a_position", VertexFormat::Vec2)
+///     .with_attribute("a_uv", VertexFormat::Vec2)
+///     .with_attribute("a_color", VertexFormat::Vec4)
+///     .with_attribute("a_uv2", VertexFormat::Vec2);
+///
+/// assert_eq!(10, layout.stride());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct VertexLayout {
+    attributes: Vec<VertexAttribute>,
+}
+
+impl VertexLayout {
+    /// Creates a new, empty vertex layout.
+    pub fn new() -> VertexLayout {
+        VertexLayout {
+            attributes: Vec::new(),
+        }
+    }
+
+    /// Adds an attribute to the end of the layout.
+    pub fn with_attribute<N>(mut self, name: N, format: VertexFormat) -> VertexLayout
+    where
+        N: Into<String>,
+    {
+        self.attributes.push(VertexAttribute {
+            name: name.into(),
+            format,
+        });
+
+        self
+    }
+
+    /// Returns the attributes in the layout.
+    pub fn attributes(&self) -> &[VertexAttribute] {
+        &self.attributes
+    }
+
+    /// Returns the number of floats that make up a single vertex.
+    pub fn stride(&self) -> usize {
+        self.attributes.iter().map(|a| a.format.components()).sum()
+    }
+}
+
 // SAFETY: While the contract for `Pod` states that all fields should also be `Pod`,
 // that isn't possible without upstream changes. All of the fields meet the
 // *requirements* to be `Pod`, however, so this should not be unsound.
@@ -93,6 +197,22 @@ impl VertexWinding {
     }
 }
 
+/// Which faces of a piece of geometry should be culled (not rendered).
+///
+/// Whether a face is considered to be front-facing or back-facing is determined by its
+/// [`VertexWinding`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CullMode {
+    /// No faces will be culled.
+    None,
+
+    /// Back-facing geometry will be culled.
+    Back,
+
+    /// Front-facing geometry will be culled.
+    Front,
+}
+
 /// Vertex data, stored in GPU memory.
 ///
 /// This data can be drawn to the screen via a [`Mesh`].
@@ -107,9 +227,19 @@ impl VertexWinding {
 /// This does mean, however, that updating a `VertexBuffer` will also update any other clones of
 /// that `VertexBuffer`.
 ///
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug)]
 pub struct VertexBuffer {
     handle: Rc<RawVertexBuffer>,
+
+    /// The bounds of the vertex positions, or `None` if they need to be recalculated.
+    bounds: Rc<Cell<Option<Rectangle>>>,
+}
+
+impl PartialEq for VertexBuffer {
+    fn eq(&self, other: &VertexBuffer) -> bool {
+        // The bounds are just a cache, so we can ignore them for equality checks.
+        self.handle.eq(&other.handle)
+    }
 }
 
 impl VertexBuffer {
@@ -146,9 +276,62 @@ impl VertexBuffer {
 
         Ok(VertexBuffer {
             handle: Rc::new(buffer),
+            bounds: Rc::new(Cell::new(vertex_bounds(vertices))),
+        })
+    }
+
+    /// Creates a new vertex buffer from raw data, with a custom layout.
+    ///
+    /// The data should contain a whole number of vertices, each of which is made up of the
+    /// attributes in the layout.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the underlying
+    /// graphics API encounters an error.
+    /// * [`TetraError::NotEnoughData`](crate::TetraError::NotEnoughData) will be returned if the
+    /// length of the data is not a multiple of the layout's [stride](VertexLayout::stride).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the layout has no attributes.
+    pub fn with_layout(
+        ctx: &mut Context,
+        layout: &VertexLayout,
+        data: &[f32],
+        usage: BufferUsage,
+    ) -> Result<VertexBuffer> {
+        let stride = layout.stride();
+
+        assert!(stride > 0, "vertex layout must have at least one attribute");
+
+        let count = data.len() / stride;
+
+        if count * stride != data.len() {
+            return Err(TetraError::NotEnoughData {
+                expected: (count + 1) * stride,
+                actual: data.len(),
+            });
+        }
+
+        let buffer = ctx
+            .device
+            .new_vertex_buffer_with_layout(count, layout.clone(), usage)?;
+
+        ctx.device.set_vertex_buffer_data(&buffer, data, 0);
+
+        Ok(VertexBuffer {
+            handle: Rc::new(buffer),
+            bounds: Rc::new(Cell::new(None)),
         })
     }
 
+    /// Returns the custom layout of the buffer's data, or [`None`] if it uses the
+    /// standard [`Vertex`] layout.
+    pub fn layout(&self) -> Option<&VertexLayout> {
+        self.handle.layout()
+    }
+
     /// Uploads new vertex data to the GPU.
     ///
     /// # Panics
@@ -157,6 +340,45 @@ impl VertexBuffer {
     pub fn set_data(&self, ctx: &mut Context, vertices: &[Vertex], offset: usize) {
         ctx.device
             .set_vertex_buffer_data(&self.handle, bytemuck::cast_slice(vertices), offset);
+
+        self.bounds.set(None);
+    }
+
+    /// Uploads new raw vertex data to the GPU.
+    ///
+    /// This is mainly useful for buffers with a custom [layout](VertexBuffer::with_layout).
+    /// The offset is measured in floats, rather than vertices.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the offset is out of bounds.
+    pub fn set_raw_data(&self, ctx: &mut Context, data: &[f32], offset: usize) {
+        ctx.device
+            .set_vertex_buffer_data(&self.handle, data, offset);
+
+        self.bounds.set(None);
+    }
+
+    /// Returns the axis-aligned bounding box of the vertex positions in the buffer.
+    ///
+    /// The bounds are cached, and will only be recalculated after the buffer's data has
+    /// been changed. Buffers created from [`Vertex`] data have their bounds calculated
+    /// upfront, but if the data is changed later on, recalculating the bounds requires
+    /// reading the data back from the GPU, which is slow.
+    ///
+    /// Returns `None` if the buffer is empty, or if it has a custom [layout](Self::with_layout)
+    /// (as the position of the vertices is not known).
+    pub fn bounds(&self, ctx: &mut Context) -> Option<Rectangle> {
+        if self.layout().is_some() {
+            return None;
+        }
+
+        if self.bounds.get().is_none() {
+            let data = ctx.device.get_vertex_buffer_data(&self.handle);
+            self.bounds.set(vertex_bounds(bytemuck::cast_slice(&data)));
+        }
+
+        self.bounds.get()
     }
 
     /// Creates a mesh using this buffer.
@@ -257,6 +479,49 @@ pub enum ShapeStyle {
     Stroke(f32),
 }
 
+/// A segment of a curved path, used by [`Mesh::bezier`] and [`GeometryBuilder::bezier`].
+///
+/// Each segment continues on from the end point of the previous segment.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum CurveSegment {
+    /// A straight line to the specified point.
+    Line(Vec2<f32>),
+
+    /// A quadratic Bézier curve to the specified point.
+    QuadraticBezier {
+        /// The control point of the curve.
+        control: Vec2<f32>,
+
+        /// The end point of the curve.
+        to: Vec2<f32>,
+    },
+
+    /// A cubic Bézier curve to the specified point.
+    CubicBezier {
+        /// The first control point of the curve.
+        control1: Vec2<f32>,
+
+        /// The second control point of the curve.
+        control2: Vec2<f32>,
+
+        /// The end point of the curve.
+        to: Vec2<f32>,
+    },
+
+    /// An elliptical arc around the specified center point, starting from the end point of the
+    /// previous segment.
+    Arc {
+        /// The center of the ellipse that the arc is a part of.
+        center: Vec2<f32>,
+
+        /// The horizontal and vertical radii of the ellipse.
+        radii: Vec2<f32>,
+
+        /// The angle that the arc should sweep through, in radians. Positive values sweep clockwise.
+        sweep_angle: f32,
+    },
+}
+
 /// A 2D mesh that can be drawn to the screen.
 ///
 /// A `Mesh` is a wrapper for a [`VertexBuffer`], which allows it to be drawn in combination with several
@@ -298,7 +563,7 @@ pub struct Mesh {
     texture: Option<Texture>,
     draw_range: Option<DrawRange>,
     winding: VertexWinding,
-    backface_culling: bool,
+    cull_mode: CullMode,
 }
 
 impl Mesh {
@@ -310,7 +575,7 @@ impl Mesh {
             texture: None,
             draw_range: None,
             winding: VertexWinding::CounterClockwise,
-            backface_culling: true,
+            cull_mode: CullMode::Back,
         }
     }
 
@@ -322,7 +587,7 @@ impl Mesh {
             texture: None,
             winding: VertexWinding::CounterClockwise,
             draw_range: None,
-            backface_culling: true,
+            cull_mode: CullMode::Back,
         }
     }
 
@@ -426,6 +691,51 @@ impl Mesh {
             .build_mesh(ctx)
     }
 
+    /// Creates a new polygon mesh, with holes cut out of it.
+    ///
+    /// If you need to draw multiple shapes, consider using [`GeometryBuilder`] to generate a combined mesh
+    /// instead.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::TessellationError`](crate::TetraError::TessellationError) will be returned if the shape
+    /// could not be turned into vertex data.
+    /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the underlying
+    /// graphics API encounters an error.
+    pub fn polygon_with_holes(
+        ctx: &mut Context,
+        style: ShapeStyle,
+        outline: &[Vec2<f32>],
+        holes: &[&[Vec2<f32>]],
+    ) -> Result<Mesh> {
+        GeometryBuilder::new()
+            .polygon_with_holes(style, outline, holes)?
+            .build_mesh(ctx)
+    }
+
+    /// Creates a new mesh from a curved path.
+    ///
+    /// If you need to draw multiple shapes, consider using [`GeometryBuilder`] to generate a combined mesh
+    /// instead. This will also allow you to configure the tolerance that is used when approximating
+    /// the curves.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::TessellationError`](crate::TetraError::TessellationError) will be returned if the shape
+    /// could not be turned into vertex data.
+    /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the underlying
+    /// graphics API encounters an error.
+    pub fn bezier(
+        ctx: &mut Context,
+        style: ShapeStyle,
+        start: Vec2<f32>,
+        segments: &[CurveSegment],
+    ) -> Result<Mesh> {
+        GeometryBuilder::new()
+            .bezier(style, start, segments)?
+            .build_mesh(ctx)
+    }
+
     /// Creates a new polyline mesh.
     ///
     /// If you need to draw multiple shapes, consider using [`GeometryBuilder`] to generate a combined mesh
@@ -443,13 +753,109 @@ impl Mesh {
             .build_mesh(ctx)
     }
 
+    /// Combines multiple meshes into a single mesh, so that they can be drawn with one draw call.
+    ///
+    /// This is useful for static geometry that is made up of lots of small meshes (e.g. the
+    /// tiles of a level), which would otherwise need a draw call each. The vertex data of
+    /// the meshes is copied as-is, so any positioning needs to have been baked into the
+    /// vertices - [`DrawParams`] can only be applied to the combined mesh as a whole.
+    ///
+    /// The combined mesh will always be indexed, and its buffers will be created with the
+    /// [`BufferUsage::Static`] usage hint. Only the vertices within each mesh's draw range
+    /// will be drawn. The texture, winding and cull mode will be taken from the first mesh.
+    ///
+    /// # Performance
+    ///
+    /// This reads the data of each mesh back from the GPU, so it is slow - combine your
+    /// meshes once upfront, rather than every frame.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the underlying
+    /// graphics API encounters an error.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no meshes are provided, or if the meshes do not all have the same texture
+    /// and vertex layout.
+    pub fn combine(ctx: &mut Context, meshes: &[&Mesh]) -> Result<Mesh> {
+        let first = meshes.first().expect("at least one mesh must be provided");
+        let layout = first.vertex_buffer.layout();
+
+        assert!(
+            meshes
+                .iter()
+                .all(|m| m.texture == first.texture && m.vertex_buffer.layout() == layout),
+            "meshes must have the same texture and vertex layout to be combined"
+        );
+
+        let stride = first.vertex_buffer.handle.stride();
+
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+
+        for mesh in meshes {
+            let mesh_vertices = ctx
+                .device
+                .get_vertex_buffer_data(&mesh.vertex_buffer.handle);
+
+            let mesh_indices = mesh
+                .index_buffer
+                .as_ref()
+                .map(|b| ctx.device.get_index_buffer_data(&b.handle));
+
+            append_mesh_data(
+                &mut vertices,
+                &mut indices,
+                stride,
+                &mesh_vertices,
+                mesh_indices.as_deref(),
+                mesh.draw_range,
+            );
+        }
+
+        let vertex_buffer = match layout {
+            Some(layout) => VertexBuffer::with_layout(ctx, layout, &vertices, BufferUsage::Static)?,
+            None => {
+                VertexBuffer::with_usage(ctx, bytemuck::cast_slice(&vertices), BufferUsage::Static)?
+            }
+        };
+
+        let index_buffer = IndexBuffer::with_usage(ctx, &indices, BufferUsage::Static)?;
+
+        Ok(Mesh {
+            vertex_buffer,
+            index_buffer: Some(index_buffer),
+            texture: first.texture.clone(),
+            draw_range: None,
+            winding: first.winding,
+            cull_mode: first.cull_mode,
+        })
+    }
+
     /// Draws the mesh to the screen (or to a canvas, if one is enabled).
+    ///
+    /// The `params` are applied to the mesh in the same way as they would be to a
+    /// [`Texture`] - the vertices are offset by the origin, scaled, rotated and then
+    /// positioned, and the color is blended with the vertex colors.
+    ///
+    /// If [`origin_normalized`](DrawParams::origin_normalized) is set, it will be resolved
+    /// against the mesh's [bounds](Self::bounds). For meshes with a custom vertex layout
+    /// (where the bounds cannot be calculated), the pixel `origin` will be used instead.
     pub fn draw<P>(&self, ctx: &mut Context, params: P)
     where
         P: Into<DrawParams>,
     {
         graphics::flush(ctx);
 
+        let mut params = params.into();
+
+        if params.origin_normalized.is_some() {
+            if let Some(bounds) = self.bounds(ctx) {
+                params = params.with_resolved_origin(bounds);
+            }
+        }
+
         let texture = match &self.texture {
             Some(t) => t,
             None => &ctx.graphics.default_texture,
@@ -460,7 +866,6 @@ impl Mesh {
             ActiveShader::User(s) => s,
         };
 
-        let params = params.into();
         let model_matrix = params.to_matrix();
 
         // TODO: Failing to apply the defaults should be handled more gracefully than this,
@@ -469,19 +874,19 @@ impl Mesh {
             &mut ctx.device,
             ctx.graphics.projection_matrix * ctx.graphics.transform_matrix * model_matrix,
             params.color,
+            params.color_blend,
         );
 
-        ctx.device.cull_face(self.backface_culling);
+        ctx.device.cull_face(self.cull_mode);
 
-        // Because canvas rendering is effectively done upside-down, the winding order is the opposite
-        // of what you'd expect in that case.
-        ctx.device.front_face(match &ctx.graphics.canvas {
-            ActiveCanvas::Window => self.winding,
-            ActiveCanvas::User(_) => self.winding.flipped(),
-        });
+        ctx.device
+            .front_face(graphics::front_face_winding(ctx, self.winding));
 
         let draw_range = self.draw_range.map(|r| (r.start, r.count));
 
+        graphics::apply_sampler(&mut ctx.device, ctx.graphics.sampler, texture);
+        graphics::invalidate_canvas_alpha_mask(ctx);
+
         match &self.index_buffer {
             Some(index_buffer) => {
                 let (start, count) = draw_range.unwrap_or_else(|| (0, index_buffer.handle.count()));
@@ -508,6 +913,20 @@ impl Mesh {
                 );
             }
         }
+
+        graphics::restore_sampler(&mut ctx.device, ctx.graphics.sampler, texture);
+    }
+
+    /// Returns the axis-aligned bounding box of the mesh's vertices, before any
+    /// [`DrawParams`] are applied.
+    ///
+    /// This can be used to cull meshes that are off-screen, or to position a mesh relative
+    /// to its extents. All of the vertices in the buffer are taken into account, regardless
+    /// of the index buffer or draw range.
+    ///
+    /// See [`VertexBuffer::bounds`] for details of how the bounds are calculated and cached.
+    pub fn bounds(&self, ctx: &mut Context) -> Option<Rectangle> {
+        self.vertex_buffer.bounds(ctx)
     }
 
     /// Gets a reference to the vertex buffer contained within this mesh.
@@ -580,8 +999,11 @@ impl Mesh {
     /// considered front-facing, and clockwise vertices are considered back-facing.
     /// This can be modified via [`set_backface_culling`](Self::set_backface_culling) and
     /// [`set_front_face_winding`](Self::set_front_face_winding).
+    ///
+    /// This is a shortcut for checking whether the [`cull_mode`](Self::cull_mode) is
+    /// [`CullMode::Back`].
     pub fn backface_culling(&self) -> bool {
-        self.backface_culling
+        self.cull_mode == CullMode::Back
     }
 
     /// Sets whether or not this mesh will cull (not render) back-facing geometry.
@@ -589,8 +1011,32 @@ impl Mesh {
     /// By default, backface culling is enabled, counter-clockwise vertices are
     /// considered front-facing, and clockwise vertices are considered back-facing.
     /// This can be modified via this function and [`set_front_face_winding`](Self::set_front_face_winding).
+    ///
+    /// This is a shortcut for setting the [`cull_mode`](Self::set_cull_mode) to
+    /// [`CullMode::Back`] or [`CullMode::None`].
     pub fn set_backface_culling(&mut self, enabled: bool) {
-        self.backface_culling = enabled;
+        self.cull_mode = if enabled {
+            CullMode::Back
+        } else {
+            CullMode::None
+        };
+    }
+
+    /// Returns which faces of this mesh will be culled (not rendered).
+    ///
+    /// The default cull mode is [`CullMode::Back`].
+    pub fn cull_mode(&self) -> CullMode {
+        self.cull_mode
+    }
+
+    /// Sets which faces of this mesh will be culled (not rendered).
+    ///
+    /// Which faces are considered front-facing can be changed via
+    /// [`set_front_face_winding`](Self::set_front_face_winding).
+    ///
+    /// The default cull mode is [`CullMode::Back`].
+    pub fn set_cull_mode(&mut self, cull_mode: CullMode) {
+        self.cull_mode = cull_mode;
     }
 
     /// Sets the range of vertices (or indices, if the mesh is indexed) that should be included
@@ -608,6 +1054,55 @@ impl Mesh {
     }
 }
 
+/// Calculates the bounding box of a set of vertices.
+fn vertex_bounds(vertices: &[Vertex]) -> Option<Rectangle> {
+    let first = vertices.first()?.position;
+
+    let (min, max) = vertices.iter().fold((first, first), |(min, max), v| {
+        (
+            Vec2::partial_min(min, v.position),
+            Vec2::partial_max(max, v.position),
+        )
+    });
+
+    Some(Rectangle::new(min.x, min.y, max.x - min.x, max.y - min.y))
+}
+
+/// Appends a mesh's vertex data to a combined buffer, adding indices for the parts of the
+/// mesh that fall within its draw range.
+fn append_mesh_data(
+    vertices: &mut Vec<f32>,
+    indices: &mut Vec<u32>,
+    stride: usize,
+    mesh_vertices: &[f32],
+    mesh_indices: Option<&[u32]>,
+    draw_range: Option<DrawRange>,
+) {
+    let base = (vertices.len() / stride) as u32;
+    let vertex_count = mesh_vertices.len() / stride;
+
+    vertices.extend_from_slice(mesh_vertices);
+
+    match mesh_indices {
+        Some(mesh_indices) => {
+            let range = match draw_range {
+                Some(r) => r.start..(r.start + r.count).min(mesh_indices.len()),
+                None => 0..mesh_indices.len(),
+            };
+
+            indices.extend(mesh_indices[range].iter().map(|i| base + i));
+        }
+        None => {
+            let range = match draw_range {
+                Some(r) => r.start..(r.start + r.count).min(vertex_count),
+                None => 0..vertex_count,
+            };
+
+            indices.extend(range.map(|i| base + i as u32));
+        }
+    }
+}
+
 impl From<VertexBuffer> for Mesh {
     fn from(buffer: VertexBuffer) -> Self {
         Mesh::new(buffer)
@@ -621,6 +1116,78 @@ fn to_lyon_rect(rectangle: Rectangle) -> Rect {
     )
 }
 
+fn to_lyon_polygon_points(points: &[Vec2<f32>]) -> Result<Vec<Point>> {
+    if points.len() < 3 || points.iter().any(|p| !p.x.is_finite() || !p.y.is_finite()) {
+        return Err(TetraError::TessellationError(
+            TessellationError::InvalidVertex,
+        ));
+    }
+
+    Ok(points
+        .iter()
+        .map(|point| Point::new(point.x, point.y))
+        .collect())
+}
+
+fn to_lyon_path(start: Vec2<f32>, segments: &[CurveSegment]) -> Result<Path> {
+    fn to_point(point: Vec2<f32>) -> Result<Point> {
+        if point.x.is_finite() && point.y.is_finite() {
+            Ok(Point::new(point.x, point.y))
+        } else {
+            Err(TetraError::TessellationError(
+                TessellationError::InvalidVertex,
+            ))
+        }
+    }
+
+    let mut builder = Path::svg_builder();
+
+    builder.move_to(to_point(start)?);
+
+    for segment in segments {
+        match *segment {
+            CurveSegment::Line(to) => {
+                builder.line_to(to_point(to)?);
+            }
+
+            CurveSegment::QuadraticBezier { control, to } => {
+                builder.quadratic_bezier_to(to_point(control)?, to_point(to)?);
+            }
+
+            CurveSegment::CubicBezier {
+                control1,
+                control2,
+                to,
+            } => {
+                builder.cubic_bezier_to(to_point(control1)?, to_point(control2)?, to_point(to)?);
+            }
+
+            CurveSegment::Arc {
+                center,
+                radii,
+                sweep_angle,
+            } => {
+                if !sweep_angle.is_finite() {
+                    return Err(TetraError::TessellationError(
+                        TessellationError::InvalidVertex,
+                    ));
+                }
+
+                let radii = to_point(radii)?;
+
+                builder.arc(
+                    to_point(center)?,
+                    Vector::new(radii.x, radii.y),
+                    Angle::radians(sweep_angle),
+                    Angle::radians(0.0),
+                );
+            }
+        }
+    }
+
+    Ok(builder.build())
+}
+
 struct TetraVertexConstructor(Color);
 
 impl FillVertexConstructor<Vertex> for TetraVertexConstructor {
@@ -639,6 +1206,164 @@ impl StrokeVertexConstructor<Vertex> for TetraVertexConstructor {
     }
 }
 
+#[derive(Copy, Clone)]
+enum StrokePart {
+    Core,
+    Fringe(Side),
+}
+
+struct FeatheredStrokeVertexConstructor {
+    color: Color,
+    half_width: f32,
+    feathering: f32,
+    part: StrokePart,
+}
+
+impl StrokeVertexConstructor<Vertex> for FeatheredStrokeVertexConstructor {
+    fn new_vertex(&mut self, vertex: StrokeVertex) -> Vertex {
+        // The normal is scaled so that it points to the edge of a stroke with a width
+        // of 2.0, on the vertex's side of the path.
+        let inner = (self.half_width - self.feathering / 2.0).max(0.0);
+        let outer = self.half_width + self.feathering / 2.0;
+
+        let (distance, color) = match self.part {
+            StrokePart::Core => (inner, self.color),
+            StrokePart::Fringe(side) if side == vertex.side() => {
+                (outer, self.color.with_alpha(0.0))
+            }
+            StrokePart::Fringe(_) => (-inner, self.color),
+        };
+
+        let position = vertex.position_on_path() + vertex.normal() * distance;
+
+        Vertex::new(Vec2::new(position.x, position.y), Vec2::zero(), color)
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+struct StrokeDash {
+    dash_length: f32,
+    gap_length: f32,
+    phase: f32,
+}
+
+/// The smallest tolerance that can be passed to [`GeometryBuilder::set_tolerance`].
+const MIN_TOLERANCE: f32 = 0.001;
+
+fn dash_path(path: &Path, tolerance: f32, dash: StrokeDash) -> Path {
+    let mut builder = Path::builder();
+    let mut contour = Vec::new();
+
+    for event in path.iter().flattened(tolerance) {
+        match event {
+            PathEvent::Begin { at } => {
+                contour.clear();
+                contour.push(at);
+            }
+
+            PathEvent::Line { to, .. } => {
+                contour.push(to);
+            }
+
+            PathEvent::End { first, close, .. } => {
+                if close {
+                    contour.push(first);
+                }
+
+                for points in dash_polyline(&contour, close, dash) {
+                    builder.add_polygon(Polygon {
+                        points: &points,
+                        closed: false,
+                    });
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    builder.build()
+}
+
+fn dash_polyline(points: &[Point], closed: bool, dash: StrokeDash) -> Vec<Vec<Point>> {
+    let mut dashes = Vec::new();
+
+    if points.is_empty() {
+        return dashes;
+    }
+
+    let period = dash.dash_length + dash.gap_length;
+
+    // How far we currently are into the pattern, and whether that is within a dash.
+    // Due to rounding, `rem_euclid` can return `period` itself for small negative phases.
+    let mut position = dash.phase.rem_euclid(period);
+
+    if position >= period {
+        position = 0.0;
+    }
+
+    let mut drawing = position < dash.dash_length;
+
+    let started_in_dash = drawing;
+    let mut current = Vec::new();
+
+    if drawing {
+        current.push(points[0]);
+    }
+
+    for segment in points.windows(2) {
+        let (from, to) = (segment[0], segment[1]);
+        let length = (to - from).length();
+        let mut travelled = 0.0;
+
+        // Zero-length segments can't affect the pattern, and would cause a division by zero.
+        if length == 0.0 {
+            continue;
+        }
+
+        loop {
+            let boundary = if drawing { dash.dash_length } else { period };
+            let remaining = boundary - position;
+
+            if remaining > length - travelled {
+                position += length - travelled;
+
+                if drawing && travelled < length {
+                    current.push(to);
+                }
+
+                break;
+            }
+
+            travelled += remaining;
+            current.push(from.lerp(to, travelled / length));
+
+            if drawing {
+                dashes.push(std::mem::take(&mut current));
+                position = dash.dash_length;
+            } else {
+                position = 0.0;
+            }
+
+            drawing = !drawing;
+        }
+    }
+
+    if drawing && current.len() > 1 {
+        dashes.push(current);
+
+        // If a closed contour begins and ends in the middle of a dash, the two halves
+        // are joined, so that the seam isn't visible.
+        if closed && started_in_dash && dashes.len() > 1 {
+            let first = dashes.remove(0);
+            let last = dashes.last_mut().unwrap();
+            last.extend(first.into_iter().skip(1));
+        }
+    }
+
+    dashes
+}
+
 /// A builder for creating primitive shape geometry, and associated buffers/meshes.
 ///
 /// # Performance
@@ -656,6 +1381,9 @@ impl StrokeVertexConstructor<Vertex> for TetraVertexConstructor {
 pub struct GeometryBuilder {
     data: VertexBuffers<Vertex, u32>,
     color: Color,
+    tolerance: f32,
+    feathering: f32,
+    dash: Option<StrokeDash>,
 }
 
 impl GeometryBuilder {
@@ -664,6 +1392,9 @@ impl GeometryBuilder {
         GeometryBuilder {
             data: VertexBuffers::new(),
             color: Color::WHITE,
+            tolerance: FillOptions::DEFAULT_TOLERANCE,
+            feathering: 0.0,
+            dash: None,
         }
     }
 
@@ -678,11 +1409,11 @@ impl GeometryBuilder {
         style: ShapeStyle,
         rectangle: Rectangle,
     ) -> Result<&mut GeometryBuilder> {
-        let mut builder = BuffersBuilder::new(&mut self.data, TetraVertexConstructor(self.color));
-
         match style {
             ShapeStyle::Fill => {
-                let options = FillOptions::default();
+                let mut builder =
+                    BuffersBuilder::new(&mut self.data, TetraVertexConstructor(self.color));
+                let options = FillOptions::tolerance(self.tolerance);
                 let mut tessellator = FillTessellator::new();
                 tessellator
                     .tessellate_rectangle(&to_lyon_rect(rectangle), &options, &mut builder)
@@ -690,11 +1421,10 @@ impl GeometryBuilder {
             }
 
             ShapeStyle::Stroke(width) => {
-                let options = StrokeOptions::default().with_line_width(width);
-                let mut tessellator = StrokeTessellator::new();
-                tessellator
-                    .tessellate_rectangle(&to_lyon_rect(rectangle), &options, &mut builder)
-                    .map_err(TetraError::TessellationError)?;
+                let mut builder = Path::builder();
+                builder.add_rectangle(&to_lyon_rect(rectangle), Winding::Positive);
+
+                self.stroke(width, &builder.build())?;
             }
         }
 
@@ -713,11 +1443,11 @@ impl GeometryBuilder {
         rectangle: Rectangle,
         radii: BorderRadii,
     ) -> Result<&mut GeometryBuilder> {
-        let mut builder = BuffersBuilder::new(&mut self.data, TetraVertexConstructor(self.color));
-
         match style {
             ShapeStyle::Fill => {
-                let options = FillOptions::default();
+                let mut builder =
+                    BuffersBuilder::new(&mut self.data, TetraVertexConstructor(self.color));
+                let options = FillOptions::tolerance(self.tolerance);
                 let mut tessellator = FillTessellator::new();
                 let mut builder = tessellator.builder(&options, &mut builder);
                 builder.add_rounded_rectangle(&to_lyon_rect(rectangle), &radii, Winding::Positive);
@@ -725,11 +1455,10 @@ impl GeometryBuilder {
             }
 
             ShapeStyle::Stroke(width) => {
-                let options = StrokeOptions::default().with_line_width(width);
-                let mut tessellator = StrokeTessellator::new();
-                let mut builder = tessellator.builder(&options, &mut builder);
+                let mut builder = Path::builder();
                 builder.add_rounded_rectangle(&to_lyon_rect(rectangle), &radii, Winding::Positive);
-                builder.build().map_err(TetraError::TessellationError)?;
+
+                self.stroke(width, &builder.build())?;
             }
         }
 
@@ -748,11 +1477,11 @@ impl GeometryBuilder {
         center: Vec2<f32>,
         radius: f32,
     ) -> Result<&mut GeometryBuilder> {
-        let mut builder = BuffersBuilder::new(&mut self.data, TetraVertexConstructor(self.color));
-
         match style {
             ShapeStyle::Fill => {
-                let options = FillOptions::default();
+                let mut builder =
+                    BuffersBuilder::new(&mut self.data, TetraVertexConstructor(self.color));
+                let options = FillOptions::tolerance(self.tolerance);
                 let mut tessellator = FillTessellator::new();
 
                 tessellator
@@ -766,17 +1495,10 @@ impl GeometryBuilder {
             }
 
             ShapeStyle::Stroke(width) => {
-                let options = StrokeOptions::default().with_line_width(width);
-                let mut tessellator = StrokeTessellator::new();
+                let mut builder = Path::builder();
+                builder.add_circle(Point::new(center.x, center.y), radius, Winding::Positive);
 
-                tessellator
-                    .tessellate_circle(
-                        Point::new(center.x, center.y),
-                        radius,
-                        &options,
-                        &mut builder,
-                    )
-                    .map_err(TetraError::TessellationError)?;
+                self.stroke(width, &builder.build())?;
             }
         }
 
@@ -795,11 +1517,11 @@ impl GeometryBuilder {
         center: Vec2<f32>,
         radii: Vec2<f32>,
     ) -> Result<&mut GeometryBuilder> {
-        let mut builder = BuffersBuilder::new(&mut self.data, TetraVertexConstructor(self.color));
-
         match style {
             ShapeStyle::Fill => {
-                let options = FillOptions::default();
+                let mut builder =
+                    BuffersBuilder::new(&mut self.data, TetraVertexConstructor(self.color));
+                let options = FillOptions::tolerance(self.tolerance);
                 let mut tessellator = FillTessellator::new();
 
                 tessellator
@@ -815,19 +1537,15 @@ impl GeometryBuilder {
             }
 
             ShapeStyle::Stroke(width) => {
-                let options = StrokeOptions::default().with_line_width(width);
-                let mut tessellator = StrokeTessellator::new();
+                let mut builder = Path::builder();
+                builder.add_ellipse(
+                    Point::new(center.x, center.y),
+                    Vector::new(radii.x, radii.y),
+                    Angle::radians(0.0),
+                    Winding::Positive,
+                );
 
-                tessellator
-                    .tessellate_ellipse(
-                        Point::new(center.x, center.y),
-                        Vector::new(radii.x, radii.y),
-                        Angle::radians(0.0),
-                        Winding::Positive,
-                        &options,
-                        &mut builder,
-                    )
-                    .map_err(TetraError::TessellationError)?;
+                self.stroke(width, &builder.build())?;
             }
         }
 
@@ -836,21 +1554,20 @@ impl GeometryBuilder {
 
     /// Adds a polygon.
     ///
+    /// The polygon can be concave, and will be filled using the even-odd rule if it
+    /// intersects itself.
+    ///
     /// # Errors
     ///
     /// * [`TetraError::TessellationError`](crate::TetraError::TessellationError) will be returned if the shape
-    /// could not be turned into vertex data.
+    /// could not be turned into vertex data. This includes the case where fewer than three points are
+    /// provided, or where any of the points are not finite.
     pub fn polygon(
         &mut self,
         style: ShapeStyle,
         points: &[Vec2<f32>],
     ) -> Result<&mut GeometryBuilder> {
-        let mut builder = BuffersBuilder::new(&mut self.data, TetraVertexConstructor(self.color));
-
-        let points: Vec<Point> = points
-            .iter()
-            .map(|point| Point::new(point.x, point.y))
-            .collect();
+        let points = to_lyon_polygon_points(points)?;
 
         let polygon = Polygon {
             points: &points,
@@ -859,7 +1576,9 @@ impl GeometryBuilder {
 
         match style {
             ShapeStyle::Fill => {
-                let options = FillOptions::default();
+                let mut builder =
+                    BuffersBuilder::new(&mut self.data, TetraVertexConstructor(self.color));
+                let options = FillOptions::tolerance(self.tolerance);
                 let mut tessellator = FillTessellator::new();
 
                 tessellator
@@ -868,12 +1587,71 @@ impl GeometryBuilder {
             }
 
             ShapeStyle::Stroke(width) => {
-                let options = StrokeOptions::default().with_line_width(width);
-                let mut tessellator = StrokeTessellator::new();
+                let mut builder = Path::builder();
+                builder.add_polygon(polygon);
 
-                tessellator
-                    .tessellate_polygon(polygon, &options, &mut builder)
-                    .map_err(TetraError::TessellationError)?;
+                self.stroke(width, &builder.build())?;
+            }
+        }
+
+        Ok(self)
+    }
+
+    /// Adds a polygon, with holes cut out of it.
+    ///
+    /// The outline and holes can be concave, and the holes can be specified in either
+    /// winding order. Any areas where the shapes overlap will be filled using the even-odd rule.
+    ///
+    /// If the style is [`ShapeStyle::Stroke`], the outline and each of the holes will be stroked.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::TessellationError`](crate::TetraError::TessellationError) will be returned if the shape
+    /// could not be turned into vertex data. This includes the case where fewer than three points are
+    /// provided for the outline or for any of the holes, or where any of the points are not finite.
+    pub fn polygon_with_holes(
+        &mut self,
+        style: ShapeStyle,
+        outline: &[Vec2<f32>],
+        holes: &[&[Vec2<f32>]],
+    ) -> Result<&mut GeometryBuilder> {
+        let mut contours = Vec::with_capacity(holes.len() + 1);
+
+        contours.push(to_lyon_polygon_points(outline)?);
+
+        for hole in holes {
+            contours.push(to_lyon_polygon_points(hole)?);
+        }
+
+        match style {
+            ShapeStyle::Fill => {
+                let mut builder =
+                    BuffersBuilder::new(&mut self.data, TetraVertexConstructor(self.color));
+                let options = FillOptions::tolerance(self.tolerance);
+                let mut tessellator = FillTessellator::new();
+                let mut builder = tessellator.builder(&options, &mut builder);
+
+                for points in &contours {
+                    builder.add_polygon(Polygon {
+                        points,
+                        closed: true,
+                    });
+                }
+
+                builder.build().map_err(TetraError::TessellationError)?;
+            }
+
+            ShapeStyle::Stroke(width) => {
+                let mut builder = Path::builder();
+
+                for points in &contours {
+                    builder.add_polygon(Polygon {
+                        points,
+                        closed: true,
+                    });
+                }
+
+                self.stroke(width, &builder.build())?;
             }
         }
 
@@ -891,8 +1669,6 @@ impl GeometryBuilder {
         stroke_width: f32,
         points: &[Vec2<f32>],
     ) -> Result<&mut GeometryBuilder> {
-        let mut builder = BuffersBuilder::new(&mut self.data, TetraVertexConstructor(self.color));
-
         let points: Vec<Point> = points
             .iter()
             .map(|point| Point::new(point.x, point.y))
@@ -903,16 +1679,71 @@ impl GeometryBuilder {
             closed: false,
         };
 
-        let options = StrokeOptions::default().with_line_width(stroke_width);
-        let mut tessellator = StrokeTessellator::new();
+        let mut builder = Path::builder();
+        builder.add_polygon(polygon);
+
+        self.stroke(stroke_width, &builder.build())?;
 
-        tessellator
-            .tessellate_polygon(polygon, &options, &mut builder)
-            .map_err(TetraError::TessellationError)?;
+        Ok(self)
+    }
+
+    /// Adds a curved path, made up of straight lines, Bézier curves and arcs.
+    ///
+    /// The path begins at `start`, and each segment continues on from the end of the previous one.
+    /// If the style is [`ShapeStyle::Fill`], the path will be closed automatically - otherwise,
+    /// it will be left open.
+    ///
+    /// The curves will be approximated using straight lines - the accuracy of this approximation
+    /// can be configured via [`set_tolerance`](Self::set_tolerance).
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::TessellationError`](crate::TetraError::TessellationError) will be returned if the shape
+    /// could not be turned into vertex data. This includes the case where any of the points are not finite.
+    pub fn bezier(
+        &mut self,
+        style: ShapeStyle,
+        start: Vec2<f32>,
+        segments: &[CurveSegment],
+    ) -> Result<&mut GeometryBuilder> {
+        let path = to_lyon_path(start, segments)?;
+
+        match style {
+            ShapeStyle::Fill => {
+                let mut builder =
+                    BuffersBuilder::new(&mut self.data, TetraVertexConstructor(self.color));
+                let options = FillOptions::tolerance(self.tolerance);
+                let mut tessellator = FillTessellator::new();
+
+                tessellator
+                    .tessellate_path(&path, &options, &mut builder)
+                    .map_err(TetraError::TessellationError)?;
+            }
+
+            ShapeStyle::Stroke(width) => {
+                self.stroke(width, &path)?;
+            }
+        }
 
         Ok(self)
     }
 
+    /// Sets the width of the anti-aliased edge that will be added to subsequent stroked shapes.
+    ///
+    /// When this is greater than zero, the edges of each stroke will fade out to transparent
+    /// over the given distance, centered on the edge of the line. This makes strokes look smooth
+    /// without requiring multisampling, at the cost of generating roughly three times as much
+    /// geometry. A value of `1.0` is usually a good choice for lines that are drawn at their
+    /// native scale.
+    ///
+    /// Filled shapes are not affected by this setting.
+    ///
+    /// Defaults to `0.0` (no feathering).
+    pub fn set_stroke_feathering(&mut self, feathering: f32) -> &mut GeometryBuilder {
+        self.feathering = feathering.max(0.0);
+        self
+    }
+
     /// Sets the color that will be used for subsequent shapes.
     ///
     /// You can also use [`DrawParams::color`](super::DrawParams) to tint an entire mesh -
@@ -923,6 +1754,104 @@ impl GeometryBuilder {
         self
     }
 
+    /// Sets the tolerance that will be used when approximating curves in subsequent shapes.
+    ///
+    /// This is the maximum distance that the generated geometry is allowed to deviate from the
+    /// true curve. Smaller values will produce smoother curves, at the cost of generating more
+    /// vertices.
+    ///
+    /// The tolerance must be positive - values smaller than `0.001` (including zero, negative
+    /// numbers and NaN) will be clamped to `0.001`, as they would otherwise cause the
+    /// tessellator to generate an unbounded number of vertices.
+    ///
+    /// Defaults to `0.1`.
+    pub fn set_tolerance(&mut self, tolerance: f32) -> &mut GeometryBuilder {
+        // `f32::max` returns the other value if one side is NaN.
+        self.tolerance = tolerance.max(MIN_TOLERANCE);
+        self
+    }
+
+    /// Sets a dash pattern that will be applied to subsequent stroked shapes.
+    ///
+    /// Each stroke will alternate between a dash of `dash_length` and a gap of `gap_length`,
+    /// measured along the path. The `phase` offsets how far into the pattern each outline
+    /// begins - animating it can be used to create 'marching ants' selection outlines.
+    /// A dotted line can be created by setting `dash_length` to the width of the stroke.
+    ///
+    /// If either of the lengths is not greater than zero, strokes will be drawn solid.
+    ///
+    /// Filled shapes are not affected by this setting.
+    pub fn set_stroke_dash(
+        &mut self,
+        dash_length: f32,
+        gap_length: f32,
+        phase: f32,
+    ) -> &mut GeometryBuilder {
+        self.dash = Some(StrokeDash {
+            dash_length,
+            gap_length,
+            phase,
+        });
+
+        self
+    }
+
+    /// Resets subsequent stroked shapes to being drawn solid.
+    pub fn reset_stroke_dash(&mut self) -> &mut GeometryBuilder {
+        self.dash = None;
+        self
+    }
+
+    fn stroke(&mut self, width: f32, path: &Path) -> Result {
+        let dashed_path;
+
+        let path = match self.dash {
+            Some(dash) if dash.dash_length > 0.0 && dash.gap_length > 0.0 => {
+                dashed_path = dash_path(path, self.tolerance, dash);
+                &dashed_path
+            }
+            _ => path,
+        };
+
+        let options = StrokeOptions::tolerance(self.tolerance).with_line_width(width);
+        let mut tessellator = StrokeTessellator::new();
+
+        if self.feathering <= 0.0 {
+            let mut builder =
+                BuffersBuilder::new(&mut self.data, TetraVertexConstructor(self.color));
+
+            tessellator
+                .tessellate_path(path, &options, &mut builder)
+                .map_err(TetraError::TessellationError)?;
+
+            return Ok(());
+        }
+
+        // The stroke is split into a solid core and a fringe on each side, which fades
+        // from the core's color to transparent.
+        for part in &[
+            StrokePart::Core,
+            StrokePart::Fringe(Side::Left),
+            StrokePart::Fringe(Side::Right),
+        ] {
+            let mut builder = BuffersBuilder::new(
+                &mut self.data,
+                FeatheredStrokeVertexConstructor {
+                    color: self.color,
+                    half_width: width / 2.0,
+                    feathering: self.feathering,
+                    part: *part,
+                },
+            );
+
+            tessellator
+                .tessellate_path(path, &options, &mut builder)
+                .map_err(TetraError::TessellationError)?;
+        }
+
+        Ok(())
+    }
+
     /// Clears the geometry builder's data.
     pub fn clear(&mut self) -> &mut GeometryBuilder {
         self.data.vertices.clear();
@@ -977,3 +1906,276 @@ impl Default for GeometryBuilder {
         GeometryBuilder::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use lyon_tessellation::math::Point;
+
+    use super::{
+        append_mesh_data, dash_polyline, vertex_bounds, CurveSegment, DrawRange, GeometryBuilder,
+        ShapeStyle, StrokeDash, Vertex, MIN_TOLERANCE,
+    };
+    use crate::graphics::{Color, Rectangle};
+    use crate::math::Vec2;
+
+    #[test]
+    fn concave_polygon_fill() {
+        let points = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(10.0, 0.0),
+            Vec2::new(5.0, 5.0),
+            Vec2::new(10.0, 10.0),
+            Vec2::new(0.0, 10.0),
+        ];
+
+        let mut builder = GeometryBuilder::new();
+        builder.polygon(ShapeStyle::Fill, &points).unwrap();
+
+        assert_eq!(9, builder.indices().len());
+    }
+
+    #[test]
+    fn polygon_with_hole_fill() {
+        let outline = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(10.0, 0.0),
+            Vec2::new(10.0, 10.0),
+            Vec2::new(0.0, 10.0),
+        ];
+
+        let hole = [
+            Vec2::new(3.0, 3.0),
+            Vec2::new(7.0, 3.0),
+            Vec2::new(7.0, 7.0),
+            Vec2::new(3.0, 7.0),
+        ];
+
+        let mut builder = GeometryBuilder::new();
+
+        builder
+            .polygon_with_holes(ShapeStyle::Fill, &outline, &[&hole])
+            .unwrap();
+
+        // A square with a square hole is made up of eight triangles.
+        assert_eq!(24, builder.indices().len());
+    }
+
+    #[test]
+    fn bezier_curves_are_flattened() {
+        let segments = [
+            CurveSegment::QuadraticBezier {
+                control: Vec2::new(50.0, -50.0),
+                to: Vec2::new(100.0, 0.0),
+            },
+            CurveSegment::Line(Vec2::new(100.0, 100.0)),
+        ];
+
+        let mut coarse = GeometryBuilder::new();
+        coarse.set_tolerance(10.0);
+        coarse
+            .bezier(ShapeStyle::Stroke(1.0), Vec2::zero(), &segments)
+            .unwrap();
+
+        let mut fine = GeometryBuilder::new();
+        fine.set_tolerance(0.01);
+        fine.bezier(ShapeStyle::Stroke(1.0), Vec2::zero(), &segments)
+            .unwrap();
+
+        assert!(fine.vertices().len() > coarse.vertices().len());
+    }
+
+    #[test]
+    fn invalid_tolerances_are_clamped() {
+        for &tolerance in &[0.0, -1.0, f32::NAN] {
+            let mut builder = GeometryBuilder::new();
+            builder.set_tolerance(tolerance);
+
+            assert_eq!(builder.tolerance, MIN_TOLERANCE);
+
+            builder
+                .circle(ShapeStyle::Fill, Vec2::zero(), 16.0)
+                .unwrap();
+
+            assert!(!builder.vertices().is_empty());
+        }
+    }
+
+    #[test]
+    fn feathered_strokes_fade_out() {
+        let mut builder = GeometryBuilder::new();
+
+        builder
+            .set_stroke_feathering(1.0)
+            .polyline(4.0, &[Vec2::new(0.0, 0.0), Vec2::new(10.0, 0.0)])
+            .unwrap();
+
+        let vertices = builder.vertices();
+
+        // The outermost vertices should be transparent, and extend half of the feathering
+        // distance beyond the edge of the line.
+        assert!(vertices
+            .iter()
+            .filter(|v| v.color.a == 0.0)
+            .all(|v| (v.position.y.abs() - 2.5).abs() < 0.001));
+
+        assert!(vertices
+            .iter()
+            .filter(|v| v.color.a == 1.0)
+            .all(|v| (v.position.y.abs() - 1.5).abs() < 0.001));
+    }
+
+    #[test]
+    fn degenerate_polygons_fail() {
+        let mut builder = GeometryBuilder::new();
+
+        assert!(builder
+            .polygon(
+                ShapeStyle::Fill,
+                &[Vec2::new(0.0, 0.0), Vec2::new(1.0, 1.0)]
+            )
+            .is_err());
+
+        assert!(builder
+            .polygon(
+                ShapeStyle::Fill,
+                &[
+                    Vec2::new(0.0, 0.0),
+                    Vec2::new(f32::NAN, 1.0),
+                    Vec2::new(1.0, 0.0)
+                ]
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn combined_mesh_indices_are_offset() {
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+
+        // A non-indexed triangle, followed by an indexed quad with a draw range that
+        // only covers its second triangle.
+        append_mesh_data(&mut vertices, &mut indices, 2, &[0.0; 6], None, None);
+
+        append_mesh_data(
+            &mut vertices,
+            &mut indices,
+            2,
+            &[1.0; 8],
+            Some(&[0, 1, 2, 2, 3, 0]),
+            Some(DrawRange { start: 3, count: 3 }),
+        );
+
+        assert_eq!(14, vertices.len());
+        assert_eq!(vec![0, 1, 2, 5, 6, 3], indices);
+    }
+
+    #[test]
+    fn bounds_cover_all_vertices() {
+        let vertex = |x, y| Vertex::new(Vec2::new(x, y), Vec2::zero(), Color::WHITE);
+
+        let vertices = [vertex(2.0, -1.0), vertex(-3.0, 4.0), vertex(5.0, 0.0)];
+
+        assert_eq!(
+            Some(Rectangle::new(-3.0, -1.0, 8.0, 5.0)),
+            vertex_bounds(&vertices)
+        );
+
+        assert_eq!(None, vertex_bounds(&[]));
+    }
+
+    #[test]
+    fn dashes_follow_pattern() {
+        let line = [Point::new(0.0, 0.0), Point::new(8.0, 0.0)];
+
+        let dash = StrokeDash {
+            dash_length: 3.0,
+            gap_length: 1.0,
+            phase: 0.0,
+        };
+
+        assert_eq!(
+            vec![
+                vec![Point::new(0.0, 0.0), Point::new(3.0, 0.0)],
+                vec![Point::new(4.0, 0.0), Point::new(7.0, 0.0)],
+            ],
+            dash_polyline(&line, false, dash)
+        );
+
+        assert_eq!(
+            vec![
+                vec![Point::new(1.0, 0.0), Point::new(4.0, 0.0)],
+                vec![Point::new(5.0, 0.0), Point::new(8.0, 0.0)],
+            ],
+            dash_polyline(
+                &line,
+                false,
+                StrokeDash {
+                    phase: -1.0,
+                    ..dash
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn dashes_handle_degenerate_input() {
+        let line = [
+            Point::new(0.0, 0.0),
+            Point::new(0.0, 0.0),
+            Point::new(8.0, 0.0),
+        ];
+
+        let dash = StrokeDash {
+            dash_length: 3.0,
+            gap_length: 1.0,
+            phase: -1e-7,
+        };
+
+        assert_eq!(
+            vec![
+                vec![Point::new(0.0, 0.0), Point::new(3.0, 0.0)],
+                vec![Point::new(4.0, 0.0), Point::new(7.0, 0.0)],
+            ],
+            dash_polyline(&line, false, dash)
+        );
+    }
+
+    #[test]
+    fn dashes_join_across_start_of_closed_contour() {
+        let square = [
+            Point::new(0.0, 0.0),
+            Point::new(8.0, 0.0),
+            Point::new(8.0, 8.0),
+            Point::new(0.0, 8.0),
+            Point::new(0.0, 0.0),
+        ];
+
+        let dash = StrokeDash {
+            dash_length: 3.0,
+            gap_length: 1.0,
+            phase: 2.0,
+        };
+
+        let dashes = dash_polyline(&square, true, dash);
+
+        assert_eq!(8, dashes.len());
+
+        assert_eq!(
+            vec![
+                Point::new(6.0, 0.0),
+                Point::new(8.0, 0.0),
+                Point::new(8.0, 1.0),
+            ],
+            dashes[1]
+        );
+
+        assert_eq!(
+            vec![
+                Point::new(0.0, 2.0),
+                Point::new(0.0, 0.0),
+                Point::new(1.0, 0.0),
+            ],
+            dashes[7]
+        );
+    }
+}
@@ -0,0 +1,156 @@
+use crate::error::Result;
+use crate::graphics::{self, Canvas, Color, DrawParams, Shader};
+use crate::math::Vec2;
+use crate::Context;
+
+/// Draws the contents of one canvas into another, using the specified shader.
+///
+/// The source canvas will be stretched to fill the destination canvas, and the destination
+/// canvas will be cleared before drawing. This can be used to apply full-screen effects
+/// (such as vignettes, blurs, or CRT filters) to a rendered frame. Any uniforms should be
+/// set on the shader before calling this function.
+///
+/// Some commonly needed values are also provided to the shader automatically, if it
+/// declares them:
+///
+/// * `uniform vec2 u_resolution` - the size of `destination`, in pixels.
+/// * `uniform vec2 u_texel_size` - the size of a single pixel of `source`, in texture
+///   co-ordinates (i.e. `1.0 / size`).
+/// * `uniform float u_time` - the number of seconds since the game started.
+///
+/// These overwrite any values that you have set for the same uniforms. See the [`Shader`]
+/// documentation for more details.
+///
+/// The active canvas and shader will be restored once the pass has been drawn. Note that
+/// this function will trigger a [`flush`](graphics::flush) to the graphics hardware.
+///
+/// If you need to chain multiple passes together, consider using [`PostProcess`].
+///
+/// # Panics
+///
+/// Panics if `source` and `destination` are the same canvas, as a canvas cannot be sampled
+/// from while it is being rendered to.
+pub fn apply_shader_to_canvas(
+    ctx: &mut Context,
+    source: &Canvas,
+    destination: &Canvas,
+    shader: &Shader,
+) {
+    assert!(
+        source != destination,
+        "the source and destination of a post-processing pass must be different canvases"
+    );
+
+    let previous_canvas = ctx.graphics.canvas.clone();
+    let previous_shader = ctx.graphics.shader.clone();
+
+    graphics::set_canvas(ctx, destination);
+    graphics::clear(ctx, Color::rgba(0.0, 0.0, 0.0, 0.0));
+    graphics::set_shader(ctx, shader);
+
+    let (source_width, source_height) = source.size();
+    let (destination_width, destination_height) = destination.size();
+
+    shader.set_uniform(
+        ctx,
+        "u_resolution",
+        Vec2::new(destination_width as f32, destination_height as f32),
+    );
+
+    shader.set_uniform(
+        ctx,
+        "u_texel_size",
+        Vec2::new(1.0 / source_width as f32, 1.0 / source_height as f32),
+    );
+
+    let elapsed = ctx.time.start_time.elapsed().as_secs_f32();
+    shader.set_uniform(ctx, "u_time", elapsed);
+
+    source.draw(
+        ctx,
+        DrawParams::new().scale(Vec2::new(
+            destination_width as f32 / source_width as f32,
+            destination_height as f32 / source_height as f32,
+        )),
+    );
+
+    graphics::set_shader_ex(ctx, previous_shader);
+    graphics::set_canvas_ex(ctx, previous_canvas);
+}
+
+/// A helper for applying a chain of full-screen shaders to a rendered frame.
+///
+/// `PostProcess` owns a pair of canvases, which it 'ping-pongs' between when applying
+/// multiple passes - the output of each pass is used as the input of the next.
+///
+/// Each pass is drawn via [`apply_shader_to_canvas`], so the shaders will have the
+/// `u_resolution` (`vec2`), `u_texel_size` (`vec2`) and `u_time` (`float`) uniforms set
+/// automatically, overwriting any values that you have set for them. For each pass,
+/// `u_texel_size` is based on the size of that pass's input.
+///
+/// # Performance
+///
+/// Creating a `PostProcess` will create two canvases, which is a relatively expensive operation.
+/// If you can, store it in your [`State`](crate::State) struct rather than recreating it each frame.
+#[derive(Debug, Clone)]
+pub struct PostProcess {
+    canvases: [Canvas; 2],
+}
+
+impl PostProcess {
+    /// Creates a new post-processing helper, with intermediate canvases of the specified size.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the underlying
+    /// graphics API encounters an error.
+    pub fn new(ctx: &mut Context, width: i32, height: i32) -> Result<PostProcess> {
+        Ok(PostProcess {
+            canvases: [
+                Canvas::new(ctx, width, height)?,
+                Canvas::new(ctx, width, height)?,
+            ],
+        })
+    }
+
+    /// Applies each of the shaders to the source canvas in turn, returning the canvas that
+    /// contains the final result.
+    ///
+    /// If `shaders` is empty, `source` will be returned unchanged.
+    ///
+    /// The returned canvas is owned by the `PostProcess`, and so its contents will be overwritten
+    /// the next time this method is called.
+    ///
+    /// `source` may be one of this helper's own [`canvases`](Self::canvases) (for example, the
+    /// result of a previous call) - the passes will be arranged so that a canvas is never
+    /// sampled from while it is being rendered to.
+    pub fn apply<'a>(
+        &'a self,
+        ctx: &mut Context,
+        source: &'a Canvas,
+        shaders: &[Shader],
+    ) -> &'a Canvas {
+        let mut current = source;
+
+        for shader in shaders {
+            // Always render into whichever canvas is not currently being read from - this
+            // also covers the case where the source is one of our own canvases.
+            let target = if *current == self.canvases[0] {
+                &self.canvases[1]
+            } else {
+                &self.canvases[0]
+            };
+
+            apply_shader_to_canvas(ctx, current, target, shader);
+
+            current = target;
+        }
+
+        current
+    }
+
+    /// Returns the intermediate canvases used by this helper.
+    pub fn canvases(&self) -> &[Canvas; 2] {
+        &self.canvases
+    }
+}
@@ -91,6 +91,38 @@ where
         }
     }
 
+    /// Returns an iterator of rectangles arranged in a grid, starting at the specified
+    /// point. The rectangles are returned row by row, from left to right.
+    ///
+    /// This can be useful when slicing spritesheets where all of the sprites are the same
+    /// size, and for iterating over the cells of a tilemap.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tetra::graphics::Rectangle;
+    /// let rects: Vec<Rectangle> = Rectangle::grid(0.0, 0.0, 16.0, 16.0, 2, 2).collect();
+    ///
+    /// assert_eq!(Rectangle::new(0.0, 0.0, 16.0, 16.0), rects[0]);
+    /// assert_eq!(Rectangle::new(16.0, 0.0, 16.0, 16.0), rects[1]);
+    /// assert_eq!(Rectangle::new(0.0, 16.0, 16.0, 16.0), rects[2]);
+    /// assert_eq!(Rectangle::new(16.0, 16.0, 16.0, 16.0), rects[3]);
+    /// ```
+    pub fn grid(
+        x: T,
+        y: T,
+        width: T,
+        height: T,
+        columns: usize,
+        rows: usize,
+    ) -> impl Iterator<Item = Rectangle<T>>
+    where
+        T: AddAssign,
+    {
+        Rectangle::column(x, y, width, height)
+            .take(rows)
+            .flat_map(move |start| Rectangle::row(start.x, start.y, width, height).take(columns))
+    }
+
     /// Returns `true` if the `other` rectangle intersects with `self`.
     pub fn intersects(&self, other: &Rectangle<T>) -> bool
     where
@@ -225,6 +257,30 @@ where
     }
 }
 
+impl<T> From<(T, T, T, T)> for Rectangle<T> {
+    fn from((x, y, width, height): (T, T, T, T)) -> Rectangle<T> {
+        Rectangle::new(x, y, width, height)
+    }
+}
+
+impl<T> From<Rectangle<T>> for (T, T, T, T) {
+    fn from(rectangle: Rectangle<T>) -> (T, T, T, T) {
+        (rectangle.x, rectangle.y, rectangle.width, rectangle.height)
+    }
+}
+
+impl<T> From<[T; 4]> for Rectangle<T> {
+    fn from([x, y, width, height]: [T; 4]) -> Rectangle<T> {
+        Rectangle::new(x, y, width, height)
+    }
+}
+
+impl<T> From<Rectangle<T>> for [T; 4] {
+    fn from(rectangle: Rectangle<T>) -> [T; 4] {
+        [rectangle.x, rectangle.y, rectangle.width, rectangle.height]
+    }
+}
+
 #[derive(Debug, Clone)]
 struct RectangleRow<T> {
     next_rect: Rectangle<T>,
@@ -326,4 +382,28 @@ mod tests {
             Rectangle::new(8.0, 0.0, 40.0, 72.0),
         )
     }
+
+    #[test]
+    fn conversions() {
+        let rectangle = Rectangle::new(1, 2, 3, 4);
+
+        assert_eq!(rectangle, Rectangle::from((1, 2, 3, 4)));
+        assert_eq!(rectangle, Rectangle::from([1, 2, 3, 4]));
+
+        assert_eq!((1, 2, 3, 4), rectangle.into());
+        assert_eq!([1, 2, 3, 4], <[i32; 4]>::from(rectangle));
+    }
+
+    #[test]
+    fn grid() {
+        let rects: Vec<Rectangle<i32>> = Rectangle::grid(8, 4, 16, 32, 3, 2).collect();
+
+        assert_eq!(6, rects.len());
+        assert_eq!(Rectangle::new(8, 4, 16, 32), rects[0]);
+        assert_eq!(Rectangle::new(40, 4, 16, 32), rects[2]);
+        assert_eq!(Rectangle::new(8, 36, 16, 32), rects[3]);
+        assert_eq!(Rectangle::new(40, 36, 16, 32), rects[5]);
+
+        assert_eq!(0, Rectangle::grid(0, 0, 16, 16, 0, 4).count());
+    }
 }
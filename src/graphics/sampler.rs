@@ -0,0 +1,69 @@
+use crate::graphics::FilterMode;
+
+/// How a texture should be sampled outside of the `0.0` to `1.0` range of texture
+/// co-ordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WrapMode {
+    /// The edge pixels of the texture are stretched outwards. This is the behaviour that
+    /// textures use by default.
+    Clamp,
+
+    /// The texture is tiled.
+    Repeat,
+
+    /// The texture is tiled, with every other repetition being mirrored.
+    MirroredRepeat,
+}
+
+/// Settings that control how textures are sampled when they are drawn, independent of
+/// the textures themselves.
+///
+/// Normally, the way a texture is sampled is controlled by the texture's own
+/// [filter mode](crate::graphics::Texture::set_filter_mode). As this is shared between all
+/// clones of a texture, drawing the same texture in two different ways would require
+/// changing the filter mode back and forth, or creating a copy of the texture.
+///
+/// A `Sampler` can instead be activated via [`graphics::set_sampler`](crate::graphics::set_sampler),
+/// which overrides the sampling settings of every texture drawn until it is
+/// [reset](crate::graphics::reset_sampler). The textures themselves are not modified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sampler {
+    /// The filtering algorithm that should be used when scaling textures.
+    pub filter_mode: FilterMode,
+
+    /// How textures should be sampled outside of the `0.0` to `1.0` range of texture co-ordinates.
+    pub wrap_mode: WrapMode,
+}
+
+impl Sampler {
+    /// Creates a new sampler.
+    pub fn new(filter_mode: FilterMode, wrap_mode: WrapMode) -> Sampler {
+        Sampler {
+            filter_mode,
+            wrap_mode,
+        }
+    }
+
+    /// Creates a new sampler that uses nearest-neighbor filtering, and clamps to the edges
+    /// of textures.
+    pub fn nearest() -> Sampler {
+        Sampler::new(FilterMode::Nearest, WrapMode::Clamp)
+    }
+
+    /// Creates a new sampler that uses linear filtering, and clamps to the edges of textures.
+    pub fn linear() -> Sampler {
+        Sampler::new(FilterMode::Linear, WrapMode::Clamp)
+    }
+
+    /// Sets the filtering algorithm that should be used when scaling textures.
+    pub fn filter_mode(mut self, filter_mode: FilterMode) -> Sampler {
+        self.filter_mode = filter_mode;
+        self
+    }
+
+    /// Sets how textures should be sampled outside of the `0.0` to `1.0` range of texture co-ordinates.
+    pub fn wrap_mode(mut self, wrap_mode: WrapMode) -> Sampler {
+        self.wrap_mode = wrap_mode;
+        self
+    }
+}
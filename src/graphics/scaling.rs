@@ -125,6 +125,10 @@ impl ScreenScaler {
     }
 
     /// Converts a point from window co-ordinates to scaled screen co-ordinates.
+    ///
+    /// When using one of the pixel perfect [scaling modes](ScalingMode), every window pixel
+    /// within a screen pixel will map back to that screen pixel exactly, so the result can
+    /// be rounded down to get the position of the pixel under the mouse.
     pub fn project(&self, position: Vec2<f32>) -> Vec2<f32> {
         let (width, height) = self.canvas().size();
 
@@ -225,8 +229,20 @@ pub enum ScalingMode {
     ShowAll,
 
     /// Works the same as ShowAll, but will only scale by integer values.
+    ///
+    /// The screen will be scaled by the largest integer that fits in the window, and centered,
+    /// so every pixel of the screen will be drawn as a square block of window pixels. This is
+    /// usually the best choice for pixel art games. If the window is smaller than the native
+    /// resolution, the screen will be drawn at a scale of 1 and cropped.
     ShowAllPixelPerfect,
 
+    /// Works the same as ShowAllPixelPerfect, but will only scale by even integer values
+    /// (2, 4, 6, etc), falling back to a scale of 1 if the window is too small for a scale of 2.
+    ///
+    /// This can be useful if your game positions things at half-pixel offsets, as these will
+    /// still line up with the window's pixels at an even scale.
+    ShowAllPixelPerfectEven,
+
     /// The screen will fill the entire window, maintaining the original aspect ratio but
     /// potentially being cropped.
     Crop,
@@ -281,13 +297,17 @@ pub fn get_screen_rect(
 
             Rectangle::new(screen_x, screen_y, screen_width, screen_height)
         }
-        ScalingMode::ShowAllPixelPerfect => {
+        ScalingMode::ShowAllPixelPerfect | ScalingMode::ShowAllPixelPerfectEven => {
             let mut scale_factor = if internal_aspect_ratio > screen_aspect_ratio {
                 outer_width / inner_width
             } else {
                 outer_height / inner_height
             };
 
+            if mode == ScalingMode::ShowAllPixelPerfectEven && scale_factor > 1 {
+                scale_factor -= scale_factor % 2;
+            }
+
             if scale_factor == 0 {
                 scale_factor = 1;
             }
@@ -342,3 +362,38 @@ pub fn get_screen_rect(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{get_screen_rect, project_impl, ScalingMode};
+    use crate::graphics::Rectangle;
+
+    #[test]
+    fn pixel_perfect_scales_by_integers() {
+        assert_eq!(
+            Rectangle::new(20.0, 15.0, 960.0, 540.0),
+            get_screen_rect(ScalingMode::ShowAllPixelPerfect, 320, 180, 1000, 570)
+        );
+
+        assert_eq!(
+            Rectangle::new(180.0, 105.0, 640.0, 360.0),
+            get_screen_rect(ScalingMode::ShowAllPixelPerfectEven, 320, 180, 1000, 570)
+        );
+
+        assert_eq!(
+            Rectangle::new(40.0, 10.0, 320.0, 180.0),
+            get_screen_rect(ScalingMode::ShowAllPixelPerfectEven, 320, 180, 400, 200)
+        );
+    }
+
+    #[test]
+    fn pixel_perfect_projection_is_exact() {
+        let rect = get_screen_rect(ScalingMode::ShowAllPixelPerfect, 320, 180, 1000, 570);
+
+        for window_x in 20..980 {
+            let screen_x = project_impl(window_x as f32, rect.x, rect.width, 320.0);
+
+            assert_eq!((window_x - 20) / 3, screen_x.floor() as i32);
+        }
+    }
+}
@@ -1,14 +1,16 @@
 //! Functions and types relating to shader programs.
 
 use std::cell::{Cell, RefCell};
+use std::fmt::{self, Display, Formatter};
 use std::path::Path;
 use std::rc::Rc;
 
 use hashbrown::HashMap;
 
-use crate::error::Result;
+use crate::error::{Result, TetraError};
 use crate::fs;
-use crate::graphics::{Color, Texture};
+use crate::graphics::mesh::VertexLayout;
+use crate::graphics::{Color, ColorBlend, Texture};
 use crate::math::{Mat2, Mat3, Mat4, Vec2, Vec3, Vec4};
 use crate::platform::{GraphicsDevice, RawProgram};
 use crate::Context;
@@ -23,8 +25,31 @@ pub const DEFAULT_VERTEX_SHADER: &str = include_str!("../resources/shader.vert")
 /// The source code for this shader is available in [`src/resources/shader.vert`](https://github.com/17cupsofcoffee/tetra/blob/main/src/resources/shader.frag).
 pub const DEFAULT_FRAGMENT_SHADER: &str = include_str!("../resources/shader.frag");
 
+/// A stage of the shader pipeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShaderStage {
+    /// The vertex shader.
+    Vertex,
+
+    /// The fragment shader.
+    Fragment,
+
+    /// The linking of the vertex and fragment shaders into a program.
+    Linking,
+}
+
+impl Display for ShaderStage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ShaderStage::Vertex => write!(f, "vertex"),
+            ShaderStage::Fragment => write!(f, "fragment"),
+            ShaderStage::Linking => write!(f, "linking"),
+        }
+    }
+}
+
 #[derive(Debug)]
-pub(crate) struct Sampler {
+pub(crate) struct SamplerUniform {
     pub(crate) texture: Texture,
     pub(crate) unit: u32,
 }
@@ -32,7 +57,7 @@ pub(crate) struct Sampler {
 #[derive(Debug)]
 pub(crate) struct ShaderSharedData {
     pub(crate) handle: RawProgram,
-    pub(crate) samplers: RefCell<HashMap<String, Sampler>>,
+    pub(crate) samplers: RefCell<HashMap<String, SamplerUniform>>,
     pub(crate) next_unit: Cell<u32>,
 }
 
@@ -57,6 +82,9 @@ impl PartialEq for ShaderSharedData {
 /// * `a_color` - A `vec4` representing the color of the vertex. This will be multiplied by
 ///   `u_diffuse` and the color sampled from `u_texture` (see 'Uniforms' below).
 ///
+/// If you need different per-vertex data, you can draw a [`Mesh`](super::mesh::Mesh) using a
+/// [custom vertex layout](super::mesh::VertexLayout) instead.
+///
 /// Position data should be output as a `vec4` to the built-in `gl_Position` variable.
 ///
 /// ## Fragment Shaders
@@ -66,16 +94,52 @@ impl PartialEq for ShaderSharedData {
 ///
 /// ## Uniforms
 ///
-/// By default, the shader is provided with three uniform variables:
+/// By default, the shader is provided with four uniform variables:
 ///
 /// * `u_projection` - A `mat4` which can be used to translate world space co-ordinates into screen space.
 /// * `u_texture` - A `sampler2D` which can be used to access color data from the currently active texture.
 /// * `u_diffuse` - A `vec4` representing the color of the current geometry. This is currently only used to
 ///   pass through the [`DrawParams::color`](super::DrawParams::color) for a [`Mesh`](super::mesh::Mesh), and will
 ///   otherwise be set to [`Color::WHITE`].
+/// * `u_color_blend` - A `vec2` representing the [`ColorBlend`](super::ColorBlend) of the current geometry.
+///   The X component is the mode (`0.0` for `Multiply`, `1.0` for `Add`, `2.0` for `Mix`), and the Y
+///   component is the amount used by `Mix`. Custom shaders are free to ignore this.
 ///
 /// You can also set data into your own uniform variables via the `set_uniform` method.
 ///
+/// ## Post-processing Uniforms
+///
+/// When a shader is used for a full-screen pass (via
+/// [`apply_shader_to_canvas`](super::apply_shader_to_canvas) or
+/// [`PostProcess`](super::PostProcess)), the following uniforms will also be set, if the
+/// shader declares them:
+///
+/// * `u_resolution` - A `vec2` containing the size of the canvas being rendered to, in pixels.
+/// * `u_texel_size` - A `vec2` containing the size of a single pixel of the source canvas, in
+///   texture co-ordinates (i.e. `1.0 / size`). This is useful for sampling neighbouring pixels,
+///   e.g. when blurring.
+/// * `u_time` - A `float` containing the number of seconds since the game started. This is
+///   based on real time, so it keeps counting while the game is paused.
+///
+/// These names are reserved during a post-processing pass - any values that you have set
+/// for them via `set_uniform` will be overwritten. They are not set for regular drawing,
+/// so if you use a shader for both, you will need to set them yourself via `set_uniform`.
+///
+/// ## Preprocessing
+///
+/// Before a shader is compiled, Tetra will apply some light preprocessing to the source code:
+///
+/// * Lines of the form `#include "path/to/file.glsl"` will be replaced with the contents of the
+///   specified file. When loading a shader from a file, the path is relative to that file - otherwise,
+///   it is relative to the current working directory. Included files can include other files.
+///   The path may be followed by a comment. Directives inside `//` or `/* */` comments are ignored.
+/// * If the shader does not specify a `#version`, the preamble `#version 150` (matching the OpenGL
+///   3.2 core profile that Tetra uses) will be inserted, followed by `#define TETRA`. Note that this
+///   will offset the line numbers reported in any compilation errors by two.
+///
+/// The inserted preamble is only valid for desktop OpenGL - if you are targeting OpenGL ES (or
+/// any other version of GLSL), specify the `#version` in your shader explicitly.
+///
 /// # Performance
 ///
 /// Creating a `Shader` is a relatively expensive operation. If you can, store them in your
@@ -106,6 +170,8 @@ impl Shader {
     /// * [`TetraError::FailedToLoadAsset`](crate::TetraError::FailedToLoadAsset) will be returned
     /// if the files could not be loaded.
     /// * [`TetraError::InvalidShader`](crate::TetraError::InvalidShader) will be returned if the
+    /// shader source could not be preprocessed.
+    /// * [`TetraError::ShaderCompilation`](crate::TetraError::ShaderCompilation) will be returned if the
     /// shader could not be compiled.
     pub fn new<P>(ctx: &mut Context, vertex_path: P, fragment_path: P) -> Result<Shader>
     where
@@ -113,8 +179,8 @@ impl Shader {
     {
         Shader::with_device(
             &mut ctx.device,
-            &fs::read_to_string(vertex_path)?,
-            &fs::read_to_string(fragment_path)?,
+            &load_source(vertex_path)?,
+            &load_source(fragment_path)?,
         )
     }
 
@@ -129,6 +195,8 @@ impl Shader {
     /// * [`TetraError::FailedToLoadAsset`](crate::TetraError::FailedToLoadAsset) will be returned
     /// if the file could not be loaded.
     /// * [`TetraError::InvalidShader`](crate::TetraError::InvalidShader) will be returned if the
+    /// shader source could not be preprocessed.
+    /// * [`TetraError::ShaderCompilation`](crate::TetraError::ShaderCompilation) will be returned if the
     /// shader could not be compiled.
     pub fn from_vertex_file<P>(ctx: &mut Context, path: P) -> Result<Shader>
     where
@@ -136,7 +204,7 @@ impl Shader {
     {
         Shader::with_device(
             &mut ctx.device,
-            &fs::read_to_string(path)?,
+            &load_source(path)?,
             DEFAULT_FRAGMENT_SHADER,
         )
     }
@@ -152,16 +220,14 @@ impl Shader {
     /// * [`TetraError::FailedToLoadAsset`](crate::TetraError::FailedToLoadAsset) will be returned
     /// if the file could not be loaded.
     /// * [`TetraError::InvalidShader`](crate::TetraError::InvalidShader) will be returned if the
+    /// shader source could not be preprocessed.
+    /// * [`TetraError::ShaderCompilation`](crate::TetraError::ShaderCompilation) will be returned if the
     /// shader could not be compiled.
     pub fn from_fragment_file<P>(ctx: &mut Context, path: P) -> Result<Shader>
     where
         P: AsRef<Path>,
     {
-        Shader::with_device(
-            &mut ctx.device,
-            DEFAULT_VERTEX_SHADER,
-            &fs::read_to_string(path)?,
-        )
+        Shader::with_device(&mut ctx.device, DEFAULT_VERTEX_SHADER, &load_source(path)?)
     }
 
     /// Creates a new shader program from the given strings.
@@ -170,14 +236,22 @@ impl Shader {
     ///
     /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the
     /// underlying graphics API encounters an error.
+    /// * [`TetraError::FailedToLoadAsset`](crate::TetraError::FailedToLoadAsset) will be returned
+    /// if an `#include`d file could not be loaded.
     /// * [`TetraError::InvalidShader`](crate::TetraError::InvalidShader) will be returned if the
+    /// shader source could not be preprocessed.
+    /// * [`TetraError::ShaderCompilation`](crate::TetraError::ShaderCompilation) will be returned if the
     /// shader could not be compiled.
     pub fn from_string(
         ctx: &mut Context,
         vertex_shader: &str,
         fragment_shader: &str,
     ) -> Result<Shader> {
-        Shader::with_device(&mut ctx.device, vertex_shader, fragment_shader)
+        Shader::with_device(
+            &mut ctx.device,
+            &preprocess(vertex_shader, Path::new(""))?,
+            &preprocess(fragment_shader, Path::new(""))?,
+        )
     }
 
     /// Creates a new shader program from the given vertex shader string.
@@ -188,10 +262,18 @@ impl Shader {
     ///
     /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the
     /// underlying graphics API encounters an error.
+    /// * [`TetraError::FailedToLoadAsset`](crate::TetraError::FailedToLoadAsset) will be returned
+    /// if an `#include`d file could not be loaded.
     /// * [`TetraError::InvalidShader`](crate::TetraError::InvalidShader) will be returned if the
+    /// shader source could not be preprocessed.
+    /// * [`TetraError::ShaderCompilation`](crate::TetraError::ShaderCompilation) will be returned if the
     /// shader could not be compiled.
     pub fn from_vertex_string<P>(ctx: &mut Context, shader: &str) -> Result<Shader> {
-        Shader::with_device(&mut ctx.device, shader, DEFAULT_FRAGMENT_SHADER)
+        Shader::with_device(
+            &mut ctx.device,
+            &preprocess(shader, Path::new(""))?,
+            DEFAULT_FRAGMENT_SHADER,
+        )
     }
 
     /// Creates a new shader program from the given fragment shader string.
@@ -202,10 +284,18 @@ impl Shader {
     ///
     /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the
     /// underlying graphics API encounters an error.
+    /// * [`TetraError::FailedToLoadAsset`](crate::TetraError::FailedToLoadAsset) will be returned
+    /// if an `#include`d file could not be loaded.
     /// * [`TetraError::InvalidShader`](crate::TetraError::InvalidShader) will be returned if the
+    /// shader source could not be preprocessed.
+    /// * [`TetraError::ShaderCompilation`](crate::TetraError::ShaderCompilation) will be returned if the
     /// shader could not be compiled.
     pub fn from_fragment_string<P>(ctx: &mut Context, shader: &str) -> Result<Shader> {
-        Shader::with_device(&mut ctx.device, DEFAULT_VERTEX_SHADER, shader)
+        Shader::with_device(
+            &mut ctx.device,
+            DEFAULT_VERTEX_SHADER,
+            &preprocess(shader, Path::new(""))?,
+        )
     }
 
     pub(crate) fn with_device(
@@ -232,11 +322,46 @@ impl Shader {
         value.set_uniform(ctx, self, name)
     }
 
+    /// Checks that a [`VertexLayout`] provides all of the vertex attributes that this shader
+    /// uses, with matching types.
+    ///
+    /// Attributes in the layout that the shader doesn't use are allowed, as they may have been
+    /// optimized out by the graphics driver.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::InvalidShader`] will be returned if the shader uses an attribute that
+    /// is missing from the layout, or if the types do not match.
+    pub fn validate_vertex_layout(&self, ctx: &mut Context, layout: &VertexLayout) -> Result {
+        for (name, format) in ctx.device.get_active_attributes(&self.data.handle) {
+            let attribute = layout
+                .attributes()
+                .iter()
+                .find(|a| a.name == name)
+                .ok_or_else(|| {
+                    TetraError::InvalidShader(format!(
+                        "vertex layout is missing attribute '{}'",
+                        name
+                    ))
+                })?;
+
+            if format != Some(attribute.format) {
+                return Err(TetraError::InvalidShader(format!(
+                    "attribute '{}' does not match the type in the vertex layout ({:?})",
+                    name, attribute.format
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
     pub(crate) fn set_default_uniforms(
         &self,
         device: &mut GraphicsDevice,
         projection: Mat4<f32>,
         diffuse: Color,
+        color_blend: ColorBlend,
     ) -> Result {
         let samplers = self.data.samplers.borrow();
 
@@ -252,6 +377,14 @@ impl Shader {
 
         device.set_uniform_vec4(&self.data.handle, diffuse_location.as_ref(), diffuse.into());
 
+        let color_blend_location = device.get_uniform_location(&self.data.handle, "u_color_blend");
+
+        device.set_uniform_vec2(
+            &self.data.handle,
+            color_blend_location.as_ref(),
+            color_blend.to_uniform(),
+        );
+
         Ok(())
     }
 }
@@ -318,7 +451,7 @@ impl UniformValue for Texture {
 
             samplers.insert(
                 name.to_owned(),
-                Sampler {
+                SamplerUniform {
                     texture: self.clone(),
                     unit: next_unit,
                 },
@@ -343,3 +476,208 @@ where
         }
     }
 }
+
+/// The GLSL version that is assumed if a shader does not specify one.
+const DEFAULT_GLSL_PREAMBLE: &str = "#version 150\n#define TETRA\n";
+
+/// The maximum depth of nested `#include` directives, to guard against include cycles.
+const MAX_INCLUDE_DEPTH: usize = 32;
+
+fn load_source<P>(path: P) -> Result<String>
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let source = fs::read_to_string(path)?;
+
+    preprocess(&source, path.parent().unwrap_or_else(|| Path::new("")))
+}
+
+fn preprocess(source: &str, base_dir: &Path) -> Result<String> {
+    let mut output = String::with_capacity(source.len());
+
+    resolve_includes(source, base_dir, 0, &mut output)?;
+
+    let has_version = output
+        .lines()
+        .any(|line| line.trim_start().starts_with("#version"));
+
+    if !has_version {
+        output.insert_str(0, DEFAULT_GLSL_PREAMBLE);
+    }
+
+    Ok(output)
+}
+
+fn resolve_includes(source: &str, base_dir: &Path, depth: usize, output: &mut String) -> Result {
+    if depth > MAX_INCLUDE_DEPTH {
+        return Err(TetraError::InvalidShader(format!(
+            "#include directives nested more than {} levels deep (is there an include cycle?)",
+            MAX_INCLUDE_DEPTH
+        )));
+    }
+
+    let mut in_block_comment = false;
+
+    for line in source.lines() {
+        let trimmed = line.trim();
+        let in_comment = in_block_comment;
+
+        in_block_comment = ends_in_block_comment(line, in_block_comment);
+
+        if let Some(rest) = trimmed.strip_prefix("#include").filter(|_| !in_comment) {
+            let (path, comment) = parse_include_path(rest).ok_or_else(|| {
+                TetraError::InvalidShader(format!("invalid #include directive: {}", trimmed))
+            })?;
+
+            let include_path = base_dir.join(path);
+            let include_source = fs::read_to_string(&include_path)?;
+            let include_dir = include_path.parent().unwrap_or_else(|| Path::new(""));
+
+            resolve_includes(&include_source, include_dir, depth + 1, output)?;
+
+            // A trailing comment is kept, in case it opens a block comment that continues
+            // onto the following lines.
+            if !comment.is_empty() {
+                output.push_str(comment);
+                output.push('\n');
+            }
+        } else {
+            output.push_str(line);
+            output.push('\n');
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns whether a `/* */` comment is still open at the end of the line.
+fn ends_in_block_comment(line: &str, mut in_block_comment: bool) -> bool {
+    let mut rest = line;
+
+    loop {
+        if in_block_comment {
+            match rest.find("*/") {
+                Some(end) => {
+                    in_block_comment = false;
+                    rest = &rest[end + 2..];
+                }
+                None => return true,
+            }
+        } else {
+            let line_comment = rest.find("//").unwrap_or(rest.len());
+
+            match rest.find("/*") {
+                Some(start) if start < line_comment => {
+                    in_block_comment = true;
+                    rest = &rest[start + 2..];
+                }
+                _ => return false,
+            }
+        }
+    }
+}
+
+/// Parses the quoted path from the rest of an `#include` directive, returning it along
+/// with any comment that follows it.
+///
+/// Returns `None` if the path is not quoted, or if anything other than whitespace or
+/// a comment follows it.
+fn parse_include_path(directive: &str) -> Option<(&str, &str)> {
+    let directive = directive.trim().strip_prefix('"')?;
+    let end = directive.find('"')?;
+
+    let path = &directive[..end];
+    let rest = directive[end + 1..].trim();
+
+    if is_comment(rest) {
+        Some((path, rest))
+    } else {
+        None
+    }
+}
+
+/// Returns whether the given text consists only of whitespace and comments.
+fn is_comment(text: &str) -> bool {
+    let text = text.trim_start();
+
+    if text.is_empty() || text.starts_with("//") {
+        return true;
+    }
+
+    match text.strip_prefix("/*") {
+        Some(comment) => match comment.find("*/") {
+            Some(end) => is_comment(&comment[end + 2..]),
+            None => true,
+        },
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{parse_include_path, preprocess};
+
+    #[test]
+    fn preamble_is_added_if_version_missing() {
+        let source = preprocess("void main() {}", Path::new("")).unwrap();
+
+        assert!(source.starts_with("#version 150\n"));
+        assert!(source.ends_with("void main() {}\n"));
+    }
+
+    #[test]
+    fn preamble_is_not_added_if_version_present() {
+        let source = preprocess("#version 330\nvoid main() {}", Path::new("")).unwrap();
+
+        assert_eq!("#version 330\nvoid main() {}\n", source);
+    }
+
+    #[test]
+    fn invalid_include_is_an_error() {
+        assert!(preprocess("#include <foo.glsl>", Path::new("")).is_err());
+    }
+
+    #[test]
+    fn include_paths_can_be_followed_by_comments() {
+        assert_eq!(
+            Some(("common.glsl", "// lighting helpers")),
+            parse_include_path(" \"common.glsl\" // lighting helpers")
+        );
+
+        assert_eq!(
+            Some(("common.glsl", "/* a */ /* b")),
+            parse_include_path(" \"common.glsl\" /* a */ /* b")
+        );
+
+        assert_eq!(
+            Some(("common.glsl", "")),
+            parse_include_path(" \"common.glsl\"  ")
+        );
+    }
+
+    #[test]
+    fn include_paths_cannot_be_followed_by_other_text() {
+        assert_eq!(None, parse_include_path(" \"a\" \"b\""));
+        assert_eq!(None, parse_include_path(" \"a\" /* comment */ b"));
+        assert_eq!(None, parse_include_path(" \"unterminated"));
+        assert_eq!(None, parse_include_path(" <a.glsl>"));
+    }
+
+    #[test]
+    fn includes_in_comments_are_ignored() {
+        let input = "#version 330\n// #include \"missing.glsl\"\n/*\n#include \"missing.glsl\"\n*/\nvoid main() {}";
+        let source = preprocess(input, Path::new("")).unwrap();
+
+        assert_eq!(format!("{}\n", input), source);
+    }
+
+    #[test]
+    fn includes_after_block_comments_are_resolved() {
+        let input = "/* comment */\n#include \"missing.glsl\"";
+
+        assert!(preprocess(input, Path::new("")).is_err());
+    }
+}
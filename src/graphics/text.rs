@@ -15,13 +15,20 @@ use std::path::Path;
 use std::rc::Rc;
 
 use crate::error::Result;
-use crate::graphics::text::cache::{FontCache, TextGeometry};
+use crate::graphics::text::cache::{FontCache, TextGeometry, TextLayout};
+use crate::graphics::Texture;
 use crate::graphics::{self, DrawParams, Rectangle};
+use crate::math::Vec2;
 use crate::Context;
 
+pub use crate::graphics::text::cache::{AtlasGlyph, TextQuad};
 #[cfg(feature = "font_ttf")]
 pub use crate::graphics::text::vector::VectorFontBuilder;
 
+/// The data for the font that is embedded in Tetra (DejaVu Sans Mono).
+#[cfg(feature = "embedded_font")]
+pub(crate) const DEFAULT_FONT_DATA: &[u8] = include_bytes!("../resources/DejaVuSansMono.ttf");
+
 /// A font with an associated size, cached on the GPU.
 ///
 /// # Performance
@@ -89,6 +96,80 @@ impl Font {
     ) -> Result<Font> {
         VectorFontBuilder::from_file_data(data)?.with_size(ctx, size)
     }
+
+    /// Creates a `Font` from the font that is embedded in Tetra, with the given size.
+    ///
+    /// The embedded font is [DejaVu Sans Mono](https://dejavu-fonts.github.io/), which is
+    /// released under a [permissive license](https://github.com/17cupsofcoffee/tetra/blob/main/src/resources/LICENSE-DejaVu).
+    /// This is useful for prototyping, as it allows you to draw text without having to
+    /// load a font first.
+    ///
+    /// This function is only available if the `embedded_font` feature is enabled. This
+    /// feature is not enabled by default, as it adds around 330KB to the size of your
+    /// game's binary.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the GPU cache for the font
+    /// could not be created.
+    #[cfg(feature = "embedded_font")]
+    pub fn default(ctx: &mut Context, size: f32) -> Result<Font> {
+        Font::from_vector_file_data(ctx, DEFAULT_FONT_DATA, size)
+    }
+
+    /// Returns the vertical metrics of the font, in pixels.
+    ///
+    /// These can be used to align other graphics with the baseline of a piece of text.
+    pub fn metrics(&self) -> FontMetrics {
+        self.data.borrow().metrics()
+    }
+
+    /// Returns the texture atlas that the font's glyphs are cached in.
+    ///
+    /// This can be combined with [`Text::quads`] to draw text via your own batching.
+    ///
+    /// Note that the atlas is replaced with a larger texture when it runs out of space, so
+    /// the returned texture will become stale if new glyphs are added to the cache. To make
+    /// sure you have the right texture, call this after [`Text::quads`].
+    #[doc(alias = "atlas_texture")]
+    pub fn texture(&self) -> Texture {
+        self.data.borrow().texture().clone()
+    }
+
+    /// Returns the glyphs that are currently stored in the font's texture atlas, along
+    /// with the region of the [`texture`](Self::texture) that each one occupies.
+    ///
+    /// This is mainly useful for debugging - for example, you could draw the atlas to the
+    /// screen with each glyph outlined, to check how the glyphs are being packed and
+    /// rasterized.
+    ///
+    /// The glyphs are not returned in any particular order. A character may appear
+    /// more than once, as separate copies are cached for different subpixel offsets.
+    /// Characters that do not have any visible pixels (e.g. spaces) are not included.
+    pub fn atlas_glyphs(&self) -> Vec<AtlasGlyph> {
+        self.data.borrow().atlas_glyphs()
+    }
+
+    /// Lays out and draws a string to the screen (or to a canvas, if one is enabled),
+    /// using the default layout settings.
+    ///
+    /// This is a convenient way of drawing text that is only displayed once, or that changes
+    /// every frame anyway (e.g. debug overlays). The layout is recalculated on every call,
+    /// so for text that stays the same between frames, creating a [`Text`] and keeping it
+    /// around will be faster. The glyphs are still cached in the font's texture atlas.
+    ///
+    /// The `params` are applied in the same way as they are by [`Text::draw`].
+    pub fn draw_text<P>(&self, ctx: &mut Context, content: &str, params: P)
+    where
+        P: Into<DrawParams>,
+    {
+        let geometry =
+            self.data
+                .borrow_mut()
+                .render(&mut ctx.device, content, &TextLayout::default());
+
+        draw_geometry(ctx, self, &geometry, params.into());
+    }
 }
 
 impl Debug for Font {
@@ -97,6 +178,70 @@ impl Debug for Font {
     }
 }
 
+/// The vertical metrics of a [`Font`], in pixels.
+///
+/// When a [`Text`] is drawn, the top of the first line is at `0.0`, and the baseline of the
+/// first line is at `ascent` (rounded to the nearest pixel).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FontMetrics {
+    /// The distance from the baseline to the highest point of the font's glyphs.
+    pub ascent: f32,
+
+    /// The distance from the baseline to the lowest point of the font's glyphs.
+    ///
+    /// This is usually negative, as the lowest point is below the baseline.
+    pub descent: f32,
+
+    /// The additional space that should be left between lines, on top of the height of
+    /// the glyphs.
+    pub line_gap: f32,
+
+    /// The distance between the baselines of consecutive lines of text. This is equal to
+    /// `ascent - descent + line_gap`.
+    pub line_height: f32,
+}
+
+/// Determines how lines of [`Text`] that are wider than the available space are handled.
+///
+/// Lines are only ever cut between characters (after the text has been normalized), so a
+/// character will never be split in half.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Overflow {
+    /// Lines are drawn in full, regardless of how wide they are.
+    Visible,
+
+    /// Any characters that would extend past `max_width` are not drawn.
+    Clip {
+        /// The maximum width of each line, in pixels.
+        max_width: f32,
+    },
+
+    /// Lines that are wider than `max_width` are cut short, and an ellipsis ('…') is
+    /// appended to them. The ellipsis is included in the width.
+    Ellipsis {
+        /// The maximum width of each line, in pixels.
+        max_width: f32,
+    },
+
+    /// Lines that are wider than `max_width` are wrapped onto the next line. Explicit line
+    /// breaks (`\n`) in the text are always respected.
+    ///
+    /// Lines are broken after whitespace, and between the characters of CJK text (which
+    /// does not use spaces between words), following a simplified version of the
+    /// [Unicode Line Breaking Algorithm](https://www.unicode.org/reports/tr14/). Punctuation
+    /// is kept attached to the text it belongs to - for example, a line will not usually
+    /// start with '。' or end with '「'. Words that are too long to fit on a line by themselves
+    /// will be split between characters, and as this ignores the punctuation rules, a forced
+    /// split can still leave punctuation at the start or end of a line.
+    ///
+    /// Trailing whitespace is not counted towards the width of a wrapped line, and whitespace
+    /// at the start of a wrapped line is skipped.
+    Wrap {
+        /// The maximum width of each line, in pixels.
+        max_width: f32,
+    },
+}
+
 /// A piece of text that can be rendered.
 ///
 /// # Performance
@@ -107,6 +252,23 @@ impl Debug for Font {
 /// Cloning a `Text` is a fairly expensive operation, as it creates an entirely new copy of the
 /// object with its own cache.
 ///
+/// # Scaling
+///
+/// Text can be drawn at a different size to the one that its [`Font`] was rasterized at by
+/// setting the [`scale`](DrawParams::scale) of the `DrawParams` passed to [`draw`](Self::draw).
+/// This does not require the glyphs to be re-rasterized, so it is cheap enough to animate
+/// every frame (e.g. for a zoom effect).
+///
+/// The scale is applied to the text as a whole, around the origin of the `DrawParams` (which,
+/// if [`origin_normalized`](DrawParams::origin_normalized) is used, is resolved against the
+/// [bounds](Self::get_bounds) of the unscaled text). Layout settings such as the width used
+/// for [overflow](Self::set_overflow) are measured before scaling is applied.
+///
+/// As the glyphs are stored in a bitmap atlas, scaling the text up will make it look blurry
+/// (or blocky, when using [`FilterMode::Nearest`](crate::graphics::FilterMode::Nearest)).
+/// For the best quality, create the font at the largest size you will need, and scale
+/// down from there.
+///
 /// # Examples
 ///
 /// The [`text`](https://github.com/17cupsofcoffee/tetra/blob/main/examples/text.rs)
@@ -115,6 +277,7 @@ impl Debug for Font {
 pub struct Text {
     content: String,
     font: Font,
+    layout: TextLayout,
     geometry: Option<TextGeometry>,
 }
 
@@ -127,39 +290,107 @@ impl Text {
         Text {
             content: content.into(),
             font,
+            layout: TextLayout::default(),
             geometry: None,
         }
     }
 
     /// Draws the text to the screen (or to a canvas, if one is enabled).
+    ///
+    /// See the [type-level documentation](Self#scaling) for details of how the `scale` of
+    /// the params is applied.
     pub fn draw<P>(&mut self, ctx: &mut Context, params: P)
     where
         P: Into<DrawParams>,
     {
         self.update_geometry(ctx);
 
-        let params = params.into();
+        let geometry = self
+            .geometry
+            .as_ref()
+            .expect("geometry should have been generated");
 
-        let data = self.font.data.borrow();
-        graphics::set_texture(ctx, data.texture());
+        draw_geometry(ctx, &self.font, geometry, params.into());
+    }
+
+    /// Draws the text to the screen (or to a canvas, if one is enabled), calling the given
+    /// function to modify the parameters of each glyph before it is drawn.
+    ///
+    /// This can be used to animate individual glyphs - for example, to make text bob up and
+    /// down in a wave, or to cycle through colors.
+    ///
+    /// The function receives the index of the glyph and a set of parameters, which start out
+    /// as the [defaults](DrawParams::new). These are applied to the glyph before the params
+    /// for the whole text:
+    ///
+    /// * `position` offsets the glyph from where it would usually be drawn.
+    /// * `scale` and `rotation` are applied around the center of the glyph.
+    /// * `color` is multiplied with the color of the text.
+    ///
+    /// The `origin` and `color_blend` of the glyph parameters are ignored. Only visible glyphs
+    /// are drawn, so whitespace is not included when counting the index.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use tetra::graphics::text::Text;
+    /// # use tetra::math::Vec2;
+    /// # use tetra::Context;
+    /// # fn example(ctx: &mut Context, text: &mut Text, timer: f32) {
+    /// // `timer` is the number of seconds that have passed since the text appeared:
+    /// text.draw_with(ctx, Vec2::new(16.0, 16.0), |index, glyph| {
+    ///     glyph.position.y = (timer * 4.0 + index as f32 * 0.5).sin() * 4.0;
+    /// });
+    /// # }
+    /// ```
+    pub fn draw_with<P, F>(&mut self, ctx: &mut Context, params: P, mut modify: F)
+    where
+        P: Into<DrawParams>,
+        F: FnMut(usize, &mut DrawParams),
+    {
+        self.update_geometry(ctx);
 
         let geometry = self
             .geometry
             .as_ref()
             .expect("geometry should have been generated");
 
-        for quad in &geometry.quads {
+        let params = match geometry.bounds {
+            Some(bounds) => params.into().with_resolved_origin(bounds),
+            None => params.into(),
+        };
+
+        let data = self.font.data.borrow();
+        graphics::set_texture(ctx, data.texture());
+
+        for (index, quad) in geometry.quads.iter().enumerate() {
+            let mut glyph = DrawParams::new();
+            modify(index, &mut glyph);
+
+            let size = Vec2::new(quad.position.width, quad.position.height);
+            let center = quad.position.center() + glyph.position;
+
+            let glyph_params = DrawParams {
+                position: params.to_world(center, quad.position),
+                scale: params.scale * glyph.scale,
+                origin: size / 2.0,
+                origin_normalized: None,
+                rotation: params.rotation + glyph.rotation,
+                color: params.color * glyph.color,
+                color_blend: params.color_blend,
+            };
+
             graphics::push_quad(
                 ctx,
-                quad.position.x,
-                quad.position.y,
-                quad.position.right(),
-                quad.position.bottom(),
+                0.0,
+                0.0,
+                size.x,
+                size.y,
                 quad.uv.x,
                 quad.uv.y,
                 quad.uv.right(),
                 quad.uv.bottom(),
-                &params,
+                &glyph_params,
             );
         }
     }
@@ -195,6 +426,57 @@ impl Text {
         self.font = font;
     }
 
+    /// Returns whether kerning is applied to the text.
+    pub fn kerning(&self) -> bool {
+        self.layout.kerning
+    }
+
+    /// Sets whether kerning should be applied to the text.
+    ///
+    /// Kerning adjusts the spacing between specific pairs of characters (e.g. 'AV') to make
+    /// them look more natural, using the information provided by the font. This is enabled
+    /// by default.
+    ///
+    /// Calling this function will cause a re-layout of the text the next time it
+    /// is rendered.
+    pub fn set_kerning(&mut self, kerning: bool) {
+        self.geometry.take();
+        self.layout.kerning = kerning;
+    }
+
+    /// Returns the amount of extra space that is added between characters, in pixels.
+    pub fn letter_spacing(&self) -> f32 {
+        self.layout.letter_spacing
+    }
+
+    /// Sets the amount of extra space that should be added between characters, in pixels.
+    ///
+    /// Negative values will move the characters closer together. This defaults to `0.0`.
+    ///
+    /// Calling this function will cause a re-layout of the text the next time it
+    /// is rendered.
+    pub fn set_letter_spacing(&mut self, letter_spacing: f32) {
+        self.geometry.take();
+        self.layout.letter_spacing = letter_spacing;
+    }
+
+    /// Returns how lines that are too wide to fit are handled.
+    pub fn overflow(&self) -> Overflow {
+        self.layout.overflow
+    }
+
+    /// Sets how lines that are too wide to fit should be handled.
+    ///
+    /// This is useful for fitting text into fixed-width UI elements. Each line of the text
+    /// is handled separately. This defaults to [`Overflow::Visible`].
+    ///
+    /// Calling this function will cause a re-layout of the text the next time it
+    /// is rendered.
+    pub fn set_overflow(&mut self, overflow: Overflow) {
+        self.geometry.take();
+        self.layout.overflow = overflow;
+    }
+
     /// Appends the given character to the end of the text.
     ///
     /// Calling this function will cause a re-layout of the text the next time it
@@ -238,6 +520,61 @@ impl Text {
             .bounds
     }
 
+    /// Returns the number of lines in the text.
+    ///
+    /// If the text's layout needs calculating, this method will do so.
+    pub fn line_count(&mut self, ctx: &mut Context) -> usize {
+        self.update_geometry(ctx);
+
+        self.geometry
+            .as_ref()
+            .expect("geometry should have been generated")
+            .lines
+            .len()
+    }
+
+    /// Get the bounds of the specified line of the text, or [`None`] if the line does not exist.
+    ///
+    /// Unlike [`get_bounds`](Self::get_bounds), which measures the area covered by the rendered
+    /// glyphs, this measures the space that the line takes up in the text's layout. The
+    /// rectangle starts at the left edge of the text, is as wide as the total advance of the
+    /// line's characters, and is as tall as the font's line height. This means that empty
+    /// lines will still have a position, which makes this useful for implementing scrolling
+    /// and text selection.
+    ///
+    /// If the text's layout needs calculating, this method will do so.
+    ///
+    /// Note that this method will not take into account the positioning applied to the text via [`DrawParams`].
+    pub fn line_bounds(&mut self, ctx: &mut Context, line: usize) -> Option<Rectangle> {
+        self.update_geometry(ctx);
+
+        self.geometry
+            .as_ref()
+            .expect("geometry should have been generated")
+            .lines
+            .get(line)
+            .copied()
+    }
+
+    /// Returns the quads that make up the text, one per visible glyph.
+    ///
+    /// Each quad should be drawn using the font's [texture atlas](Font::texture). This can be
+    /// used to integrate text into your own batching, rather than drawing it via
+    /// [`draw`](Self::draw).
+    ///
+    /// If the text's layout needs calculating, this method will do so.
+    ///
+    /// Note that this method will not take into account the positioning applied to the text via [`DrawParams`].
+    pub fn quads(&mut self, ctx: &mut Context) -> &[TextQuad] {
+        self.update_geometry(ctx);
+
+        &self
+            .geometry
+            .as_ref()
+            .expect("geometry should have been generated")
+            .quads
+    }
+
     fn update_geometry(&mut self, ctx: &mut Context) {
         let mut data = self.font.data.borrow_mut();
 
@@ -247,8 +584,33 @@ impl Text {
         };
 
         if needs_render {
-            let new_geometry = data.render(&mut ctx.device, &self.content);
+            let new_geometry = data.render(&mut ctx.device, &self.content, &self.layout);
             self.geometry = Some(new_geometry);
         }
     }
 }
+
+fn draw_geometry(ctx: &mut Context, font: &Font, geometry: &TextGeometry, params: DrawParams) {
+    let params = match geometry.bounds {
+        Some(bounds) => params.with_resolved_origin(bounds),
+        None => params,
+    };
+
+    let data = font.data.borrow();
+    graphics::set_texture(ctx, data.texture());
+
+    for quad in &geometry.quads {
+        graphics::push_quad(
+            ctx,
+            quad.position.x,
+            quad.position.y,
+            quad.position.right(),
+            quad.position.bottom(),
+            quad.uv.x,
+            quad.uv.y,
+            quad.uv.right(),
+            quad.uv.bottom(),
+            &params,
+        );
+    }
+}
@@ -1,13 +1,18 @@
 use hashbrown::hash_map::Entry;
 use hashbrown::HashMap;
 use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
 
 use crate::error::Result;
 use crate::graphics::text::packer::ShelfPacker;
+use crate::graphics::text::{FontMetrics, Overflow};
 use crate::graphics::{Rectangle, Texture};
 use crate::math::Vec2;
 use crate::platform::GraphicsDevice;
 
+/// The character that is appended to text that has been truncated.
+const ELLIPSIS: char = '\u{2026}';
+
 /// The data produced by rasterizing a glyph from a font.
 pub(crate) struct RasterizedGlyph {
     /// The rasterized RGBA data.
@@ -73,22 +78,68 @@ pub(crate) trait Rasterizer {
     /// The ascent of the font.
     fn ascent(&self) -> f32;
 
+    /// The descent of the font.
+    fn descent(&self) -> f32;
+
+    /// The gap between lines of the font.
+    fn line_gap(&self) -> f32;
+
     /// The amount of kerning that should be applied between the given glyphs.
     fn kerning(&self, previous: char, current: char) -> f32;
 }
 
-/// An individual quad within a `TextGeometry`.
-#[derive(Debug, Clone)]
-pub(crate) struct TextQuad {
+/// Settings that control how a piece of text is laid out.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct TextLayout {
+    pub kerning: bool,
+    pub letter_spacing: f32,
+    pub overflow: Overflow,
+}
+
+impl Default for TextLayout {
+    fn default() -> TextLayout {
+        TextLayout {
+            kerning: true,
+            letter_spacing: 0.0,
+            overflow: Overflow::Visible,
+        }
+    }
+}
+
+/// An individual glyph within a piece of laid out text.
+///
+/// These can be obtained via [`Text::quads`](crate::graphics::text::Text::quads), and are
+/// useful if you want to integrate text rendering into your own batching.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextQuad {
+    /// The position of the glyph, relative to the top left of the text.
     pub position: Rectangle,
+
+    /// The region of the font's [texture atlas](crate::graphics::text::Font::texture) that
+    /// the glyph should be drawn from, in normalized texture coordinates (from `0.0` to `1.0`).
     pub uv: Rectangle,
 }
 
+/// A glyph that has been cached in a font's texture atlas.
+///
+/// These can be obtained via [`Font::atlas_glyphs`](crate::graphics::text::Font::atlas_glyphs),
+/// and are mainly useful for debugging font rendering.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AtlasGlyph {
+    /// The character that the glyph represents.
+    pub glyph: char,
+
+    /// The region of the font's [texture atlas](crate::graphics::text::Font::texture) that
+    /// the glyph is stored in, in pixels.
+    pub region: Rectangle,
+}
+
 /// The geometry that can be used to render a piece of text.
 #[derive(Debug, Clone)]
 pub(crate) struct TextGeometry {
     pub quads: Vec<TextQuad>,
     pub bounds: Option<Rectangle>,
+    pub lines: Vec<Rectangle>,
     pub resize_count: usize,
 }
 
@@ -116,6 +167,36 @@ impl FontCache {
         self.packer.texture()
     }
 
+    /// Returns the glyphs that are currently stored in the texture atlas.
+    pub fn atlas_glyphs(&self) -> Vec<AtlasGlyph> {
+        let (texture_width, texture_height) = self.packer.texture().size();
+
+        self.glyphs
+            .iter()
+            .filter_map(|(key, cached)| {
+                cached.as_ref().map(|cached| AtlasGlyph {
+                    glyph: key.glyph,
+                    region: Rectangle::new(
+                        cached.uv.x * texture_width as f32,
+                        cached.uv.y * texture_height as f32,
+                        cached.uv.width * texture_width as f32,
+                        cached.uv.height * texture_height as f32,
+                    ),
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the vertical metrics of the font.
+    pub fn metrics(&self) -> FontMetrics {
+        FontMetrics {
+            ascent: self.rasterizer.ascent(),
+            descent: self.rasterizer.descent(),
+            line_gap: self.rasterizer.line_gap(),
+            line_height: self.rasterizer.line_height(),
+        }
+    }
+
     /// Returns the number of times that the cache has been resized.
     ///
     /// This can be compared against the `resize_count` of the `TextGeometry` to determine
@@ -125,9 +206,14 @@ impl FontCache {
     }
 
     /// Generates the geometry for the given string, resizing the texture atlas if needed.
-    pub fn render(&mut self, device: &mut GraphicsDevice, input: &str) -> TextGeometry {
+    pub fn render(
+        &mut self,
+        device: &mut GraphicsDevice,
+        input: &str,
+        layout: &TextLayout,
+    ) -> TextGeometry {
         loop {
-            match self.try_render(device, input) {
+            match self.try_render(device, input, layout) {
                 Ok(new_geometry) => return new_geometry,
                 Err(CacheError::OutOfSpace) => {
                     self.resize(device).expect("Failed to resize font texture");
@@ -142,93 +228,126 @@ impl FontCache {
         &mut self,
         device: &mut GraphicsDevice,
         input: &str,
+        layout: &TextLayout,
     ) -> std::result::Result<TextGeometry, CacheError> {
         let line_height = self.rasterizer.line_height().round();
+        let ascent = self.rasterizer.ascent().round();
 
         let mut quads = Vec::new();
+        let mut lines = Vec::new();
 
-        let mut cursor = Vec2::new(0.0, self.rasterizer.ascent().round());
+        let mut cursor = Vec2::new(0.0, ascent);
         let mut last_glyph: Option<char> = None;
         let mut text_bounds: Option<Rectangle> = None;
 
-        for ch in input.nfc() {
-            if ch.is_control() {
-                if ch == '\n' {
-                    cursor.x = 0.0;
-                    cursor.y += line_height;
-                    last_glyph = None;
-                }
-
-                continue;
+        let chars: Vec<char> = input.nfc().collect();
+
+        let lines_to_render: Vec<Vec<char>> = chars
+            .split(|&ch| ch == '\n')
+            .flat_map(|line| apply_overflow(&*self.rasterizer, line, layout))
+            .collect();
+
+        for (i, line) in lines_to_render.iter().enumerate() {
+            if i > 0 {
+                lines.push(Rectangle::new(
+                    0.0,
+                    cursor.y - ascent,
+                    cursor.x,
+                    line_height,
+                ));
+
+                cursor.x = 0.0;
+                cursor.y += line_height;
+                last_glyph = None;
             }
 
-            let subpixel_offset = cursor.map(f32::fract);
-
-            // This is a bit of a hack to allow us to hash the subpixel offset:
-            //
-            // * Multiply by ten, so that the first decimal place becomes the integer part.
-            // * Round to the closest number.
-            //
-            // So 0.05 becomes 0, 0.57 becomes 6, 0.99 becomes 10, etc. This effectively gives us
-            // up to eleven different subpixel rendered versions of each glyph, which strikes
-            // a nice balance between prettiness and reasonable texture size.
-            //
-            // We could wrap back around to 0 instead of 10 being a valid value, which would make
-            // the distribution a bit more even, but I don't know if it's worth it.
-            let subpixel_x = (subpixel_offset.x * 10.0).round() as u32;
-            let subpixel_y = (subpixel_offset.y * 10.0).round() as u32;
-
-            let cache_key = CacheKey {
-                glyph: ch,
-                subpixel_x,
-                subpixel_y,
-            };
-
-            let cached_glyph = match self.glyphs.entry(cache_key) {
-                Entry::Occupied(e) => e.into_mut(),
-                Entry::Vacant(e) => {
-                    let outline = match self.rasterizer.rasterize(ch, cursor) {
-                        Some(r) => Some(add_glyph_to_texture(device, &mut self.packer, &r)?),
-                        None => None,
-                    };
-
-                    e.insert(outline)
+            for &ch in line {
+                if ch.is_control() {
+                    continue;
                 }
-            };
 
-            if let Some(last_glyph) = last_glyph.take() {
-                cursor.x += self.rasterizer.kerning(last_glyph, ch);
-            }
+                // This needs to be applied before the glyph is rasterized, as it affects
+                // the subpixel offset.
+                if let Some(last_glyph) = last_glyph.take() {
+                    if layout.kerning {
+                        cursor.x += self.rasterizer.kerning(last_glyph, ch);
+                    }
 
-            if let Some(CachedGlyph { mut bounds, uv }) = *cached_glyph {
-                // The glyph's bounds are relative, so we need to combine them
-                // with the cursor to make them absolute.
-                bounds.x += cursor.x;
-                bounds.y += cursor.y;
+                    cursor.x += layout.letter_spacing;
+                }
 
-                // Expand the cached bounds of the text geometry:
-                match &mut text_bounds {
-                    Some(existing) => {
-                        *existing = bounds.combine(existing);
+                let subpixel_offset = cursor.map(f32::fract);
+
+                // This is a bit of a hack to allow us to hash the subpixel offset:
+                //
+                // * Multiply by ten, so that the first decimal place becomes the integer part.
+                // * Round to the closest number.
+                //
+                // So 0.05 becomes 0, 0.57 becomes 6, 0.99 becomes 10, etc. This effectively gives us
+                // up to eleven different subpixel rendered versions of each glyph, which strikes
+                // a nice balance between prettiness and reasonable texture size.
+                //
+                // We could wrap back around to 0 instead of 10 being a valid value, which would make
+                // the distribution a bit more even, but I don't know if it's worth it.
+                let subpixel_x = (subpixel_offset.x * 10.0).round() as u32;
+                let subpixel_y = (subpixel_offset.y * 10.0).round() as u32;
+
+                let cache_key = CacheKey {
+                    glyph: ch,
+                    subpixel_x,
+                    subpixel_y,
+                };
+
+                let cached_glyph = match self.glyphs.entry(cache_key) {
+                    Entry::Occupied(e) => e.into_mut(),
+                    Entry::Vacant(e) => {
+                        let outline = match self.rasterizer.rasterize(ch, cursor) {
+                            Some(r) => Some(add_glyph_to_texture(device, &mut self.packer, &r)?),
+                            None => None,
+                        };
+
+                        e.insert(outline)
                     }
-                    None => {
-                        text_bounds.replace(bounds);
+                };
+
+                if let Some(CachedGlyph { mut bounds, uv }) = *cached_glyph {
+                    // The glyph's bounds are relative, so we need to combine them
+                    // with the cursor to make them absolute.
+                    bounds.x += cursor.x;
+                    bounds.y += cursor.y;
+
+                    // Expand the cached bounds of the text geometry:
+                    match &mut text_bounds {
+                        Some(existing) => {
+                            *existing = bounds.combine(existing);
+                        }
+                        None => {
+                            text_bounds.replace(bounds);
+                        }
                     }
-                }
 
-                quads.push(TextQuad {
-                    position: bounds,
-                    uv,
-                });
-            }
+                    quads.push(TextQuad {
+                        position: bounds,
+                        uv,
+                    });
+                }
 
-            cursor.x += self.rasterizer.advance(ch);
+                cursor.x += self.rasterizer.advance(ch);
 
-            last_glyph = Some(ch);
+                last_glyph = Some(ch);
+            }
         }
 
+        lines.push(Rectangle::new(
+            0.0,
+            cursor.y - ascent,
+            cursor.x,
+            line_height,
+        ));
+
         Ok(TextGeometry {
             quads,
+            lines,
             resize_count: self.resize_count,
             bounds: text_bounds,
         })
@@ -250,6 +369,359 @@ impl FontCache {
     }
 }
 
+/// Measures the width of a line of text one character at a time, so that the width of
+/// each prefix of the line can be found without re-measuring it from the start.
+struct LineMeasurer<'a> {
+    rasterizer: &'a dyn Rasterizer,
+    layout: &'a TextLayout,
+    width: f32,
+    last_glyph: Option<char>,
+}
+
+impl<'a> LineMeasurer<'a> {
+    fn new(rasterizer: &'a dyn Rasterizer, layout: &'a TextLayout) -> LineMeasurer<'a> {
+        LineMeasurer {
+            rasterizer,
+            layout,
+            width: 0.0,
+            last_glyph: None,
+        }
+    }
+
+    /// Returns the width of the characters that have been measured so far.
+    fn width(&self) -> f32 {
+        self.width
+    }
+
+    /// Returns what the width would be if the given character was measured next.
+    fn width_with(&self, ch: char) -> f32 {
+        if ch.is_control() {
+            return self.width;
+        }
+
+        let mut width = self.width;
+
+        if let Some(last_glyph) = self.last_glyph {
+            if self.layout.kerning {
+                width += self.rasterizer.kerning(last_glyph, ch);
+            }
+
+            width += self.layout.letter_spacing;
+        }
+
+        width + self.rasterizer.advance(ch)
+    }
+
+    /// Adds a character to the end of the line.
+    fn push(&mut self, ch: char) {
+        self.width = self.width_with(ch);
+
+        if !ch.is_control() {
+            self.last_glyph = Some(ch);
+        }
+    }
+}
+
+/// Measures the width of a single line of text.
+fn measure_line(rasterizer: &dyn Rasterizer, line: &[char], layout: &TextLayout) -> f32 {
+    let mut measurer = LineMeasurer::new(rasterizer, layout);
+
+    for &ch in line {
+        measurer.push(ch);
+    }
+
+    measurer.width()
+}
+
+/// Returns the index of the end of each grapheme cluster (i.e. each user-perceived
+/// character) in a line of text.
+///
+/// Text should only be cut at these positions, so that combining marks, emoji sequences
+/// and the like are never split apart.
+fn grapheme_ends(line: &[char]) -> Vec<usize> {
+    let text: String = line.iter().collect();
+
+    text.graphemes(true)
+        .scan(0, |end, grapheme| {
+            *end += grapheme.chars().count();
+            Some(*end)
+        })
+        .collect()
+}
+
+/// Returns the characters of a single line of text that should be rendered, taking
+/// the overflow behaviour into account.
+///
+/// If the text is being wrapped, the line may be split into multiple lines.
+fn apply_overflow(
+    rasterizer: &dyn Rasterizer,
+    line: &[char],
+    layout: &TextLayout,
+) -> Vec<Vec<char>> {
+    match layout.overflow {
+        Overflow::Visible => vec![line.to_vec()],
+
+        Overflow::Wrap { max_width } => wrap_line(rasterizer, line, layout, max_width),
+
+        Overflow::Clip { max_width } => {
+            let mut measurer = LineMeasurer::new(rasterizer, layout);
+            let mut end = 0;
+
+            for grapheme_end in grapheme_ends(line) {
+                for &ch in &line[end..grapheme_end] {
+                    measurer.push(ch);
+                }
+
+                if measurer.width() > max_width {
+                    break;
+                }
+
+                end = grapheme_end;
+            }
+
+            vec![line[..end].to_vec()]
+        }
+
+        Overflow::Ellipsis { max_width } => {
+            if measure_line(rasterizer, line, layout) <= max_width {
+                return vec![line.to_vec()];
+            }
+
+            let mut measurer = LineMeasurer::new(rasterizer, layout);
+            let mut end = 0;
+
+            for grapheme_end in grapheme_ends(line) {
+                for &ch in &line[end..grapheme_end] {
+                    measurer.push(ch);
+                }
+
+                if measurer.width_with(ELLIPSIS) > max_width {
+                    break;
+                }
+
+                end = grapheme_end;
+            }
+
+            // Trailing whitespace before the ellipsis looks odd, so trim it.
+            let mut output = trim_end(&line[..end]).to_vec();
+            output.push(ELLIPSIS);
+
+            vec![output]
+        }
+    }
+}
+
+/// Splits a single line of text into multiple lines, none of which are wider than
+/// `max_width` (unless a single character is wider than that).
+///
+/// Lines are split at the last break opportunity that fits (see [`can_break_between`]).
+/// If a line contains no break opportunities that fit (e.g. a long word), it will be
+/// split between grapheme clusters instead. Whitespace at the start of a wrapped line
+/// is skipped.
+fn wrap_line(
+    rasterizer: &dyn Rasterizer,
+    line: &[char],
+    layout: &TextLayout,
+    max_width: f32,
+) -> Vec<Vec<char>> {
+    let mut is_grapheme_end = vec![false; line.len() + 1];
+
+    for end in grapheme_ends(line) {
+        is_grapheme_end[end] = true;
+    }
+
+    let mut output = Vec::new();
+    let mut start = 0;
+
+    loop {
+        let rest = &line[start..];
+
+        let mut measurer = LineMeasurer::new(rasterizer, layout);
+        let mut has_content = false;
+        let mut split = None;
+        let mut forced_split = None;
+        let mut overflow = None;
+
+        for (i, &ch) in rest.iter().enumerate() {
+            // Everything before this character is known to fit, so we can split here -
+            // as long as that wouldn't leave the line empty, or split a grapheme.
+            if has_content && is_grapheme_end[start + i] {
+                if can_break_between(rest[i - 1], ch) {
+                    split = Some(i);
+                }
+
+                forced_split = Some(i);
+            }
+
+            measurer.push(ch);
+
+            // Trailing whitespace doesn't count towards the width, so only check for
+            // overflow once there's something visible after it.
+            if !ch.is_whitespace() {
+                if measurer.width() > max_width {
+                    overflow = Some(i);
+                    break;
+                }
+
+                has_content = true;
+            }
+        }
+
+        let overflow = match overflow {
+            Some(overflow) => overflow,
+            None => {
+                output.push(rest.to_vec());
+                return output;
+            }
+        };
+
+        // A line always has to contain at least one grapheme, otherwise we'd loop forever.
+        let end = split.or(forced_split).unwrap_or_else(|| {
+            (overflow + 1..=rest.len())
+                .find(|&end| is_grapheme_end[start + end])
+                .unwrap_or(rest.len())
+        });
+
+        output.push(trim_end(&rest[..end]).to_vec());
+        start += end;
+
+        while start < line.len() && line[start].is_whitespace() {
+            start += 1;
+        }
+
+        if start == line.len() {
+            return output;
+        }
+    }
+}
+
+/// Removes any trailing whitespace from a line, as it shouldn't count towards the
+/// width when wrapping.
+fn trim_end(line: &[char]) -> &[char] {
+    let end = line
+        .iter()
+        .rposition(|ch| !ch.is_whitespace())
+        .map_or(0, |i| i + 1);
+
+    &line[..end]
+}
+
+/// Returns whether a line can be wrapped between the two given characters.
+///
+/// This is a simplified version of the rules in the [Unicode Line Breaking Algorithm](https://www.unicode.org/reports/tr14/):
+///
+/// * Lines can be broken after whitespace (other than non-breaking spaces).
+/// * Lines can be broken before or after CJK characters, as these languages do not
+///   use spaces between words.
+/// * Lines can't be broken before closing punctuation, or after opening punctuation,
+///   so that these stay attached to the text they surround.
+fn can_break_between(previous: char, next: char) -> bool {
+    if next.is_whitespace() {
+        return false;
+    }
+
+    if previous.is_whitespace() {
+        return !matches!(previous, '\u{00A0}' | '\u{2007}' | '\u{202F}');
+    }
+
+    if prohibits_break_before(next) || prohibits_break_after(previous) {
+        return false;
+    }
+
+    is_cjk(previous) || is_cjk(next)
+}
+
+/// Returns whether a character is part of a CJK script, where lines can usually be
+/// broken between any two characters.
+fn is_cjk(ch: char) -> bool {
+    matches!(ch,
+        '\u{2E80}'..='\u{2FDF}' // CJK Radicals
+        | '\u{3000}'..='\u{303F}' // CJK Symbols and Punctuation
+        | '\u{3040}'..='\u{30FF}' // Hiragana and Katakana
+        | '\u{3100}'..='\u{31FF}' // Bopomofo, Hangul Compatibility Jamo, etc.
+        | '\u{3400}'..='\u{4DBF}' // CJK Unified Ideographs Extension A
+        | '\u{4E00}'..='\u{9FFF}' // CJK Unified Ideographs
+        | '\u{AC00}'..='\u{D7AF}' // Hangul Syllables
+        | '\u{F900}'..='\u{FAFF}' // CJK Compatibility Ideographs
+        | '\u{FF00}'..='\u{FFEF}' // Halfwidth and Fullwidth Forms
+        | '\u{20000}'..='\u{3FFFF}' // Supplementary ideographic planes
+    )
+}
+
+/// Returns whether a character should never appear at the start of a line (closing
+/// punctuation, small kana, etc).
+fn prohibits_break_before(ch: char) -> bool {
+    matches!(
+        ch,
+        ')' | ']'
+            | '}'
+            | ','
+            | '.'
+            | '!'
+            | '?'
+            | ':'
+            | ';'
+            | '\u{2026}' // …
+            | '\u{2025}' // ‥
+            | '\u{3001}' // 、
+            | '\u{3002}' // 。
+            | '\u{3005}' // 々
+            | '\u{3009}' // 〉
+            | '\u{300B}' // 》
+            | '\u{300D}' // 」
+            | '\u{300F}' // 』
+            | '\u{3011}' // 】
+            | '\u{3015}' // 〕
+            | '\u{3017}' // 〗
+            | '\u{3019}' // 〙
+            | '\u{309B}'..='\u{309E}' // ゛ ゜ ゝ ゞ
+            | '\u{30A0}' // ゠
+            | '\u{30FB}'..='\u{30FE}' // ・ ー ヽ ヾ
+            | '\u{3041}' | '\u{3043}' | '\u{3045}' | '\u{3047}' | '\u{3049}' // Small hiragana
+            | '\u{3063}' | '\u{3083}' | '\u{3085}' | '\u{3087}' | '\u{308E}'
+            | '\u{30A1}' | '\u{30A3}' | '\u{30A5}' | '\u{30A7}' | '\u{30A9}' // Small katakana
+            | '\u{30C3}' | '\u{30E3}' | '\u{30E5}' | '\u{30E7}' | '\u{30EE}'
+            | '\u{30F5}' | '\u{30F6}'
+            | '\u{FF01}' // ！
+            | '\u{FF09}' // ）
+            | '\u{FF0C}' // ，
+            | '\u{FF0E}' // ．
+            | '\u{FF1A}' // ：
+            | '\u{FF1B}' // ；
+            | '\u{FF1F}' // ？
+            | '\u{FF3D}' // ］
+            | '\u{FF5D}' // ｝
+            | '\u{FF61}' // ｡
+            | '\u{FF63}' // ｣
+            | '\u{FF64}' // ､
+            | '\u{FF70}' // ｰ
+    )
+}
+
+/// Returns whether a character should never appear at the end of a line (opening
+/// punctuation).
+fn prohibits_break_after(ch: char) -> bool {
+    matches!(
+        ch,
+        '(' | '['
+            | '{'
+            | '\u{2018}' // ‘
+            | '\u{201C}' // “
+            | '\u{3008}' // 〈
+            | '\u{300A}' // 《
+            | '\u{300C}' // 「
+            | '\u{300E}' // 『
+            | '\u{3010}' // 【
+            | '\u{3014}' // 〔
+            | '\u{3016}' // 〖
+            | '\u{3018}' // 〘
+            | '\u{FF08}' // （
+            | '\u{FF3B}' // ［
+            | '\u{FF5B}' // ｛
+            | '\u{FF62}' // ｢
+    )
+}
+
 /// Adds a rasterized glyph to the texture atlas.
 ///
 /// This is a free function rather than a method to avoid borrow checker issues.
@@ -279,3 +751,183 @@ fn add_glyph_to_texture(
         ),
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A rasterizer where every character is 10 pixels wide.
+    struct MonospaceRasterizer;
+
+    impl Rasterizer for MonospaceRasterizer {
+        fn rasterize(&self, _: char, _: Vec2<f32>) -> Option<RasterizedGlyph> {
+            None
+        }
+
+        fn advance(&self, _: char) -> f32 {
+            10.0
+        }
+
+        fn line_height(&self) -> f32 {
+            10.0
+        }
+
+        fn ascent(&self) -> f32 {
+            10.0
+        }
+
+        fn descent(&self) -> f32 {
+            0.0
+        }
+
+        fn line_gap(&self) -> f32 {
+            0.0
+        }
+
+        fn kerning(&self, _: char, _: char) -> f32 {
+            0.0
+        }
+    }
+
+    fn overflow(text: &str, overflow: Overflow) -> Vec<String> {
+        let chars: Vec<char> = text.chars().collect();
+        let layout = TextLayout {
+            overflow,
+            ..TextLayout::default()
+        };
+
+        apply_overflow(&MonospaceRasterizer, &chars, &layout)
+            .into_iter()
+            .map(|line| line.into_iter().collect())
+            .collect()
+    }
+
+    fn break_points(text: &str) -> Vec<usize> {
+        let chars: Vec<char> = text.chars().collect();
+
+        (1..chars.len())
+            .filter(|&i| can_break_between(chars[i - 1], chars[i]))
+            .collect()
+    }
+
+    #[test]
+    fn latin_breaks_after_spaces() {
+        assert_eq!(vec![7, 13], break_points("hello  world again"));
+        assert!(break_points("non\u{00A0}breaking").is_empty());
+    }
+
+    #[test]
+    fn cjk_breaks_between_characters() {
+        // 日本語のテキスト
+        assert_eq!(vec![1, 2, 3, 4, 5, 6, 7], break_points("日本語のテキスト"));
+
+        // Latin words embedded in CJK text stay together, but can be broken around.
+        assert_eq!(vec![1, 6], break_points("私Tetraが"));
+    }
+
+    #[test]
+    fn cjk_punctuation_is_kept_attached() {
+        // 「はい」と言った。
+        assert_eq!(vec![2, 4, 5, 7], break_points("「はい」と言った。"));
+    }
+
+    #[test]
+    fn clip_cuts_between_graphemes() {
+        let clip = |max_width| overflow("abx\u{301}yz", Overflow::Clip { max_width });
+
+        assert_eq!(vec!["abx\u{301}yz"], clip(60.0));
+        assert_eq!(vec!["abx\u{301}"], clip(45.0));
+
+        // The combining accent would fit within 35 pixels, but the 'x' can't be drawn without it.
+        assert_eq!(vec!["ab"], clip(35.0));
+
+        // Flags are made up of two regional indicator characters.
+        assert_eq!(
+            vec!["\u{1F1EF}\u{1F1F5}"],
+            overflow(
+                "\u{1F1EF}\u{1F1F5}\u{1F1EF}\u{1F1F5}",
+                Overflow::Clip { max_width: 30.0 }
+            )
+        );
+    }
+
+    #[test]
+    fn ellipsis_cuts_between_graphemes() {
+        let ellipsis = |max_width| overflow("abx\u{301}yz", Overflow::Ellipsis { max_width });
+
+        assert_eq!(vec!["abx\u{301}yz"], ellipsis(60.0));
+        assert_eq!(vec!["abx\u{301}\u{2026}"], ellipsis(55.0));
+        assert_eq!(vec!["ab\u{2026}"], ellipsis(45.0));
+        assert_eq!(vec!["\u{2026}"], ellipsis(5.0));
+    }
+
+    #[test]
+    fn ellipsis_trims_trailing_whitespace() {
+        assert_eq!(
+            vec!["ab\u{2026}"],
+            overflow("ab  cdefgh", Overflow::Ellipsis { max_width: 45.0 })
+        );
+    }
+
+    fn wrap(text: &str, max_width: f32) -> Vec<String> {
+        overflow(text, Overflow::Wrap { max_width })
+    }
+
+    #[test]
+    fn wrap_breaks_at_last_opportunity_that_fits() {
+        assert_eq!(vec!["hello", "world"], wrap("hello world", 80.0));
+        assert_eq!(vec!["one two", "three"], wrap("one two three", 80.0));
+    }
+
+    #[test]
+    fn wrap_splits_long_words() {
+        assert_eq!(vec!["abcd", "efgh", "ij"], wrap("abcdefghij", 40.0));
+        assert_eq!(vec!["a", "bcdefg", "hij"], wrap("a bcdefghij", 60.0));
+    }
+
+    #[test]
+    fn wrap_does_not_split_graphemes() {
+        assert_eq!(vec!["ab", "x\u{301}y"], wrap("abx\u{301}y", 35.0));
+    }
+
+    #[test]
+    fn wrap_keeps_at_least_one_grapheme_per_line() {
+        assert_eq!(vec!["a", "b", "c"], wrap("abc", 5.0));
+        assert_eq!(vec!["x\u{301}", "y"], wrap("x\u{301}y", 5.0));
+    }
+
+    #[test]
+    fn wrap_ignores_trailing_whitespace() {
+        assert_eq!(vec!["hello   "], wrap("hello   ", 50.0));
+        assert_eq!(vec!["hello", "world  "], wrap("hello   world  ", 50.0));
+    }
+
+    #[test]
+    fn wrap_skips_leading_whitespace_after_split() {
+        // The forced split falls just before the spaces, which shouldn't start the next line.
+        assert_eq!(vec!["abcd", "efgh"], wrap("abcd\u{00A0}\u{00A0}efgh", 40.0));
+
+        // Leading whitespace can't be split off into an empty line of its own.
+        assert_eq!(vec!["  ab", "cdef"], wrap("  abcdef", 40.0));
+    }
+
+    #[test]
+    fn wrap_handles_explicit_line_breaks() {
+        // Explicit line breaks are split out before overflow is applied.
+        let lines: Vec<String> = "hello world\n\nfoo"
+            .split('\n')
+            .flat_map(|line| wrap(line, 50.0))
+            .collect();
+
+        assert_eq!(vec!["hello", "world", "", "foo"], lines);
+    }
+
+    #[test]
+    fn wrap_can_start_line_with_punctuation_when_forced() {
+        // There's nowhere else to break, so the closing punctuation ends up on its own line.
+        assert_eq!(vec!["本", "。"], wrap("本。", 10.0));
+
+        // Otherwise, it stays attached to the previous character.
+        assert_eq!(vec!["日", "本。"], wrap("日本。", 20.0));
+    }
+}
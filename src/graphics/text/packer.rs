@@ -1,5 +1,5 @@
 use crate::error::Result;
-use crate::graphics::{FilterMode, Texture};
+use crate::graphics::{FilterMode, Texture, TextureFormat};
 use crate::platform::GraphicsDevice;
 
 /// An individual shelf within the packed atlas, tracking how much space
@@ -33,6 +33,7 @@ impl ShelfPacker {
                 texture_width,
                 texture_height,
                 FilterMode::Nearest,
+                TextureFormat::Rgba8,
             )?,
             shelves: Vec::new(),
             next_y: Self::PADDING,
@@ -51,8 +52,13 @@ impl ShelfPacker {
         texture_width: i32,
         texture_height: i32,
     ) -> Result {
-        self.texture =
-            Texture::with_device_empty(device, texture_width, texture_height, FilterMode::Nearest)?;
+        self.texture = Texture::with_device_empty(
+            device,
+            texture_width,
+            texture_height,
+            FilterMode::Nearest,
+            TextureFormat::Rgba8,
+        )?;
 
         self.shelves.clear();
         self.next_y = Self::PADDING;
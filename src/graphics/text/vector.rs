@@ -86,6 +86,18 @@ where
         scaled_font.ascent()
     }
 
+    fn descent(&self) -> f32 {
+        let scaled_font = self.font.as_scaled(self.scale);
+
+        scaled_font.descent()
+    }
+
+    fn line_gap(&self) -> f32 {
+        let scaled_font = self.font.as_scaled(self.scale);
+
+        scaled_font.line_gap()
+    }
+
     fn kerning(&self, previous: char, current: char) -> f32 {
         let scaled_font = self.font.as_scaled(self.scale);
 
@@ -139,8 +151,11 @@ impl VectorFontBuilder {
     where
         P: AsRef<Path>,
     {
-        let font_bytes = fs::read(path)?;
-        let font = FontVec::try_from_vec(font_bytes).map_err(|_| TetraError::InvalidFont)?;
+        VectorFontBuilder::from_vec(fs::read(path)?)
+    }
+
+    pub(crate) fn from_vec(data: Vec<u8>) -> Result<VectorFontBuilder> {
+        let font = FontVec::try_from_vec(data).map_err(|_| TetraError::InvalidFont)?;
 
         Ok(VectorFontBuilder {
             data: VectorFontData::Owned(Rc::new(font)),
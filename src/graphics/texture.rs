@@ -1,12 +1,24 @@
 //! Functions and types relating to textures.
 
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
+#[cfg(feature = "texture_gif")]
+use std::io::Cursor;
+use std::io::{BufReader, Read, Seek};
 use std::path::Path;
 use std::rc::Rc;
+#[cfg(feature = "texture_gif")]
+use std::time::Duration;
+
+#[cfg(feature = "texture_gif")]
+use image::codecs::gif::GifDecoder;
+#[cfg(feature = "texture_gif")]
+use image::error::{ImageError, LimitError, LimitErrorKind};
+use image::AnimationDecoder;
 
 use crate::error::{Result, TetraError};
 use crate::fs;
-use crate::graphics::{self, DrawParams, Rectangle};
+use crate::graphics::{self, Color, DrawParams, Rectangle, WrapMode};
+use crate::math::Vec2;
 use crate::platform::{GraphicsDevice, RawTexture};
 use crate::Context;
 
@@ -14,12 +26,24 @@ use crate::Context;
 pub(crate) struct TextureSharedData {
     pub(crate) handle: RawTexture,
     filter_mode: Cell<FilterMode>,
+    wrap_mode: Cell<WrapMode>,
+    anisotropy: Cell<f32>,
+    format: TextureFormat,
+    alpha_mask: RefCell<Option<Vec<u8>>>,
+}
+
+impl TextureSharedData {
+    /// Discards the cached copy of the texture's alpha channel, so that it will be read back
+    /// from the GPU the next time it is needed.
+    pub(crate) fn invalidate_alpha_mask(&self) {
+        self.alpha_mask.replace(None);
+    }
 }
 
 impl PartialEq for TextureSharedData {
     fn eq(&self, other: &TextureSharedData) -> bool {
-        // filter_mode should always match what's set on the GPU,
-        // so we can ignore it for equality checks.
+        // filter_mode, anisotropy and format should always match what's set on the GPU,
+        // and alpha_mask is just a cache, so we can ignore them for equality checks.
 
         self.handle.eq(&other.handle)
     }
@@ -52,7 +76,8 @@ impl PartialEq for TextureSharedData {
 /// Cloning a `Texture` is a very cheap operation, as the underlying data is shared between the
 /// original instance and the clone via [reference-counting](https://doc.rust-lang.org/std/rc/struct.Rc.html).
 /// This does mean, however, that updating a `Texture` (for example, changing its filter mode) will also
-/// update any other clones of that `Texture`.
+/// update any other clones of that `Texture`. If you need an independent copy, use
+/// [`deep_clone`](Texture::deep_clone) instead.
 ///
 /// # Examples
 ///
@@ -118,10 +143,49 @@ impl Texture {
         )
     }
 
+    /// Creates a new texture from a reader, which provides data encoded in one of Tetra's
+    /// supported file formats (except for TGA).
+    ///
+    /// This is useful if your assets are stored somewhere other than the filesystem (e.g. in
+    /// an archive), as the data can be decoded as it is read, rather than having to be
+    /// buffered into memory first. If your reader can't seek, read the data into a `Vec`
+    /// and use [`from_file_data`](Self::from_file_data) instead.
+    ///
+    /// The format will be determined based on the 'magic bytes' at the beginning of the
+    /// data, in the same way as [`from_file_data`](Self::from_file_data).
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`] will be returned if the underlying graphics API encounters an error.
+    /// * [`TetraError::InvalidTexture`] will be returned if the data could not be read, or if
+    /// the texture data was invalid.
+    pub fn from_reader<R>(ctx: &mut Context, reader: R) -> Result<Texture>
+    where
+        R: Read + Seek,
+    {
+        let image = image::io::Reader::new(BufReader::new(reader))
+            .with_guessed_format()
+            .map_err(|e| TetraError::InvalidTexture(e.into()))?
+            .decode()
+            .map_err(TetraError::InvalidTexture)?
+            .to_rgba8();
+
+        let (width, height) = image.dimensions();
+
+        Texture::from_rgba(
+            ctx,
+            width as i32,
+            height as i32,
+            image.into_raw().as_slice(),
+        )
+    }
+
     /// Creates a new texture from a slice of RGBA pixel data.
     ///
     /// This is useful if you wish to create a texture at runtime.
     ///
+    /// The texture will be created with the [default texture format](graphics::get_default_texture_format).
+    ///
     /// This method requires you to provide enough data to fill the texture.
     /// If you provide too little data, an error will be returned.
     /// If you provide too much data, it will be truncated.
@@ -137,6 +201,67 @@ impl Texture {
             height,
             data,
             ctx.graphics.default_filter_mode,
+            ctx.graphics.default_texture_format,
+        )
+    }
+
+    /// Creates a new texture, filled with a solid color.
+    ///
+    /// This is useful for placeholder art, or for solid colored UI elements. If you just
+    /// need a 1x1 white texture to stretch and tint, use [`graphics::white_texture`] instead.
+    ///
+    /// The texture will be created with the [default texture format](graphics::get_default_texture_format).
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::InvalidTexture`] will be returned if the texture is too large.
+    /// * [`TetraError::PlatformError`] will be returned if the underlying graphics API encounters an error.
+    pub fn filled(ctx: &mut Context, width: i32, height: i32, color: Color) -> Result<Texture> {
+        let pixel: Vec<u8> = [color.r, color.g, color.b, color.a]
+            .iter()
+            .map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8)
+            .collect();
+
+        // The graphics API measures sizes using i32, so the length has to fit in one.
+        let len = width
+            .max(0)
+            .checked_mul(height.max(0))
+            .and_then(|len| len.checked_mul(4))
+            .ok_or_else(|| {
+                TetraError::InvalidTexture(ImageError::Limits(LimitError::from_kind(
+                    LimitErrorKind::DimensionError,
+                )))
+            })?;
+
+        let data: Vec<u8> = pixel.iter().cycle().take(len as usize).copied().collect();
+
+        Texture::from_rgba(ctx, width, height, &data)
+    }
+
+    /// Creates a new texture from a slice of RGBA pixel data, using the specified format.
+    ///
+    /// This method requires you to provide enough data to fill the texture.
+    /// If you provide too little data, an error will be returned.
+    /// If you provide too much data, it will be truncated.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::NotEnoughData`] will be returned if not enough data is provided to fill
+    /// the texture. This is to prevent the graphics API from trying to read uninitialized memory.
+    pub fn from_rgba_with_format(
+        ctx: &mut Context,
+        width: i32,
+        height: i32,
+        data: &[u8],
+        format: TextureFormat,
+    ) -> Result<Texture> {
+        Texture::with_device(
+            &mut ctx.device,
+            width,
+            height,
+            data,
+            ctx.graphics.default_filter_mode,
+            format,
         )
     }
 
@@ -146,18 +271,13 @@ impl Texture {
         height: i32,
         data: &[u8],
         filter_mode: FilterMode,
+        format: TextureFormat,
     ) -> Result<Texture> {
-        let handle = device.new_texture(width, height)?;
+        let texture = Texture::with_device_empty(device, width, height, filter_mode, format)?;
 
-        device.set_texture_data(&handle, &data, 0, 0, width, height)?;
-        device.set_texture_filter_mode(&handle, filter_mode);
+        device.set_texture_data(&texture.data.handle, data, 0, 0, width, height)?;
 
-        Ok(Texture {
-            data: Rc::new(TextureSharedData {
-                handle,
-                filter_mode: Cell::new(FilterMode::Linear),
-            }),
-        })
+        Ok(texture)
     }
 
     pub(crate) fn with_device_empty(
@@ -165,18 +285,155 @@ impl Texture {
         width: i32,
         height: i32,
         filter_mode: FilterMode,
+        format: TextureFormat,
     ) -> Result<Texture> {
-        let handle = device.new_texture(width, height)?;
+        let handle = device.new_texture(width, height, format)?;
         device.set_texture_filter_mode(&handle, filter_mode);
 
         Ok(Texture {
             data: Rc::new(TextureSharedData {
                 handle,
                 filter_mode: Cell::new(filter_mode),
+                wrap_mode: Cell::new(WrapMode::Clamp),
+                anisotropy: Cell::new(1.0),
+                format,
+                alpha_mask: RefCell::new(None),
             }),
         })
     }
 
+    /// Wraps an existing raw texture, which must already have the given filter mode set.
+    pub(crate) fn from_raw(
+        handle: RawTexture,
+        filter_mode: FilterMode,
+        format: TextureFormat,
+    ) -> Texture {
+        Texture {
+            data: Rc::new(TextureSharedData {
+                handle,
+                filter_mode: Cell::new(filter_mode),
+                wrap_mode: Cell::new(WrapMode::Clamp),
+                anisotropy: Cell::new(1.0),
+                format,
+                alpha_mask: RefCell::new(None),
+            }),
+        }
+    }
+
+    /// Creates a new texture with a copy of this texture's contents.
+    ///
+    /// Unlike [`clone`](Clone::clone), which creates a new reference to the same texture, this
+    /// allocates an entirely separate texture on the GPU. This means that changes made to the
+    /// new texture (e.g. setting its filter mode, or writing data to it) will not affect the
+    /// original, and vice versa.
+    ///
+    /// The new texture will have the same filter mode, wrap mode, anisotropy and format as
+    /// the original.
+    ///
+    /// # Performance
+    ///
+    /// This reads the texture's data back from the GPU via [`get_data`](Self::get_data), so it
+    /// is slow - avoid calling it every frame.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`] will be returned if the underlying graphics API encounters an error.
+    pub fn deep_clone(&self, ctx: &mut Context) -> Result<Texture> {
+        let (width, height) = self.size();
+        let data = self.get_data(ctx);
+
+        let texture = Texture::with_device_empty(
+            &mut ctx.device,
+            width,
+            height,
+            self.filter_mode(),
+            self.format(),
+        )?;
+
+        ctx.device
+            .set_texture_data(&texture.data.handle, &data, 0, 0, width, height)?;
+
+        self.copy_sampler_settings(ctx, &texture);
+
+        Ok(texture)
+    }
+
+    /// Creates a new texture containing a copy of the specified region of this texture.
+    ///
+    /// This can be used to extract a single sprite from a sprite sheet, for use with code that
+    /// expects a whole texture. As with [`deep_clone`](Self::deep_clone), the new texture is
+    /// entirely separate from the original, and will have the same filter mode, wrap mode,
+    /// anisotropy and format.
+    ///
+    /// If you just want to draw part of a texture, use [`draw_region`](Self::draw_region)
+    /// instead, as it is much cheaper.
+    ///
+    /// # Performance
+    ///
+    /// This reads the texture's data back from the GPU via [`get_data`](Self::get_data), so it
+    /// is slow - avoid calling it every frame.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`] will be returned if the underlying graphics API encounters an error.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any part of the region is outside the bounds of the texture.
+    pub fn clone_region(&self, ctx: &mut Context, region: Rectangle<i32>) -> Result<Texture> {
+        let (width, height) = self.size();
+
+        assert!(
+            region.x >= 0
+                && region.y >= 0
+                && region.width >= 0
+                && region.height >= 0
+                && region.right() <= width
+                && region.bottom() <= height,
+            "tried to clone a region outside the bounds of the texture"
+        );
+
+        let data = copy_region(&self.get_data(ctx), width, region);
+
+        let texture = Texture::with_device_empty(
+            &mut ctx.device,
+            region.width,
+            region.height,
+            self.filter_mode(),
+            self.format(),
+        )?;
+
+        ctx.device.set_texture_data(
+            &texture.data.handle,
+            &data,
+            0,
+            0,
+            region.width,
+            region.height,
+        )?;
+
+        self.copy_sampler_settings(ctx, &texture);
+
+        Ok(texture)
+    }
+
+    /// Applies this texture's wrap mode and anisotropy to a newly created copy of it.
+    fn copy_sampler_settings(&self, ctx: &mut Context, texture: &Texture) {
+        if self.wrap_mode() != WrapMode::Clamp {
+            ctx.device
+                .set_texture_wrap_mode(&texture.data.handle, self.wrap_mode());
+
+            texture.data.wrap_mode.set(self.wrap_mode());
+        }
+
+        if self.anisotropy() > 1.0 {
+            ctx.device
+                .set_texture_anisotropy(&texture.data.handle, self.anisotropy());
+
+            texture.data.anisotropy.set(self.anisotropy());
+        }
+    }
+
     /// Draws the texture to the screen (or to a canvas, if one is enabled).
     pub fn draw<P>(&self, ctx: &mut Context, params: P)
     where
@@ -224,8 +481,42 @@ impl Texture {
         );
     }
 
+    /// Draws the texture to the screen (or to a canvas, if one is enabled), stretched to fill
+    /// the given rectangle.
+    ///
+    /// The rectangle's position is added to the `position` of the `params`, and the
+    /// `origin` is relative to the top left of the rectangle (with `origin_normalized` being
+    /// resolved against its size). This means that rotating the texture will rotate it
+    /// around the destination rectangle, rather than the texture's original size. Any
+    /// `scale` set in the `params` is applied on top of the stretching.
+    pub fn draw_to<P>(&self, ctx: &mut Context, dest: Rectangle, params: P)
+    where
+        P: Into<DrawParams>,
+    {
+        let mut params = params.into();
+        params.position += Vec2::new(dest.x, dest.y);
+
+        graphics::set_texture(ctx, self);
+        graphics::push_quad(
+            ctx,
+            0.0,
+            0.0,
+            dest.width,
+            dest.height,
+            0.0,
+            0.0,
+            1.0,
+            1.0,
+            &params,
+        );
+    }
+
     /// Draws a region of the texture by splitting it into nine slices, allowing it to be stretched or
     /// squashed without distorting the borders.
+    ///
+    /// If the target size is smaller than the combined size of the borders (e.g. `width` is less
+    /// than `config.left + config.right`), the borders on that axis will be scaled down
+    /// proportionally so that they do not overlap.
     pub fn draw_nine_slice<P>(
         &self,
         ctx: &mut Context,
@@ -236,17 +527,22 @@ impl Texture {
     ) where
         P: Into<DrawParams>,
     {
-        let params = params.into();
+        let params = params
+            .into()
+            .with_resolved_origin(Rectangle::new(0.0, 0.0, width, height));
 
         let texture_width = self.width() as f32;
         let texture_height = self.height() as f32;
 
+        let (left, right) = fit_borders(config.left, config.right, width);
+        let (top, bottom) = fit_borders(config.top, config.bottom, height);
+
         let x1 = 0.0;
         let y1 = 0.0;
-        let x2 = config.left;
-        let y2 = config.top;
-        let x3 = width - config.right;
-        let y3 = height - config.bottom;
+        let x2 = left;
+        let y2 = top;
+        let x3 = width - right;
+        let y3 = height - bottom;
         let x4 = width;
         let y4 = height;
 
@@ -317,8 +613,67 @@ impl Texture {
         self.data.filter_mode.set(filter_mode);
     }
 
+    /// Returns the wrap mode being used by the texture.
+    pub fn wrap_mode(&self) -> WrapMode {
+        self.data.wrap_mode.get()
+    }
+
+    /// Sets how the texture should be sampled outside of the `0.0` to `1.0` range of
+    /// texture co-ordinates.
+    ///
+    /// This only has a visible effect when the texture is drawn via a [`Mesh`](crate::graphics::mesh::Mesh)
+    /// or a custom [`Shader`](crate::graphics::Shader) with texture co-ordinates outside of that
+    /// range, as Tetra's other drawing methods never sample outside of the texture.
+    ///
+    /// Defaults to [`WrapMode::Clamp`].
+    pub fn set_wrap_mode(&mut self, ctx: &mut Context, wrap_mode: WrapMode) {
+        ctx.device
+            .set_texture_wrap_mode(&self.data.handle, wrap_mode);
+
+        self.data.wrap_mode.set(wrap_mode);
+    }
+
+    /// Returns the level of anisotropic filtering being used by the texture.
+    ///
+    /// A value of `1.0` means that anisotropic filtering is disabled.
+    pub fn anisotropy(&self) -> f32 {
+        self.data.anisotropy.get()
+    }
+
+    /// Sets the level of anisotropic filtering that should be used by the texture.
+    ///
+    /// Anisotropic filtering improves the quality of textures that are drawn at
+    /// oblique angles or with non-uniform scaling (e.g. ground planes in a 2.5D game),
+    /// which would otherwise look blurry when using [`FilterMode::Linear`].
+    ///
+    /// The level will be clamped between `1.0` (disabled) and the maximum supported
+    /// by the graphics driver, which can be found via
+    /// [`GraphicsDeviceInfo::max_anisotropy`](crate::graphics::GraphicsDeviceInfo::max_anisotropy).
+    ///
+    /// Anisotropic filtering is provided by an OpenGL extension, which is not supported by
+    /// all drivers. If it is unavailable, calling this method will have no effect.
+    pub fn set_anisotropy(&mut self, ctx: &mut Context, anisotropy: f32) {
+        if let Some(max_anisotropy) = ctx.device.get_max_anisotropy() {
+            let anisotropy = anisotropy.clamp(1.0, max_anisotropy.max(1.0));
+
+            ctx.device
+                .set_texture_anisotropy(&self.data.handle, anisotropy);
+
+            self.data.anisotropy.set(anisotropy);
+        }
+    }
+
+    /// Returns the format that the texture's data is stored in.
+    pub fn format(&self) -> TextureFormat {
+        self.data.format
+    }
+
     /// Writes RGBA pixel data to a specified region of the texture.
     ///
+    /// The data should be tightly packed, with each row of the region immediately
+    /// following the previous one (i.e. `width * 4` bytes per row, with no padding).
+    /// This is the case regardless of the width of the region.
+    ///
     /// This method requires you to provide enough data to fill the target rectangle.
     /// If you provide too little data, an error will be returned.
     /// If you provide too much data, it will be truncated.
@@ -344,6 +699,8 @@ impl Texture {
         height: i32,
         data: &[u8],
     ) -> Result {
+        self.data.invalidate_alpha_mask();
+
         ctx.device
             .set_texture_data(&self.data.handle, &data, x, y, width, height)
     }
@@ -365,12 +722,341 @@ impl Texture {
         let (width, height) = self.size();
         self.set_data(ctx, 0, 0, width, height, data)
     }
+
+    /// Reads the texture's pixel data back from the GPU, as RGBA bytes.
+    ///
+    /// Any queued draw calls will be [flushed](graphics::flush) before the data is read,
+    /// so that the result includes anything that has been drawn to the texture via a
+    /// [`Canvas`](graphics::Canvas).
+    ///
+    /// # Performance
+    ///
+    /// This is a slow operation, as it requires the CPU to wait for the GPU to finish
+    /// any outstanding work. Avoid calling it every frame if you can.
+    pub fn get_data(&self, ctx: &mut Context) -> Vec<u8> {
+        graphics::flush(ctx);
+        ctx.device.get_texture_data(&self.data.handle)
+    }
+
+    /// Returns whether two textures contain the same pixel data.
+    ///
+    /// Unlike the `==` operator (which checks whether two `Texture` objects refer to the same
+    /// texture on the GPU), this reads back the contents of both textures and compares them
+    /// pixel by pixel. Textures of different sizes are never equal. This is mainly useful for
+    /// testing rendering code against a known-good image.
+    ///
+    /// # Performance
+    ///
+    /// This uses [`get_data`](Self::get_data), so it is slow - avoid calling it every frame.
+    pub fn pixels_eq(&self, ctx: &mut Context, other: &Texture) -> bool {
+        self.pixels_eq_with_tolerance(ctx, other, 0)
+    }
+
+    /// Returns whether two textures contain the same pixel data, allowing each color channel
+    /// to differ by up to `tolerance`.
+    ///
+    /// This can be used to ignore small differences caused by filtering or by rounding on
+    /// different graphics hardware.
+    ///
+    /// # Performance
+    ///
+    /// This uses [`get_data`](Self::get_data), so it is slow - avoid calling it every frame.
+    pub fn pixels_eq_with_tolerance(
+        &self,
+        ctx: &mut Context,
+        other: &Texture,
+        tolerance: u8,
+    ) -> bool {
+        if self.size() != other.size() {
+            return false;
+        }
+
+        if self == other {
+            return true;
+        }
+
+        diff_pixels(&self.get_data(ctx), &other.get_data(ctx), tolerance).is_none()
+    }
+
+    /// Compares the pixel data of two textures, returning an image of the differences if they
+    /// do not match.
+    ///
+    /// If every color channel of every pixel is within `tolerance` of the other texture, `None`
+    /// will be returned. Otherwise, the returned RGBA data (which is the same size as the
+    /// textures) will contain opaque red pixels wherever the textures differ, and a faded copy
+    /// of this texture everywhere else. This can be saved to disk to help debug failing
+    /// rendering tests.
+    ///
+    /// # Performance
+    ///
+    /// This uses [`get_data`](Self::get_data), so it is slow - avoid calling it every frame.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the textures are not the same size.
+    pub fn diff(&self, ctx: &mut Context, other: &Texture, tolerance: u8) -> Option<Vec<u8>> {
+        assert_eq!(
+            self.size(),
+            other.size(),
+            "tried to diff textures of different sizes"
+        );
+
+        diff_pixels(&self.get_data(ctx), &other.get_data(ctx), tolerance)
+    }
+
+    /// Returns whether the texel at the given position has a non-zero alpha value.
+    ///
+    /// This can be used for pixel-perfect hit testing, in combination with
+    /// [`texel_at`](Self::texel_at). Positions outside of the texture are never opaque.
+    ///
+    /// # Performance
+    ///
+    /// The first call to this method reads the texture's alpha channel back from the GPU
+    /// (via [`get_data`](Self::get_data)) and caches it on the CPU, so subsequent calls are
+    /// cheap. The cache is shared between clones of the texture, and is discarded whenever
+    /// the texture's data is changed or it is drawn to via a canvas (including while that
+    /// canvas is still active).
+    ///
+    /// As with [`get_data`](Self::get_data), drawing to a multisampled canvas will only be
+    /// reflected in the result once the canvas is no longer active.
+    pub fn is_opaque_at(&self, ctx: &mut Context, x: i32, y: i32) -> bool {
+        let (width, height) = self.size();
+
+        if x < 0 || y < 0 || x >= width || y >= height {
+            return false;
+        }
+
+        // Flushing may draw to this texture (and therefore discard the cache), so it needs
+        // to happen before the cache is checked.
+        graphics::flush(ctx);
+
+        let alpha_mask = match self.data.alpha_mask.take() {
+            Some(alpha_mask) => alpha_mask,
+            None => self
+                .get_data(ctx)
+                .chunks_exact(4)
+                .map(|pixel| pixel[3])
+                .collect(),
+        };
+
+        let opaque = alpha_mask[(y * width + x) as usize] > 0;
+        self.data.alpha_mask.replace(Some(alpha_mask));
+
+        opaque
+    }
+
+    /// Maps a position back to a texel of the texture, given the params that the texture
+    /// was drawn with.
+    ///
+    /// This is the inverse of the transformation applied by [`draw`](Self::draw), so the
+    /// position should be in the co-ordinate space that the texture was drawn into (e.g.
+    /// [`input::get_mouse_position`](crate::input::get_mouse_position) if it was drawn directly
+    /// to the window).
+    ///
+    /// Returns `None` if the position is outside of the texture, or if the params cannot be inverted.
+    pub fn texel_at<P>(&self, position: Vec2<f32>, params: P) -> Option<Vec2<i32>>
+    where
+        P: Into<DrawParams>,
+    {
+        let (width, height) = self.size();
+        let bounds = Rectangle::new(0.0, 0.0, width as f32, height as f32);
+
+        params
+            .into()
+            .to_local(position, bounds)
+            .filter(|p| p.x >= 0.0 && p.y >= 0.0 && p.x < bounds.width && p.y < bounds.height)
+            .map(|p| Vec2::new(p.x as i32, p.y as i32))
+    }
+}
+
+/// Loads each frame of an animated GIF as a separate texture, along with how long
+/// that frame should be displayed for.
+///
+/// The frames are fully composited - GIFs that only store the pixels that changed
+/// between frames, or that use disposal methods to clear parts of the image, will
+/// still produce complete images for every frame. Each texture will be the size of
+/// the GIF's logical screen.
+///
+/// As the frames are stored in separate textures, they cannot be passed directly to
+/// [`Animation`](graphics::animation::Animation), which expects all of its frames to
+/// be on a single texture. If you need that, you can draw the frames onto a
+/// [`Canvas`](graphics::Canvas) to build a spritesheet.
+///
+/// # Errors
+///
+/// * [`TetraError::PlatformError`] will be returned if the underlying graphics API encounters an error.
+/// * [`TetraError::FailedToLoadAsset`] will be returned if the file could not be loaded.
+/// * [`TetraError::InvalidTexture`] will be returned if the GIF data was invalid.
+#[cfg(feature = "texture_gif")]
+pub fn load_gif_frames<P>(ctx: &mut Context, path: P) -> Result<Vec<(Texture, Duration)>>
+where
+    P: AsRef<Path>,
+{
+    let data = fs::read(path)?;
+
+    let frames = GifDecoder::new(Cursor::new(data))
+        .and_then(|decoder| decoder.into_frames().collect_frames())
+        .map_err(TetraError::InvalidTexture)?;
+
+    frames
+        .into_iter()
+        .map(|frame| {
+            let (numer, denom) = frame.delay().numer_denom_ms();
+            let delay = Duration::from_secs_f64(f64::from(numer) / f64::from(denom) / 1000.0);
+
+            let buffer = frame.into_buffer();
+            let (width, height) = buffer.dimensions();
+
+            let texture = Texture::from_rgba(ctx, width as i32, height as i32, buffer.as_raw())?;
+
+            Ok((texture, delay))
+        })
+        .collect()
+}
+
+fn diff_pixels(a: &[u8], b: &[u8], tolerance: u8) -> Option<Vec<u8>> {
+    let differs = |pa: &[u8], pb: &[u8]| {
+        pa.iter()
+            .zip(pb)
+            .any(|(ca, cb)| (*ca as i16 - *cb as i16).abs() > tolerance as i16)
+    };
+
+    let mut any_differ = false;
+    let mut output = Vec::with_capacity(a.len());
+
+    for (pa, pb) in a.chunks_exact(4).zip(b.chunks_exact(4)) {
+        if differs(pa, pb) {
+            any_differ = true;
+            output.extend_from_slice(&[255, 0, 0, 255]);
+        } else {
+            output.extend_from_slice(&[pa[0], pa[1], pa[2], pa[3] / 4]);
+        }
+    }
+
+    if any_differ {
+        Some(output)
+    } else {
+        None
+    }
+}
+
+/// A builder for creating a [`Texture`] with specific settings.
+///
+/// Creating a texture via this builder (rather than changing the settings after the texture
+/// has been created) ensures that the texture never exists with the wrong settings, and
+/// that there is no risk of accidentally modifying a clone of the texture that is in use
+/// elsewhere.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tetra::graphics::{FilterMode, TextureBuilder, WrapMode};
+/// # use tetra::Context;
+/// # fn example(ctx: &mut Context, pixels: &[u8]) -> tetra::Result {
+/// let texture = TextureBuilder::new(64, 64)
+///     .filter_mode(FilterMode::Linear)
+///     .wrap_mode(WrapMode::Repeat)
+///     .build_with_data(ctx, pixels)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextureBuilder {
+    width: i32,
+    height: i32,
+    filter_mode: Option<FilterMode>,
+    wrap_mode: WrapMode,
+    format: Option<TextureFormat>,
+}
+
+impl TextureBuilder {
+    /// Creates a new builder for a texture of the given size.
+    pub fn new(width: i32, height: i32) -> TextureBuilder {
+        TextureBuilder {
+            width,
+            height,
+            filter_mode: None,
+            wrap_mode: WrapMode::Clamp,
+            format: None,
+        }
+    }
+
+    /// Sets the filter mode that the texture will use.
+    ///
+    /// Defaults to the [default filter mode](crate::graphics::get_default_filter_mode).
+    pub fn filter_mode(mut self, filter_mode: FilterMode) -> TextureBuilder {
+        self.filter_mode = Some(filter_mode);
+        self
+    }
+
+    /// Sets the wrap mode that the texture will use.
+    ///
+    /// Defaults to [`WrapMode::Clamp`].
+    pub fn wrap_mode(mut self, wrap_mode: WrapMode) -> TextureBuilder {
+        self.wrap_mode = wrap_mode;
+        self
+    }
+
+    /// Sets the format that the texture's data will be stored in.
+    ///
+    /// Defaults to the [default texture format](crate::graphics::get_default_texture_format).
+    pub fn format(mut self, format: TextureFormat) -> TextureBuilder {
+        self.format = Some(format);
+        self
+    }
+
+    /// Builds an empty texture.
+    ///
+    /// The contents of the texture will be undefined until data is written via
+    /// [`Texture::set_data`] or [`Texture::replace_data`].
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`] will be returned if the underlying graphics API encounters an error.
+    pub fn build(&self, ctx: &mut Context) -> Result<Texture> {
+        let texture = Texture::with_device_empty(
+            &mut ctx.device,
+            self.width,
+            self.height,
+            self.filter_mode.unwrap_or(ctx.graphics.default_filter_mode),
+            self.format.unwrap_or(ctx.graphics.default_texture_format),
+        )?;
+
+        if self.wrap_mode != WrapMode::Clamp {
+            ctx.device
+                .set_texture_wrap_mode(&texture.data.handle, self.wrap_mode);
+
+            texture.data.wrap_mode.set(self.wrap_mode);
+        }
+
+        Ok(texture)
+    }
+
+    /// Builds a texture, filled with the given RGBA pixel data.
+    ///
+    /// This method requires you to provide enough data to fill the texture.
+    /// If you provide too little data, an error will be returned.
+    /// If you provide too much data, it will be truncated.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`] will be returned if the underlying graphics API encounters an error.
+    /// * [`TetraError::NotEnoughData`] will be returned if not enough data is provided to fill
+    /// the texture. This is to prevent the graphics API from trying to read uninitialized memory.
+    pub fn build_with_data(&self, ctx: &mut Context, data: &[u8]) -> Result<Texture> {
+        let texture = self.build(ctx)?;
+
+        ctx.device
+            .set_texture_data(&texture.data.handle, data, 0, 0, self.width, self.height)?;
+
+        Ok(texture)
+    }
 }
 
 /// Filtering algorithms that can be used when scaling an image.
 ///
 /// Tetra currently defaults to using `Nearest` for all newly created textures.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum FilterMode {
     /// Nearest-neighbor interpolation. This preserves hard edges and details, but may look pixelated.
     ///
@@ -381,6 +1067,26 @@ pub enum FilterMode {
     Linear,
 }
 
+/// The formats that a texture's data can be stored in on the GPU.
+///
+/// Tetra currently defaults to using `Rgba8` for all newly created textures, unless
+/// sRGB rendering has been enabled via [`ContextBuilder::srgb`](crate::ContextBuilder::srgb).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextureFormat {
+    /// 8-bit RGBA, stored as-is. Colors sampled from the texture will be returned exactly as
+    /// they were uploaded.
+    Rgba8,
+
+    /// 8-bit RGBA, where the RGB channels are stored in the sRGB color space. Colors will be
+    /// converted to linear space when they are sampled from the texture, and (if sRGB rendering
+    /// is enabled) will be converted back into sRGB space when they are rendered to a canvas
+    /// with this format.
+    ///
+    /// Most image files are stored in sRGB, so this is usually the right format for art assets
+    /// when you want blending and filtering to be gamma-correct.
+    Srgba8,
+}
+
 /// Information on how to slice a texture so that it can be stretched or squashed without
 /// distorting the borders.
 ///
@@ -390,7 +1096,16 @@ pub enum FilterMode {
 ///
 /// The [`nineslice`](https://github.com/17cupsofcoffee/tetra/blob/main/examples/nineslice.rs)
 /// example demonstrates how to draw a `NineSlice` panel.
+///
+/// # Serde
+///
+/// Serialization and deserialization of this type (via [Serde](https://serde.rs/))
+/// can be enabled via the `serde_support` feature.
 #[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde_support",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub struct NineSlice {
     /// The region of the texture that should be used.
     pub region: Rectangle,
@@ -431,3 +1146,79 @@ impl NineSlice {
         }
     }
 }
+
+/// Scales down a pair of border sizes so that they fit within the given size.
+fn fit_borders(start: f32, end: f32, size: f32) -> (f32, f32) {
+    let total = start + end;
+
+    if total > size && total > 0.0 {
+        let scale = size.max(0.0) / total;
+        (start * scale, end * scale)
+    } else {
+        (start, end)
+    }
+}
+
+/// Copies the pixels within a region out of a buffer of RGBA data.
+fn copy_region(data: &[u8], width: i32, region: Rectangle<i32>) -> Vec<u8> {
+    let stride = width as usize * 4;
+    let row_len = region.width as usize * 4;
+
+    let mut output = Vec::with_capacity(row_len * region.height as usize);
+
+    for y in region.y..region.bottom() {
+        let start = y as usize * stride + region.x as usize * 4;
+        output.extend_from_slice(&data[start..start + row_len]);
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{copy_region, diff_pixels, fit_borders};
+    use crate::graphics::Rectangle;
+
+    #[test]
+    fn diff_within_tolerance() {
+        let a = [10, 20, 30, 255, 0, 0, 0, 0];
+        let b = [12, 20, 28, 255, 0, 0, 0, 0];
+
+        assert!(diff_pixels(&a, &a, 0).is_none());
+        assert!(diff_pixels(&a, &b, 2).is_none());
+        assert!(diff_pixels(&a, &b, 1).is_some());
+    }
+
+    #[test]
+    fn diff_marks_mismatched_pixels() {
+        let a = [10, 20, 30, 255, 0, 0, 0, 0];
+        let b = [10, 20, 30, 255, 0, 0, 0, 255];
+
+        assert_eq!(
+            Some(vec![10, 20, 30, 63, 255, 0, 0, 255]),
+            diff_pixels(&a, &b, 0)
+        );
+    }
+
+    #[test]
+    fn borders_are_scaled_to_fit() {
+        assert_eq!(fit_borders(4.0, 4.0, 16.0), (4.0, 4.0));
+        assert_eq!(fit_borders(4.0, 4.0, 8.0), (4.0, 4.0));
+        assert_eq!(fit_borders(4.0, 4.0, 4.0), (2.0, 2.0));
+        assert_eq!(fit_borders(6.0, 2.0, 4.0), (3.0, 1.0));
+        assert_eq!(fit_borders(4.0, 4.0, -1.0), (0.0, 0.0));
+    }
+
+    #[test]
+    fn region_is_copied() {
+        // A 3x2 texture, where each pixel's red channel is its index.
+        let data: Vec<u8> = (0..6).flat_map(|i| vec![i, 0, 0, 255]).collect();
+
+        let region = copy_region(&data, 3, Rectangle::new(1, 0, 2, 2));
+
+        assert_eq!(
+            vec![1, 0, 0, 255, 2, 0, 0, 255, 4, 0, 0, 255, 5, 0, 0, 255],
+            region
+        );
+    }
+}
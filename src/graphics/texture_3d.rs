@@ -0,0 +1,121 @@
+use crate::error::Result;
+use crate::graphics::{FilterMode, Shader, Texture, TextureFormat, UniformValue};
+use crate::Context;
+
+/// A three-dimensional texture, held in GPU memory as a `sampler3D`.
+///
+/// 3D textures can't be drawn directly - instead, they should be passed to a custom
+/// [`Shader`] via [`set_uniform`](Shader::set_uniform), and then sampled with a set of
+/// 3D texture co-ordinates (e.g. `texture(u_lut, color.rgb)`).
+///
+/// The most common use for this is a color lookup table (LUT) for color grading, where
+/// each axis of the texture corresponds to a color channel. Unlike a [`TextureArray`](crate::graphics::TextureArray),
+/// linear filtering is applied between the slices of a 3D texture as well as within them,
+/// so LUTs should usually use [`FilterMode::Linear`].
+///
+/// # Performance
+///
+/// Creating a `Texture3D` is a relatively expensive operation. If you can, store them in your
+/// [`State`](crate::State) struct rather than recreating them each frame.
+///
+/// Cloning a `Texture3D` is a very cheap operation, as the underlying data is shared between the
+/// original instance and the clone via [reference-counting](https://doc.rust-lang.org/std/rc/struct.Rc.html).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Texture3D {
+    pub(crate) texture: Texture,
+}
+
+impl Texture3D {
+    /// Creates a new, empty 3D texture.
+    ///
+    /// The contents of the texture will be undefined until data is written via
+    /// [`set_data`](Self::set_data).
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the underlying
+    /// graphics API encounters an error.
+    pub fn new(ctx: &mut Context, width: i32, height: i32, depth: i32) -> Result<Texture3D> {
+        let filter_mode = ctx.graphics.default_filter_mode;
+        let format = ctx.graphics.default_texture_format;
+
+        let handle = ctx.device.new_texture_3d(width, height, depth, format)?;
+
+        ctx.device.set_texture_filter_mode(&handle, filter_mode);
+
+        Ok(Texture3D {
+            texture: Texture::from_raw(handle, filter_mode, format),
+        })
+    }
+
+    /// Overwrites the entire texture with new RGBA pixel data.
+    ///
+    /// The data should be laid out as a series of `depth` slices, each of which is made
+    /// up of `height` rows of `width` pixels.
+    ///
+    /// This method requires you to provide enough data to fill the texture.
+    /// If you provide too little data, an error will be returned.
+    /// If you provide too much data, it will be truncated.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::NotEnoughData`](crate::TetraError::NotEnoughData) will be returned
+    /// if not enough data is provided to fill the texture. This is to prevent the graphics API
+    /// from trying to read uninitialized memory.
+    pub fn set_data(&self, ctx: &mut Context, data: &[u8]) -> Result {
+        let (width, height, depth) = self.size();
+
+        ctx.device.set_texture_3d_data(
+            &self.texture.data.handle,
+            data,
+            0,
+            0,
+            0,
+            width,
+            height,
+            depth,
+        )
+    }
+
+    /// Returns the width of the texture.
+    pub fn width(&self) -> i32 {
+        self.texture.width()
+    }
+
+    /// Returns the height of the texture.
+    pub fn height(&self) -> i32 {
+        self.texture.height()
+    }
+
+    /// Returns the depth of the texture.
+    pub fn depth(&self) -> i32 {
+        self.texture.data.handle.depth()
+    }
+
+    /// Returns the width, height and depth of the texture.
+    pub fn size(&self) -> (i32, i32, i32) {
+        (self.width(), self.height(), self.depth())
+    }
+
+    /// Returns the filter mode being used by the texture.
+    pub fn filter_mode(&self) -> FilterMode {
+        self.texture.filter_mode()
+    }
+
+    /// Sets the filter mode that should be used by the texture.
+    pub fn set_filter_mode(&mut self, ctx: &mut Context, filter_mode: FilterMode) {
+        self.texture.set_filter_mode(ctx, filter_mode);
+    }
+
+    /// Returns the format that the texture's data is stored in.
+    pub fn format(&self) -> TextureFormat {
+        self.texture.format()
+    }
+}
+
+impl UniformValue for Texture3D {
+    #[doc(hidden)]
+    fn set_uniform(&self, ctx: &mut Context, shader: &Shader, name: &str) {
+        self.texture.set_uniform(ctx, shader, name);
+    }
+}
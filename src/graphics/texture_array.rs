@@ -0,0 +1,125 @@
+use crate::error::Result;
+use crate::graphics::{FilterMode, Shader, Texture, TextureFormat, UniformValue};
+use crate::Context;
+
+/// An array of textures with the same size and format, held in GPU memory as a single
+/// `sampler2DArray`.
+///
+/// Texture arrays can't be drawn directly - instead, they should be passed to a custom
+/// [`Shader`] via [`set_uniform`](Shader::set_uniform), and then sampled with a layer
+/// index (e.g. `texture(u_frames, vec3(v_uv, layer))`).
+///
+/// Unlike a texture atlas, each layer is sampled independently, so linear filtering will
+/// never bleed between neighbouring frames or tiles.
+///
+/// The layers of a texture array can also be rendered into, via
+/// [`Canvas::from_texture_array`](crate::graphics::Canvas::from_texture_array).
+///
+/// # Performance
+///
+/// Creating a `TextureArray` is a relatively expensive operation. If you can, store them in your
+/// [`State`](crate::State) struct rather than recreating them each frame.
+///
+/// Cloning a `TextureArray` is a very cheap operation, as the underlying data is shared between the
+/// original instance and the clone via [reference-counting](https://doc.rust-lang.org/std/rc/struct.Rc.html).
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextureArray {
+    pub(crate) texture: Texture,
+}
+
+impl TextureArray {
+    /// Creates a new, empty texture array.
+    ///
+    /// The contents of each layer will be undefined until data is written via
+    /// [`set_layer_data`](Self::set_layer_data).
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the underlying
+    /// graphics API encounters an error.
+    pub fn new(ctx: &mut Context, width: i32, height: i32, layers: i32) -> Result<TextureArray> {
+        let filter_mode = ctx.graphics.default_filter_mode;
+        let format = ctx.graphics.default_texture_format;
+
+        let handle = ctx
+            .device
+            .new_texture_array(width, height, layers, format)?;
+
+        ctx.device.set_texture_filter_mode(&handle, filter_mode);
+
+        Ok(TextureArray {
+            texture: Texture::from_raw(handle, filter_mode, format),
+        })
+    }
+
+    /// Overwrites an entire layer of the texture array with new RGBA pixel data.
+    ///
+    /// This method requires you to provide enough data to fill the layer.
+    /// If you provide too little data, an error will be returned.
+    /// If you provide too much data, it will be truncated.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::NotEnoughData`](crate::TetraError::NotEnoughData) will be returned
+    /// if not enough data is provided to fill the layer. This is to prevent the graphics API
+    /// from trying to read uninitialized memory.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the layer does not exist.
+    pub fn set_layer_data(&self, ctx: &mut Context, layer: i32, data: &[u8]) -> Result {
+        let (width, height) = self.size();
+
+        ctx.device.set_texture_array_data(
+            &self.texture.data.handle,
+            data,
+            layer,
+            0,
+            0,
+            width,
+            height,
+        )
+    }
+
+    /// Returns the width of each layer.
+    pub fn width(&self) -> i32 {
+        self.texture.width()
+    }
+
+    /// Returns the height of each layer.
+    pub fn height(&self) -> i32 {
+        self.texture.height()
+    }
+
+    /// Returns the size of each layer.
+    pub fn size(&self) -> (i32, i32) {
+        self.texture.size()
+    }
+
+    /// Returns the number of layers in the texture array.
+    pub fn layers(&self) -> i32 {
+        self.texture.data.handle.depth()
+    }
+
+    /// Returns the filter mode being used by the texture array.
+    pub fn filter_mode(&self) -> FilterMode {
+        self.texture.filter_mode()
+    }
+
+    /// Sets the filter mode that should be used by the texture array.
+    pub fn set_filter_mode(&mut self, ctx: &mut Context, filter_mode: FilterMode) {
+        self.texture.set_filter_mode(ctx, filter_mode);
+    }
+
+    /// Returns the format that the texture array's data is stored in.
+    pub fn format(&self) -> TextureFormat {
+        self.texture.format()
+    }
+}
+
+impl UniformValue for TextureArray {
+    #[doc(hidden)]
+    fn set_uniform(&self, ctx: &mut Context, shader: &Shader, name: &str) {
+        self.texture.set_uniform(ctx, shader, name);
+    }
+}
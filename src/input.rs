@@ -9,6 +9,26 @@
 //! a new one will be allocated. This means that if you unplug a controller and then plug it back in,
 //! it should retain its existing ID. This behaviour might be made smarter in future versions.
 //!
+//! Motion sensors (gyroscopes/accelerometers) and touchpads, such as those found on
+//! DualShock 4 and DualSense controllers, are not currently supported, as the version
+//! of SDL2 that Tetra binds to does not expose them.
+//!
+//! # Consistency
+//!
+//! Tetra processes all of the events that have been received from the platform before each
+//! frame begins, and does not process any more until the next frame. This means that the input
+//! state is stable for the whole of an [`update`](crate::State::update) - every part of your game
+//! logic will see the same answer from functions like [`is_key_pressed`], no matter how many
+//! times they are called or in what order. Querying the input state never consumes it.
+//!
+//! The 'pressed' and 'released' states are cleared after each update. When using a fixed
+//! timestep, if multiple updates run in a single frame, only the first of them will see
+//! these states.
+//!
+//! If you need to keep hold of the input state for longer (e.g. to compare it against the
+//! previous frame, or to pass it to code that doesn't have access to the [`Context`]),
+//! [`snapshot`] can be used to take a copy of it.
+//!
 //! # Examples
 //!
 //! The [`keyboard`](https://github.com/17cupsofcoffee/tetra/blob/main/examples/keyboard.rs)
@@ -23,33 +43,46 @@
 //! The [`text_input`](https://github.com/17cupsofcoffee/tetra/blob/main/examples/text_input.rs)
 //! example demonstrates how to handle text entry.
 
+mod bindings;
 mod gamepad;
 mod keyboard;
 mod mouse;
+mod snapshot;
 
 use hashbrown::HashSet;
 
 use crate::math::Vec2;
 use crate::{Context, Result};
 
+pub use bindings::*;
 pub use gamepad::*;
 pub use keyboard::*;
 pub use mouse::*;
+pub use snapshot::*;
 
+#[derive(Debug, Clone)]
 pub(crate) struct InputContext {
     keys_down: HashSet<Key>,
     keys_pressed: HashSet<Key>,
     keys_released: HashSet<Key>,
 
+    scancodes_down: HashSet<Scancode>,
+    scancodes_pressed: HashSet<Scancode>,
+    scancodes_released: HashSet<Scancode>,
+
     mouse_buttons_down: HashSet<MouseButton>,
     mouse_buttons_pressed: HashSet<MouseButton>,
     mouse_buttons_released: HashSet<MouseButton>,
     mouse_position: Vec2<f32>,
-    mouse_wheel_movement: Vec2<i32>,
+    mouse_wheel_movement: Vec2<f32>,
+    mouse_wheel_ticks: Vec2<i32>,
+    mouse_warp_target: Option<Vec2<f32>>,
 
     current_text_input: Option<String>,
 
     pads: Vec<Option<GamepadState>>,
+
+    last_input_device: Option<InputDevice>,
 }
 
 impl InputContext {
@@ -59,15 +92,23 @@ impl InputContext {
             keys_pressed: HashSet::new(),
             keys_released: HashSet::new(),
 
+            scancodes_down: HashSet::new(),
+            scancodes_pressed: HashSet::new(),
+            scancodes_released: HashSet::new(),
+
             mouse_buttons_down: HashSet::new(),
             mouse_buttons_pressed: HashSet::new(),
             mouse_buttons_released: HashSet::new(),
             mouse_position: Vec2::zero(),
             mouse_wheel_movement: Vec2::zero(),
+            mouse_wheel_ticks: Vec2::zero(),
+            mouse_warp_target: None,
 
             current_text_input: None,
 
             pads: Vec::new(),
+
+            last_input_device: None,
         }
     }
 }
@@ -75,9 +116,12 @@ impl InputContext {
 pub(crate) fn clear(ctx: &mut Context) {
     ctx.input.keys_pressed.clear();
     ctx.input.keys_released.clear();
+    ctx.input.scancodes_pressed.clear();
+    ctx.input.scancodes_released.clear();
     ctx.input.mouse_buttons_pressed.clear();
     ctx.input.mouse_buttons_released.clear();
     ctx.input.mouse_wheel_movement = Vec2::zero();
+    ctx.input.mouse_wheel_ticks = Vec2::zero();
 
     ctx.input.current_text_input = None;
 
@@ -89,6 +133,44 @@ pub(crate) fn clear(ctx: &mut Context) {
     }
 }
 
+/// A type of device that the player can use to provide input.
+///
+/// # Serde
+///
+/// Serialization and deserialization of this type (via [Serde](https://serde.rs/))
+/// can be enabled via the `serde_support` feature.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde_support",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum InputDevice {
+    /// The keyboard.
+    Keyboard,
+
+    /// The mouse.
+    Mouse,
+
+    /// The gamepad with the given ID.
+    Gamepad(usize),
+}
+
+/// Returns the device that the player most recently used to provide input, or `None`
+/// if no input has been received yet.
+///
+/// This is updated when a key, mouse button or gamepad button is pressed, when the mouse
+/// is moved or scrolled, and when a gamepad stick or trigger is pushed past halfway.
+/// Releases do not count as activity.
+///
+/// This can be useful for deciding whether to show keyboard or gamepad prompts in your UI.
+pub fn get_last_input_device(ctx: &Context) -> Option<InputDevice> {
+    ctx.input.last_input_device
+}
+
+pub(crate) fn set_last_input_device(ctx: &mut Context, device: InputDevice) {
+    ctx.input.last_input_device = Some(device);
+}
+
 /// Returns the text that the user entered since the last update.
 /// This will match the user's keyboard and OS settings.
 pub fn get_text_input(ctx: &Context) -> Option<&str> {
@@ -0,0 +1,253 @@
+use std::hash::Hash;
+
+use hashbrown::HashMap;
+
+use crate::input::{self, GamepadButton, Key, MouseButton, Scancode};
+use crate::Context;
+
+/// A physical control that can be bound to an action in an [`InputMap`].
+///
+/// # Serde
+///
+/// Serialization and deserialization of this type (via [Serde](https://serde.rs/))
+/// can be enabled via the `serde_support` feature.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde_support",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum Binding {
+    /// A key on the keyboard, identified by the character it produces.
+    Key(Key),
+
+    /// A key on the keyboard, identified by its physical position.
+    Scancode(Scancode),
+
+    /// A button on the mouse.
+    MouseButton(MouseButton),
+
+    /// A button on the [`InputMap`]'s gamepad.
+    GamepadButton(GamepadButton),
+}
+
+impl Binding {
+    fn is_down(self, ctx: &Context, gamepad_id: usize) -> bool {
+        match self {
+            Binding::Key(key) => input::is_key_down(ctx, key),
+            Binding::Scancode(scancode) => input::is_scancode_down(ctx, scancode),
+            Binding::MouseButton(button) => input::is_mouse_button_down(ctx, button),
+            Binding::GamepadButton(button) => {
+                input::is_gamepad_button_down(ctx, gamepad_id, button)
+            }
+        }
+    }
+
+    fn is_pressed(self, ctx: &Context, gamepad_id: usize) -> bool {
+        match self {
+            Binding::Key(key) => input::is_key_pressed(ctx, key),
+            Binding::Scancode(scancode) => input::is_scancode_pressed(ctx, scancode),
+            Binding::MouseButton(button) => input::is_mouse_button_pressed(ctx, button),
+            Binding::GamepadButton(button) => {
+                input::is_gamepad_button_pressed(ctx, gamepad_id, button)
+            }
+        }
+    }
+
+    fn is_released(self, ctx: &Context, gamepad_id: usize) -> bool {
+        match self {
+            Binding::Key(key) => input::is_key_released(ctx, key),
+            Binding::Scancode(scancode) => input::is_scancode_released(ctx, scancode),
+            Binding::MouseButton(button) => input::is_mouse_button_released(ctx, button),
+            Binding::GamepadButton(button) => {
+                input::is_gamepad_button_released(ctx, gamepad_id, button)
+            }
+        }
+    }
+}
+
+impl From<Key> for Binding {
+    fn from(key: Key) -> Binding {
+        Binding::Key(key)
+    }
+}
+
+impl From<Scancode> for Binding {
+    fn from(scancode: Scancode) -> Binding {
+        Binding::Scancode(scancode)
+    }
+}
+
+impl From<MouseButton> for Binding {
+    fn from(button: MouseButton) -> Binding {
+        Binding::MouseButton(button)
+    }
+}
+
+impl From<GamepadButton> for Binding {
+    fn from(button: GamepadButton) -> Binding {
+        Binding::GamepadButton(button)
+    }
+}
+
+/// A mapping from your game's actions to the controls that trigger them.
+///
+/// The action type can be anything that implements [`Eq`] and [`Hash`] - usually this
+/// will be an enum defined by your game. Each action can have any number of bindings,
+/// from any combination of keyboard, mouse and gamepad controls, and the action will be
+/// considered active if any of them are.
+///
+/// Gamepad bindings are checked against a single gamepad, which defaults to ID 0 and can be
+/// changed via [`set_gamepad_id`](Self::set_gamepad_id). If you have multiple local
+/// players, you can create an `InputMap` for each of them.
+///
+/// # Serde
+///
+/// Serialization and deserialization of this type (via [Serde](https://serde.rs/))
+/// can be enabled via the `serde_support` feature, as long as the action type also
+/// supports it. This allows you to save the player's rebound controls to disk and
+/// load them again later.
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde_support",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound(
+        serialize = "A: Eq + Hash + serde::Serialize",
+        deserialize = "A: Eq + Hash + serde::Deserialize<'de>"
+    ))
+)]
+pub struct InputMap<A> {
+    gamepad_id: usize,
+    bindings: HashMap<A, Vec<Binding>>,
+}
+
+impl<A> InputMap<A>
+where
+    A: Eq + Hash,
+{
+    /// Creates a new, empty input map.
+    pub fn new() -> InputMap<A> {
+        InputMap {
+            gamepad_id: 0,
+            bindings: HashMap::new(),
+        }
+    }
+
+    /// Returns the ID of the gamepad that gamepad bindings will be checked against.
+    pub fn gamepad_id(&self) -> usize {
+        self.gamepad_id
+    }
+
+    /// Sets the ID of the gamepad that gamepad bindings will be checked against.
+    pub fn set_gamepad_id(&mut self, gamepad_id: usize) {
+        self.gamepad_id = gamepad_id;
+    }
+
+    /// Adds a binding to the specified action.
+    ///
+    /// If the binding is already assigned to the action, this has no effect.
+    pub fn bind<B>(&mut self, action: A, binding: B)
+    where
+        B: Into<Binding>,
+    {
+        let binding = binding.into();
+        let bindings = self.bindings.entry(action).or_default();
+
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+    }
+
+    /// Removes a binding from the specified action.
+    pub fn unbind<B>(&mut self, action: &A, binding: B)
+    where
+        B: Into<Binding>,
+    {
+        let binding = binding.into();
+
+        if let Some(bindings) = self.bindings.get_mut(action) {
+            bindings.retain(|b| *b != binding);
+        }
+    }
+
+    /// Removes all of the bindings from the specified action.
+    pub fn clear_bindings(&mut self, action: &A) {
+        self.bindings.remove(action);
+    }
+
+    /// Returns the bindings for the specified action, in the order they were added.
+    pub fn get_bindings(&self, action: &A) -> &[Binding] {
+        self.bindings.get(action).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns true if any of the action's bindings are currently down.
+    pub fn is_action_down(&self, ctx: &Context, action: &A) -> bool {
+        self.get_bindings(action)
+            .iter()
+            .any(|b| b.is_down(ctx, self.gamepad_id))
+    }
+
+    /// Returns true if none of the action's bindings are currently down.
+    pub fn is_action_up(&self, ctx: &Context, action: &A) -> bool {
+        !self.is_action_down(ctx, action)
+    }
+
+    /// Returns true if any of the action's bindings were pressed since the last update.
+    pub fn is_action_pressed(&self, ctx: &Context, action: &A) -> bool {
+        self.get_bindings(action)
+            .iter()
+            .any(|b| b.is_pressed(ctx, self.gamepad_id))
+    }
+
+    /// Returns true if any of the action's bindings were released since the last update.
+    pub fn is_action_released(&self, ctx: &Context, action: &A) -> bool {
+        self.get_bindings(action)
+            .iter()
+            .any(|b| b.is_released(ctx, self.gamepad_id))
+    }
+}
+
+impl<A> Default for InputMap<A>
+where
+    A: Eq + Hash,
+{
+    fn default() -> InputMap<A> {
+        InputMap::new()
+    }
+}
+
+#[cfg(all(test, feature = "serde_support"))]
+mod tests {
+    use super::{Binding, InputMap};
+    use crate::input::{GamepadButton, Key, MouseButton};
+
+    #[test]
+    fn input_map_serde_round_trip() {
+        #[derive(Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+        enum Action {
+            Jump,
+            Shoot,
+        }
+
+        let mut map = InputMap::new();
+        map.set_gamepad_id(2);
+        map.bind(Action::Jump, Key::Space);
+        map.bind(Action::Jump, GamepadButton::A);
+        map.bind(Action::Shoot, MouseButton::Left);
+
+        let json = serde_json::to_string(&map).unwrap();
+        let restored: InputMap<Action> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(2, restored.gamepad_id());
+        assert_eq!(
+            &[
+                Binding::Key(Key::Space),
+                Binding::GamepadButton(GamepadButton::A)
+            ],
+            restored.get_bindings(&Action::Jump)
+        );
+        assert_eq!(
+            &[Binding::MouseButton(MouseButton::Left)],
+            restored.get_bindings(&Action::Shoot)
+        );
+    }
+}
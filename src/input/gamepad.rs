@@ -3,6 +3,7 @@ use hashbrown::{HashMap, HashSet};
 use crate::math::Vec2;
 use crate::Context;
 
+#[derive(Debug, Clone)]
 pub(crate) struct GamepadState {
     pub platform_id: u32,
     pub buttons_down: HashSet<GamepadButton>,
@@ -79,6 +80,82 @@ pub enum GamepadButton {
     Guide,
 }
 
+impl GamepadButton {
+    /// Returns a human-readable name for the button, which does not depend on the type of
+    /// gamepad being used.
+    ///
+    /// If you want to show the label that is printed on a specific type of gamepad (e.g.
+    /// 'Cross' on a PlayStation controller), use [`label`](Self::label) instead.
+    pub fn name(self) -> &'static str {
+        match self {
+            GamepadButton::A => "A",
+            GamepadButton::B => "B",
+            GamepadButton::X => "X",
+            GamepadButton::Y => "Y",
+            GamepadButton::Up => "D-Pad Up",
+            GamepadButton::Down => "D-Pad Down",
+            GamepadButton::Left => "D-Pad Left",
+            GamepadButton::Right => "D-Pad Right",
+            GamepadButton::LeftShoulder => "Left Shoulder",
+            GamepadButton::LeftTrigger => "Left Trigger",
+            GamepadButton::LeftStick => "Left Stick",
+            GamepadButton::RightShoulder => "Right Shoulder",
+            GamepadButton::RightTrigger => "Right Trigger",
+            GamepadButton::RightStick => "Right Stick",
+            GamepadButton::Start => "Start",
+            GamepadButton::Back => "Back",
+            GamepadButton::Guide => "Guide",
+        }
+    }
+
+    /// Returns the label that is printed on the button for the given type of gamepad.
+    ///
+    /// This can be used alongside [`get_gamepad_type`] to display the correct button prompts
+    /// for the player's controller. If the type of gamepad is unknown, this is the same as
+    /// [`name`](Self::name).
+    pub fn label(self, gamepad_type: GamepadType) -> &'static str {
+        match (gamepad_type, self) {
+            (GamepadType::Xbox, GamepadButton::LeftShoulder) => "LB",
+            (GamepadType::Xbox, GamepadButton::LeftTrigger) => "LT",
+            (GamepadType::Xbox, GamepadButton::LeftStick) => "LS",
+            (GamepadType::Xbox, GamepadButton::RightShoulder) => "RB",
+            (GamepadType::Xbox, GamepadButton::RightTrigger) => "RT",
+            (GamepadType::Xbox, GamepadButton::RightStick) => "RS",
+            (GamepadType::Xbox, GamepadButton::Start) => "Menu",
+            (GamepadType::Xbox, GamepadButton::Back) => "View",
+            (GamepadType::Xbox, GamepadButton::Guide) => "Xbox",
+
+            (GamepadType::PlayStation, GamepadButton::A) => "Cross",
+            (GamepadType::PlayStation, GamepadButton::B) => "Circle",
+            (GamepadType::PlayStation, GamepadButton::X) => "Square",
+            (GamepadType::PlayStation, GamepadButton::Y) => "Triangle",
+            (GamepadType::PlayStation, GamepadButton::LeftShoulder) => "L1",
+            (GamepadType::PlayStation, GamepadButton::LeftTrigger) => "L2",
+            (GamepadType::PlayStation, GamepadButton::LeftStick) => "L3",
+            (GamepadType::PlayStation, GamepadButton::RightShoulder) => "R1",
+            (GamepadType::PlayStation, GamepadButton::RightTrigger) => "R2",
+            (GamepadType::PlayStation, GamepadButton::RightStick) => "R3",
+            (GamepadType::PlayStation, GamepadButton::Start) => "Options",
+            (GamepadType::PlayStation, GamepadButton::Back) => "Share",
+            (GamepadType::PlayStation, GamepadButton::Guide) => "PS",
+
+            // SDL maps the face buttons on Nintendo controllers by their label rather than
+            // their position, so A/B/X/Y can be used as-is.
+            (GamepadType::NintendoSwitch, GamepadButton::LeftShoulder) => "L",
+            (GamepadType::NintendoSwitch, GamepadButton::LeftTrigger) => "ZL",
+            (GamepadType::NintendoSwitch, GamepadButton::LeftStick) => "L Stick",
+            (GamepadType::NintendoSwitch, GamepadButton::RightShoulder) => "R",
+            (GamepadType::NintendoSwitch, GamepadButton::RightTrigger) => "ZR",
+            (GamepadType::NintendoSwitch, GamepadButton::RightStick) => "R Stick",
+            (GamepadType::NintendoSwitch, GamepadButton::Start) => "+",
+            (GamepadType::NintendoSwitch, GamepadButton::Back) => "-",
+            (GamepadType::NintendoSwitch, GamepadButton::Guide) => "Home",
+
+            _ => self.name(),
+        }
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(
     feature = "serde_support",
@@ -100,6 +177,20 @@ pub enum GamepadAxis {
     RightTrigger,
 }
 
+impl GamepadAxis {
+    /// Returns a human-readable name for the axis.
+    pub fn name(self) -> &'static str {
+        match self {
+            GamepadAxis::LeftStickX => "Left Stick X",
+            GamepadAxis::LeftStickY => "Left Stick Y",
+            GamepadAxis::LeftTrigger => "Left Trigger",
+            GamepadAxis::RightStickX => "Right Stick X",
+            GamepadAxis::RightStickY => "Right Stick Y",
+            GamepadAxis::RightTrigger => "Right Trigger",
+        }
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(
     feature = "serde_support",
@@ -117,6 +208,35 @@ pub enum GamepadStick {
     RightStick,
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde_support",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[non_exhaustive]
+/// The family of controllers that a gamepad belongs to.
+///
+/// This is detected based on the manufacturer of the gamepad, so third-party controllers
+/// may be reported as [`Unknown`](GamepadType::Unknown).
+///
+/// # Serde
+///
+/// Serialization and deserialization of this type (via [Serde](https://serde.rs/))
+/// can be enabled via the `serde_support` feature.
+pub enum GamepadType {
+    /// An Xbox controller (or one that identifies itself as such).
+    Xbox,
+
+    /// A PlayStation controller.
+    PlayStation,
+
+    /// A Nintendo Switch controller.
+    NintendoSwitch,
+
+    /// A gamepad of an unrecognized type.
+    Unknown,
+}
+
 /// Returns true if the specified gamepad is currently connected.
 pub fn is_gamepad_connected(ctx: &Context, gamepad_id: usize) -> bool {
     get_gamepad(ctx, gamepad_id).is_some()
@@ -129,6 +249,16 @@ pub fn get_gamepad_name(ctx: &Context, gamepad_id: usize) -> Option<String> {
         .map(|id| ctx.window.get_gamepad_name(id))
 }
 
+/// Returns the type of the specified gamepad, or [`None`] if it is not connected.
+///
+/// This can be used alongside [`GamepadButton::label`] to display the correct button prompts
+/// for the player's controller.
+pub fn get_gamepad_type(ctx: &Context, gamepad_id: usize) -> Option<GamepadType> {
+    get_gamepad(ctx, gamepad_id)
+        .map(|g| g.platform_id)
+        .map(|id| ctx.window.get_gamepad_type(id))
+}
+
 /// Returns true if the specified gamepad button is currently down.
 ///
 /// If the gamepad is disconnected, this will always return `false`.
@@ -162,6 +292,19 @@ pub fn is_gamepad_button_pressed(ctx: &Context, gamepad_id: usize, button: Gamep
     }
 }
 
+/// Returns true if any button on the specified gamepad was pressed since the last update.
+///
+/// This can be useful for implementing 'press any button' prompts.
+///
+/// If the gamepad is disconnected, this will always return `false`.
+pub fn any_gamepad_button_pressed(ctx: &Context, gamepad_id: usize) -> bool {
+    if let Some(pad) = get_gamepad(ctx, gamepad_id) {
+        !pad.buttons_pressed.is_empty()
+    } else {
+        false
+    }
+}
+
 /// Returns true if the specified gamepad button was released since the last update.
 ///
 /// If the gamepad is disconnected, this will always return `false`.
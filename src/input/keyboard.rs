@@ -1,3 +1,4 @@
+use crate::input::InputDevice;
 use crate::Context;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
@@ -8,6 +9,10 @@ use crate::Context;
 #[allow(missing_docs)]
 /// A key on a keyboard.
 ///
+/// Keys are identified by the character they produce on the player's keyboard layout - for
+/// example, [`Key::Z`] refers to the key labelled 'Z', wherever it is on the keyboard. If you
+/// need to refer to a key by its physical position instead, use [`Scancode`].
+///
 /// # Serde
 ///
 /// Serialization and deserialization of this type (via [Serde](https://serde.rs/))
@@ -96,9 +101,11 @@ pub enum Key {
     LeftCtrl,
     LeftShift,
     LeftAlt,
+    LeftSuper,
     RightCtrl,
     RightShift,
     RightAlt,
+    RightSuper,
 
     Up,
     Down,
@@ -150,6 +157,149 @@ pub enum Key {
     Underscore,
 }
 
+/// A physical key on a keyboard, identified by its position rather than by the character
+/// it produces.
+///
+/// The names of the variants are based on the US QWERTY layout - for example,
+/// [`Scancode::W`] refers to the key that is labelled 'W' on a QWERTY keyboard, but 'Z' on
+/// an AZERTY keyboard. This makes scancodes a good fit for controls that depend on the
+/// layout of the keys (such as WASD movement), as they will work the same way regardless
+/// of the player's keyboard layout. For controls that depend on the character printed on the
+/// key (such as 'I' for inventory), use [`Key`] instead.
+///
+/// # Serde
+///
+/// Serialization and deserialization of this type (via [Serde](https://serde.rs/))
+/// can be enabled via the `serde_support` feature.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde_support",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[allow(missing_docs)]
+pub enum Scancode {
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+    G,
+    H,
+    I,
+    J,
+    K,
+    L,
+    M,
+    N,
+    O,
+    P,
+    Q,
+    R,
+    S,
+    T,
+    U,
+    V,
+    W,
+    X,
+    Y,
+    Z,
+
+    Num0,
+    Num1,
+    Num2,
+    Num3,
+    Num4,
+    Num5,
+    Num6,
+    Num7,
+    Num8,
+    Num9,
+
+    F1,
+    F2,
+    F3,
+    F4,
+    F5,
+    F6,
+    F7,
+    F8,
+    F9,
+    F10,
+    F11,
+    F12,
+    F13,
+    F14,
+    F15,
+    F16,
+    F17,
+    F18,
+    F19,
+    F20,
+    F21,
+    F22,
+    F23,
+    F24,
+
+    NumLock,
+    NumPad1,
+    NumPad2,
+    NumPad3,
+    NumPad4,
+    NumPad5,
+    NumPad6,
+    NumPad7,
+    NumPad8,
+    NumPad9,
+    NumPad0,
+    NumPadPlus,
+    NumPadMinus,
+    NumPadMultiply,
+    NumPadDivide,
+    NumPadEnter,
+
+    LeftCtrl,
+    LeftShift,
+    LeftAlt,
+    LeftSuper,
+    RightCtrl,
+    RightShift,
+    RightAlt,
+    RightSuper,
+
+    Up,
+    Down,
+    Left,
+    Right,
+
+    Backquote,
+    Backslash,
+    Backspace,
+    CapsLock,
+    Comma,
+    Delete,
+    End,
+    Enter,
+    Equals,
+    Escape,
+    Home,
+    Insert,
+    LeftBracket,
+    Minus,
+    PageDown,
+    PageUp,
+    Pause,
+    Period,
+    PrintScreen,
+    Quote,
+    RightBracket,
+    ScrollLock,
+    Semicolon,
+    Slash,
+    Space,
+    Tab,
+}
+
 /// A key modifier on the keyboard.
 ///
 /// These mainly consist of keys that have duplicates in multiple places on the keyboard, such as
@@ -169,6 +319,54 @@ pub enum KeyModifier {
     Ctrl,
     Alt,
     Shift,
+    /// The Windows key on Windows, the Command key on Mac, or the Super key on Linux.
+    Super,
+}
+
+/// The set of key modifiers that are currently held down.
+///
+/// This can be obtained via [`get_modifiers`], and is useful for implementing keyboard
+/// shortcuts. Each modifier is considered to be down if either its left or right key
+/// is down.
+///
+/// # Serde
+///
+/// Serialization and deserialization of this type (via [Serde](https://serde.rs/))
+/// can be enabled via the `serde_support` feature.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(
+    feature = "serde_support",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct KeyModifiers {
+    /// Whether either Ctrl key is down.
+    pub ctrl: bool,
+
+    /// Whether either Alt key is down.
+    pub alt: bool,
+
+    /// Whether either Shift key is down.
+    pub shift: bool,
+
+    /// Whether either Super key (the Windows key on Windows, or the Command key on Mac) is down.
+    pub super_key: bool,
+}
+
+impl KeyModifiers {
+    /// Returns true if the specified key modifier is down.
+    pub fn contains(&self, key_modifier: KeyModifier) -> bool {
+        match key_modifier {
+            KeyModifier::Ctrl => self.ctrl,
+            KeyModifier::Alt => self.alt,
+            KeyModifier::Shift => self.shift,
+            KeyModifier::Super => self.super_key,
+        }
+    }
+
+    /// Returns true if no key modifiers are down.
+    pub fn is_empty(&self) -> bool {
+        !(self.ctrl || self.alt || self.shift || self.super_key)
+    }
 }
 
 /// Returns true if the specified key is currently down.
@@ -191,6 +389,26 @@ pub fn is_key_released(ctx: &Context, key: Key) -> bool {
     ctx.input.keys_released.contains(&key)
 }
 
+/// Returns true if the specified physical key is currently down.
+pub fn is_scancode_down(ctx: &Context, scancode: Scancode) -> bool {
+    ctx.input.scancodes_down.contains(&scancode)
+}
+
+/// Returns true if the specified physical key is currently up.
+pub fn is_scancode_up(ctx: &Context, scancode: Scancode) -> bool {
+    !ctx.input.scancodes_down.contains(&scancode)
+}
+
+/// Returns true if the specified physical key was pressed since the last update.
+pub fn is_scancode_pressed(ctx: &Context, scancode: Scancode) -> bool {
+    ctx.input.scancodes_pressed.contains(&scancode)
+}
+
+/// Returns true if the specified physical key was released since the last update.
+pub fn is_scancode_released(ctx: &Context, scancode: Scancode) -> bool {
+    ctx.input.scancodes_released.contains(&scancode)
+}
+
 /// Returns true if the specified key modifier is currently down.
 pub fn is_key_modifier_down(ctx: &Context, key_modifier: KeyModifier) -> bool {
     let (a, b) = get_modifier_keys(key_modifier);
@@ -205,6 +423,51 @@ pub fn is_key_modifier_up(ctx: &Context, key_modifier: KeyModifier) -> bool {
     is_key_up(ctx, a) && is_key_up(ctx, b)
 }
 
+/// Returns the set of key modifiers that are currently down.
+pub fn get_modifiers(ctx: &Context) -> KeyModifiers {
+    KeyModifiers {
+        ctrl: is_key_modifier_down(ctx, KeyModifier::Ctrl),
+        alt: is_key_modifier_down(ctx, KeyModifier::Alt),
+        shift: is_key_modifier_down(ctx, KeyModifier::Shift),
+        super_key: is_key_modifier_down(ctx, KeyModifier::Super),
+    }
+}
+
+/// Returns true if either Ctrl key is currently down.
+///
+/// This is a shortcut for [`is_key_modifier_down(ctx, KeyModifier::Ctrl)`](is_key_modifier_down).
+pub fn is_ctrl_down(ctx: &Context) -> bool {
+    is_key_modifier_down(ctx, KeyModifier::Ctrl)
+}
+
+/// Returns true if either Alt key is currently down.
+///
+/// This is a shortcut for [`is_key_modifier_down(ctx, KeyModifier::Alt)`](is_key_modifier_down).
+pub fn is_alt_down(ctx: &Context) -> bool {
+    is_key_modifier_down(ctx, KeyModifier::Alt)
+}
+
+/// Returns true if either Shift key is currently down.
+///
+/// This is a shortcut for [`is_key_modifier_down(ctx, KeyModifier::Shift)`](is_key_modifier_down).
+pub fn is_shift_down(ctx: &Context) -> bool {
+    is_key_modifier_down(ctx, KeyModifier::Shift)
+}
+
+/// Returns true if either Super key is currently down.
+///
+/// This is a shortcut for [`is_key_modifier_down(ctx, KeyModifier::Super)`](is_key_modifier_down).
+pub fn is_super_down(ctx: &Context) -> bool {
+    is_key_modifier_down(ctx, KeyModifier::Super)
+}
+
+/// Returns true if any key was pressed since the last update.
+///
+/// This can be useful for implementing 'press any key' prompts.
+pub fn any_key_pressed(ctx: &Context) -> bool {
+    !ctx.input.keys_pressed.is_empty()
+}
+
 /// Returns an iterator of the keys that are currently down.
 pub fn get_keys_down(ctx: &Context) -> impl Iterator<Item = &Key> {
     ctx.input.keys_down.iter()
@@ -224,6 +487,7 @@ pub(crate) fn set_key_down(ctx: &mut Context, key: Key) -> bool {
     let was_up = ctx.input.keys_down.insert(key);
 
     if was_up {
+        ctx.input.last_input_device = Some(InputDevice::Keyboard);
         ctx.input.keys_pressed.insert(key);
     }
 
@@ -240,10 +504,31 @@ pub(crate) fn set_key_up(ctx: &mut Context, key: Key) -> bool {
     was_down
 }
 
+pub(crate) fn set_scancode_down(ctx: &mut Context, scancode: Scancode) -> bool {
+    let was_up = ctx.input.scancodes_down.insert(scancode);
+
+    if was_up {
+        ctx.input.scancodes_pressed.insert(scancode);
+    }
+
+    was_up
+}
+
+pub(crate) fn set_scancode_up(ctx: &mut Context, scancode: Scancode) -> bool {
+    let was_down = ctx.input.scancodes_down.remove(&scancode);
+
+    if was_down {
+        ctx.input.scancodes_released.insert(scancode);
+    }
+
+    was_down
+}
+
 pub(crate) fn get_modifier_keys(key_modifier: KeyModifier) -> (Key, Key) {
     match key_modifier {
         KeyModifier::Ctrl => (Key::LeftCtrl, Key::RightCtrl),
         KeyModifier::Alt => (Key::LeftAlt, Key::RightAlt),
         KeyModifier::Shift => (Key::LeftShift, Key::RightShift),
+        KeyModifier::Super => (Key::LeftSuper, Key::RightSuper),
     }
 }
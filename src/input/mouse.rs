@@ -1,3 +1,4 @@
+use crate::input::InputDevice;
 use crate::math::Vec2;
 use crate::Context;
 
@@ -43,12 +44,12 @@ pub fn is_mouse_button_released(ctx: &Context, button: MouseButton) -> bool {
 
 /// Returns true if the user scrolled up since the last update.
 pub fn is_mouse_scrolled_up(ctx: &Context) -> bool {
-    get_mouse_wheel_movement(ctx).y > 0
+    get_mouse_wheel(ctx).y > 0
 }
 
 /// Returns true if the user scrolled down since the last update.
 pub fn is_mouse_scrolled_down(ctx: &Context) -> bool {
-    get_mouse_wheel_movement(ctx).y < 0
+    get_mouse_wheel(ctx).y < 0
 }
 
 /// Get the X co-ordinate of the mouse.
@@ -62,6 +63,10 @@ pub fn get_mouse_y(ctx: &Context) -> f32 {
 }
 
 /// Get the position of the mouse.
+///
+/// This is measured in logical pixels, relative to the top left of the window. This
+/// matches the co-ordinate space used for rendering to the window, even if high-DPI
+/// rendering is enabled.
 pub fn get_mouse_position(ctx: &Context) -> Vec2<f32> {
     ctx.input.mouse_position
 }
@@ -73,14 +78,31 @@ pub fn get_mouse_position(ctx: &Context) -> Vec2<f32> {
 ///
 /// Positive values correspond to scrolling up/right, negative values correspond to scrolling
 /// down/left.
-pub fn get_mouse_wheel_movement(ctx: &Context) -> Vec2<i32> {
+///
+/// This value is returned as floating point, so that precise scrolling can be supported in
+/// the future without another breaking change. However, the version of SDL that Tetra currently
+/// binds to only reports whole steps, so for now the values will always be whole numbers,
+/// matching [`get_mouse_wheel`].
+pub fn get_mouse_wheel_movement(ctx: &Context) -> Vec2<f32> {
     ctx.input.mouse_wheel_movement
 }
 
+/// Get the number of whole steps that the mouse wheel moved since the last update.
+///
+/// This is equivalent to [`get_mouse_wheel_movement`], but with the values truncated to integers,
+/// matching the behaviour of older versions of Tetra.
+///
+/// Positive values correspond to scrolling up/right, negative values correspond to scrolling
+/// down/left.
+pub fn get_mouse_wheel(ctx: &Context) -> Vec2<i32> {
+    ctx.input.mouse_wheel_ticks
+}
+
 pub(crate) fn set_mouse_button_down(ctx: &mut Context, btn: MouseButton) -> bool {
     let was_up = ctx.input.mouse_buttons_down.insert(btn);
 
     if was_up {
+        ctx.input.last_input_device = Some(InputDevice::Mouse);
         ctx.input.mouse_buttons_pressed.insert(btn);
     }
 
@@ -97,10 +119,44 @@ pub(crate) fn set_mouse_button_up(ctx: &mut Context, btn: MouseButton) -> bool {
     was_down
 }
 
-pub(crate) fn set_mouse_position(ctx: &mut Context, position: Vec2<f32>) {
+/// Moves the mouse cursor to the specified position.
+///
+/// This uses the same co-ordinate space as [`get_mouse_position`] (logical pixels, relative
+/// to the top left of the window), and the new position will be returned by
+/// [`get_mouse_position`] straight away. This can be useful for re-centering the cursor
+/// for mouselook, or for putting it back after dragging a UI element.
+///
+/// Moving the cursor will not be reported as movement in the `delta` of the next
+/// [`Event::MouseMoved`](crate::Event::MouseMoved), so it is safe to use this in
+/// combination with [relative mouse mode](crate::window::set_relative_mouse_mode).
+pub fn set_mouse_position(ctx: &mut Context, position: Vec2<f32>) {
+    ctx.window
+        .warp_mouse(position.x.round() as i32, position.y.round() as i32);
+
+    ctx.input.mouse_position = position;
+    ctx.input.mouse_warp_target = Some(position.round());
+}
+
+pub(crate) fn update_mouse_position(ctx: &mut Context, position: Vec2<f32>) {
     ctx.input.mouse_position = position;
+    ctx.input.last_input_device = Some(InputDevice::Mouse);
+}
+
+/// Returns `true` if the given position is where the cursor was last moved to via
+/// [`set_mouse_position`], in which case the motion should not be treated as a delta.
+///
+/// Only the first motion event after a warp is checked, so that a stale target can't
+/// swallow real movement later on.
+pub(crate) fn take_mouse_warp(ctx: &mut Context, position: Vec2<f32>) -> bool {
+    ctx.input.mouse_warp_target.take() == Some(position)
 }
 
-pub(crate) fn apply_mouse_wheel_movement(ctx: &mut Context, wheel_movement: Vec2<i32>) {
+pub(crate) fn apply_mouse_wheel_movement(
+    ctx: &mut Context,
+    wheel_movement: Vec2<f32>,
+    wheel_ticks: Vec2<i32>,
+) {
     ctx.input.mouse_wheel_movement += wheel_movement;
+    ctx.input.mouse_wheel_ticks += wheel_ticks;
+    ctx.input.last_input_device = Some(InputDevice::Mouse);
 }
@@ -0,0 +1,257 @@
+use super::gamepad::GamepadState;
+use super::keyboard::get_modifier_keys;
+use crate::input::{
+    GamepadAxis, GamepadButton, GamepadStick, InputContext, InputDevice, Key, KeyModifier,
+    KeyModifiers, MouseButton, Scancode,
+};
+use crate::math::Vec2;
+use crate::Context;
+
+/// A copy of the player's input state, taken at a specific point in time.
+///
+/// This can be created via [`snapshot`], and offers the same queries as the functions in the
+/// [`input`](crate::input) module. As it does not borrow the [`Context`], it can be stored
+/// (e.g. to compare against the state of the next frame) or passed into code that
+/// doesn't have access to the `Context`.
+///
+/// Platform-level operations (such as reading the clipboard or controlling gamepad vibration)
+/// are not available on a snapshot.
+#[derive(Debug, Clone)]
+pub struct InputState {
+    input: InputContext,
+}
+
+impl InputState {
+    /// Returns true if the specified key is down.
+    pub fn is_key_down(&self, key: Key) -> bool {
+        self.input.keys_down.contains(&key)
+    }
+
+    /// Returns true if the specified key is up.
+    pub fn is_key_up(&self, key: Key) -> bool {
+        !self.input.keys_down.contains(&key)
+    }
+
+    /// Returns true if the specified key was pressed since the last update.
+    pub fn is_key_pressed(&self, key: Key) -> bool {
+        self.input.keys_pressed.contains(&key)
+    }
+
+    /// Returns true if the specified key was released since the last update.
+    pub fn is_key_released(&self, key: Key) -> bool {
+        self.input.keys_released.contains(&key)
+    }
+
+    /// Returns true if the specified physical key is down.
+    pub fn is_scancode_down(&self, scancode: Scancode) -> bool {
+        self.input.scancodes_down.contains(&scancode)
+    }
+
+    /// Returns true if the specified physical key is up.
+    pub fn is_scancode_up(&self, scancode: Scancode) -> bool {
+        !self.input.scancodes_down.contains(&scancode)
+    }
+
+    /// Returns true if the specified physical key was pressed since the last update.
+    pub fn is_scancode_pressed(&self, scancode: Scancode) -> bool {
+        self.input.scancodes_pressed.contains(&scancode)
+    }
+
+    /// Returns true if the specified physical key was released since the last update.
+    pub fn is_scancode_released(&self, scancode: Scancode) -> bool {
+        self.input.scancodes_released.contains(&scancode)
+    }
+
+    /// Returns true if the specified key modifier is down.
+    pub fn is_key_modifier_down(&self, key_modifier: KeyModifier) -> bool {
+        let (a, b) = get_modifier_keys(key_modifier);
+
+        self.is_key_down(a) || self.is_key_down(b)
+    }
+
+    /// Returns true if the specified key modifier is up.
+    pub fn is_key_modifier_up(&self, key_modifier: KeyModifier) -> bool {
+        let (a, b) = get_modifier_keys(key_modifier);
+
+        self.is_key_up(a) && self.is_key_up(b)
+    }
+
+    /// Returns the set of key modifiers that are down.
+    pub fn get_modifiers(&self) -> KeyModifiers {
+        KeyModifiers {
+            ctrl: self.is_key_modifier_down(KeyModifier::Ctrl),
+            alt: self.is_key_modifier_down(KeyModifier::Alt),
+            shift: self.is_key_modifier_down(KeyModifier::Shift),
+            super_key: self.is_key_modifier_down(KeyModifier::Super),
+        }
+    }
+
+    /// Returns true if any key was pressed since the last update.
+    pub fn any_key_pressed(&self) -> bool {
+        !self.input.keys_pressed.is_empty()
+    }
+
+    /// Returns an iterator of the keys that are down.
+    pub fn get_keys_down(&self) -> impl Iterator<Item = &Key> {
+        self.input.keys_down.iter()
+    }
+
+    /// Returns an iterator of the keys that were pressed since the last update.
+    pub fn get_keys_pressed(&self) -> impl Iterator<Item = &Key> {
+        self.input.keys_pressed.iter()
+    }
+
+    /// Returns an iterator of the keys that were released since the last update.
+    pub fn get_keys_released(&self) -> impl Iterator<Item = &Key> {
+        self.input.keys_released.iter()
+    }
+
+    /// Returns true if the specified mouse button is down.
+    pub fn is_mouse_button_down(&self, button: MouseButton) -> bool {
+        self.input.mouse_buttons_down.contains(&button)
+    }
+
+    /// Returns true if the specified mouse button is up.
+    pub fn is_mouse_button_up(&self, button: MouseButton) -> bool {
+        !self.input.mouse_buttons_down.contains(&button)
+    }
+
+    /// Returns true if the specified mouse button was pressed since the last update.
+    pub fn is_mouse_button_pressed(&self, button: MouseButton) -> bool {
+        self.input.mouse_buttons_pressed.contains(&button)
+    }
+
+    /// Returns true if the specified mouse button was released since the last update.
+    pub fn is_mouse_button_released(&self, button: MouseButton) -> bool {
+        self.input.mouse_buttons_released.contains(&button)
+    }
+
+    /// Returns the position of the mouse.
+    pub fn get_mouse_position(&self) -> Vec2<f32> {
+        self.input.mouse_position
+    }
+
+    /// Returns the amount that the mouse wheel moved since the last update.
+    ///
+    /// See [`get_mouse_wheel_movement`](crate::input::get_mouse_wheel_movement) for details
+    /// on how this differs from [`get_mouse_wheel`](Self::get_mouse_wheel).
+    pub fn get_mouse_wheel_movement(&self) -> Vec2<f32> {
+        self.input.mouse_wheel_movement
+    }
+
+    /// Returns the number of 'clicks' that the mouse wheel moved since the last update.
+    pub fn get_mouse_wheel(&self) -> Vec2<i32> {
+        self.input.mouse_wheel_ticks
+    }
+
+    /// Returns the text that the user entered since the last update.
+    pub fn get_text_input(&self) -> Option<&str> {
+        self.input.current_text_input.as_deref()
+    }
+
+    /// Returns true if the specified gamepad was connected.
+    pub fn is_gamepad_connected(&self, gamepad_id: usize) -> bool {
+        self.gamepad(gamepad_id).is_some()
+    }
+
+    /// Returns true if the specified gamepad button is down.
+    ///
+    /// If the gamepad is disconnected, this will always return `false`.
+    pub fn is_gamepad_button_down(&self, gamepad_id: usize, button: GamepadButton) -> bool {
+        if let Some(pad) = self.gamepad(gamepad_id) {
+            pad.buttons_down.contains(&button)
+        } else {
+            false
+        }
+    }
+
+    /// Returns true if the specified gamepad button is up.
+    ///
+    /// If the gamepad is disconnected, this will always return `true`.
+    pub fn is_gamepad_button_up(&self, gamepad_id: usize, button: GamepadButton) -> bool {
+        if let Some(pad) = self.gamepad(gamepad_id) {
+            !pad.buttons_down.contains(&button)
+        } else {
+            true
+        }
+    }
+
+    /// Returns true if the specified gamepad button was pressed since the last update.
+    ///
+    /// If the gamepad is disconnected, this will always return `false`.
+    pub fn is_gamepad_button_pressed(&self, gamepad_id: usize, button: GamepadButton) -> bool {
+        if let Some(pad) = self.gamepad(gamepad_id) {
+            pad.buttons_pressed.contains(&button)
+        } else {
+            false
+        }
+    }
+
+    /// Returns true if any button on the specified gamepad was pressed since the last update.
+    ///
+    /// If the gamepad is disconnected, this will always return `false`.
+    pub fn any_gamepad_button_pressed(&self, gamepad_id: usize) -> bool {
+        if let Some(pad) = self.gamepad(gamepad_id) {
+            !pad.buttons_pressed.is_empty()
+        } else {
+            false
+        }
+    }
+
+    /// Returns true if the specified gamepad button was released since the last update.
+    ///
+    /// If the gamepad is disconnected, this will always return `false`.
+    pub fn is_gamepad_button_released(&self, gamepad_id: usize, button: GamepadButton) -> bool {
+        if let Some(pad) = self.gamepad(gamepad_id) {
+            pad.buttons_released.contains(&button)
+        } else {
+            false
+        }
+    }
+
+    /// Returns the position of the specified gamepad axis.
+    ///
+    /// If the gamepad is disconnected, this will always return `0.0`.
+    pub fn get_gamepad_axis_position(&self, gamepad_id: usize, axis: GamepadAxis) -> f32 {
+        self.gamepad(gamepad_id)
+            .and_then(|pad| pad.current_axis_state.get(&axis))
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    /// Returns the position of the specified gamepad control stick.
+    ///
+    /// If the gamepad is disconnected, this will always return `(0.0, 0.0)`.
+    pub fn get_gamepad_stick_position(&self, gamepad_id: usize, stick: GamepadStick) -> Vec2<f32> {
+        let (x_axis, y_axis) = match stick {
+            GamepadStick::LeftStick => (GamepadAxis::LeftStickX, GamepadAxis::LeftStickY),
+            GamepadStick::RightStick => (GamepadAxis::RightStickX, GamepadAxis::RightStickY),
+        };
+
+        Vec2::new(
+            self.get_gamepad_axis_position(gamepad_id, x_axis),
+            self.get_gamepad_axis_position(gamepad_id, y_axis),
+        )
+    }
+
+    /// Returns the device that the player most recently used to provide input, or `None`
+    /// if no input has been received yet.
+    pub fn get_last_input_device(&self) -> Option<InputDevice> {
+        self.input.last_input_device
+    }
+
+    fn gamepad(&self, gamepad_id: usize) -> Option<&GamepadState> {
+        self.input.pads.get(gamepad_id).and_then(Option::as_ref)
+    }
+}
+
+/// Takes a snapshot of the player's current input state.
+///
+/// The input state is already guaranteed to be stable for the duration of an
+/// [`update`](crate::State::update), so this is only needed if you want to hold onto
+/// the state without borrowing the [`Context`].
+pub fn snapshot(ctx: &Context) -> InputState {
+    InputState {
+        input: ctx.input.clone(),
+    }
+}
@@ -61,6 +61,7 @@
 
 #![warn(missing_docs)]
 
+pub mod asset;
 #[cfg(feature = "audio")]
 pub mod audio;
 mod context;
@@ -74,6 +75,13 @@ mod platform;
 pub mod time;
 pub mod window;
 
-pub use crate::context::{Context, ContextBuilder};
+pub use crate::context::{Context, ContextBuilder, GlProfile};
 pub use crate::error::{Result, TetraError};
 pub use crate::lifecycle::{Event, State};
+
+/// A re-export of the version of the [`sdl2`](https://docs.rs/sdl2) crate that Tetra uses,
+/// so that you can interact with the events passed via [`Event::Raw`].
+///
+/// This is only available when the `sdl2_raw_events` feature is enabled.
+#[cfg(feature = "sdl2_raw_events")]
+pub use sdl2;
@@ -189,4 +189,31 @@ pub enum Event {
         /// The path of the file that was dropped.
         path: PathBuf,
     },
+
+    /// An audio output device was connected to or disconnected from the system.
+    ///
+    /// If Tetra is using the system's default audio device (i.e. you have not called
+    /// [`audio::set_device`](crate::audio::set_device)), it will automatically switch to the
+    /// new default device before this event is fired. You can use
+    /// [`audio::get_devices`](crate::audio::get_devices) to find out which devices are now
+    /// available.
+    ///
+    /// This event may also be fired for each device that is already connected when
+    /// the game starts up.
+    AudioDevicesChanged,
+
+    /// An event was received from SDL2.
+    ///
+    /// This will be fired for every event that SDL2 sends, after Tetra has finished
+    /// handling it (including firing any of the other events above). It is intended as
+    /// an escape hatch for integrating with other SDL2-based libraries, or for handling
+    /// events that Tetra does not expose.
+    ///
+    /// This is only available when the `sdl2_raw_events` feature is enabled. As it exposes
+    /// Tetra's platform layer, it may change in any release.
+    #[cfg(feature = "sdl2_raw_events")]
+    Raw {
+        /// The event that was received.
+        event: sdl2::event::Event,
+    },
 }
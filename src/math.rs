@@ -12,6 +12,21 @@
 //! Note that all of the important types in [`vek`] (such as [`Vec2`] and [`Mat4`]) are
 //! re-exported at the top level - you don't need to dig down into the submodules
 //! when importing things.
+//!
+//! In addition to the [`vek`] types, this module provides [`Transform2D`], which represents
+//! a 2D position, rotation and scale, and [`Random`], a simple seedable random number
+//! generator.
+//!
+//! # Serde
+//!
+//! Serialization and deserialization of the [`vek`] types (via [Serde](https://serde.rs/))
+//! can be enabled via the `serde_support` feature.
+
+mod random;
+mod transform2d;
 
 #[doc(no_inline)]
 pub use vek::*;
+
+pub use random::*;
+pub use transform2d::*;
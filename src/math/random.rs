@@ -0,0 +1,188 @@
+/// A small, fast, seedable pseudo-random number generator.
+///
+/// This uses the [PCG32](https://www.pcg-random.org/) algorithm, which gives good
+/// statistical quality without needing much state. Given the same seed, a `Random` will
+/// always produce the same sequence of values, on every platform - this makes it useful
+/// for reproducible level generation, replays and tests.
+///
+/// This is not a cryptographically secure generator, so it should not be used for anything
+/// security-sensitive. If you need more than the basics, consider using a dedicated crate
+/// such as [`rand`](https://crates.io/crates/rand) instead.
+///
+/// # Serde
+///
+/// Serialization and deserialization of this type (via [Serde](https://serde.rs/))
+/// can be enabled via the `serde_support` feature. This stores the generator's
+/// current state, so a deserialized `Random` will continue the sequence from the
+/// point where it was serialized.
+///
+/// # Examples
+///
+/// ```
+/// # use tetra::math::Random;
+/// let mut a = Random::new(12345);
+/// let mut b = Random::new(12345);
+///
+/// assert_eq!(a.range(0, 100), b.range(0, 100));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde_support",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct Random {
+    state: u64,
+    increment: u64,
+}
+
+const MULTIPLIER: u64 = 6_364_136_223_846_793_005;
+const DEFAULT_STREAM: u64 = 0xda3e_39cb_94b9_5bdb;
+
+impl Random {
+    /// Creates a new generator, using the given seed.
+    pub fn new(seed: u64) -> Random {
+        let mut random = Random {
+            state: 0,
+            increment: (DEFAULT_STREAM << 1) | 1,
+        };
+
+        random.next_u32();
+        random.state = random.state.wrapping_add(seed);
+        random.next_u32();
+
+        random
+    }
+
+    /// Returns a random `u32`, covering the full range of the type.
+    pub fn next_u32(&mut self) -> u32 {
+        let old_state = self.state;
+
+        self.state = old_state
+            .wrapping_mul(MULTIPLIER)
+            .wrapping_add(self.increment);
+
+        let xor_shifted = (((old_state >> 18) ^ old_state) >> 27) as u32;
+        let rotation = (old_state >> 59) as u32;
+
+        xor_shifted.rotate_right(rotation)
+    }
+
+    /// Returns a random integer between `min` (inclusive) and `max` (exclusive).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `min` is not less than `max`.
+    pub fn range(&mut self, min: i32, max: i32) -> i32 {
+        assert!(min < max, "min must be less than max");
+
+        let span = (max as i64 - min as i64) as u64;
+        let value = (u64::from(self.next_u32()) * span) >> 32;
+
+        (min as i64 + value as i64) as i32
+    }
+
+    /// Returns a random float between `min` (inclusive) and `max` (exclusive).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `min` is not less than `max`.
+    pub fn range_f32(&mut self, min: f32, max: f32) -> f32 {
+        assert!(min < max, "min must be less than max");
+
+        scale_to_range(self.f32(), min, max)
+    }
+
+    /// Returns a random float between `0.0` (inclusive) and `1.0` (exclusive).
+    pub fn f32(&mut self) -> f32 {
+        // Only the top 24 bits are used, as that is all that an `f32` can represent exactly.
+        (self.next_u32() >> 8) as f32 / (1u32 << 24) as f32
+    }
+
+    /// Returns `true` with the given probability, which should be between `0.0`
+    /// (never) and `1.0` (always).
+    pub fn bool(&mut self, probability: f32) -> bool {
+        self.f32() < probability
+    }
+
+    /// Returns a reference to a random element of the slice, or `None` if it is empty.
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        if items.is_empty() {
+            return None;
+        }
+
+        let span = items.len() as u64;
+        let index = (u64::from(self.next_u32()) * span) >> 32;
+
+        items.get(index as usize)
+    }
+}
+
+/// Maps a value between `0.0` (inclusive) and `1.0` (exclusive) to the range between `min`
+/// (inclusive) and `max` (exclusive).
+fn scale_to_range(t: f32, min: f32, max: f32) -> f32 {
+    let value = min + (max - min) * t;
+
+    // Rounding can push values that are just below `max` up to `max` itself, so they
+    // are moved down to the closest float below it instead.
+    if value < max {
+        value
+    } else if max > 0.0 {
+        f32::from_bits(max.to_bits() - 1)
+    } else if max < 0.0 {
+        f32::from_bits(max.to_bits() + 1)
+    } else {
+        -f32::from_bits(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{scale_to_range, Random};
+
+    #[test]
+    fn same_seed_gives_same_sequence() {
+        let mut a = Random::new(42);
+        let mut b = Random::new(42);
+        let mut c = Random::new(43);
+
+        let seq_a: Vec<u32> = (0..16).map(|_| a.next_u32()).collect();
+        let seq_b: Vec<u32> = (0..16).map(|_| b.next_u32()).collect();
+        let seq_c: Vec<u32> = (0..16).map(|_| c.next_u32()).collect();
+
+        assert_eq!(seq_a, seq_b);
+        assert_ne!(seq_a, seq_c);
+    }
+
+    #[test]
+    fn values_stay_in_range() {
+        let mut random = Random::new(0);
+
+        for _ in 0..1000 {
+            let i = random.range(-5, 5);
+            assert!((-5..5).contains(&i));
+
+            let f = random.range_f32(2.0, 3.0);
+            assert!((2.0..3.0).contains(&f));
+        }
+
+        assert_eq!(i32::MIN, Random::new(0).range(i32::MIN, i32::MIN + 1));
+        assert_eq!(None, random.choose::<u8>(&[]));
+        assert!(!random.bool(0.0));
+        assert!(random.bool(1.0));
+    }
+
+    #[test]
+    fn float_range_excludes_max() {
+        // The largest value that `Random::f32` can return.
+        let t = 1.0 - 1.0 / (1u32 << 24) as f32;
+
+        // Without clamping, these would round up to `max`.
+        for &(min, max) in &[(16_777_216.0, 16_777_218.0), (-16_777_218.0, -16_777_216.0)] {
+            let value = scale_to_range(t, min, max);
+            assert!(value >= min && value < max);
+        }
+
+        assert!(scale_to_range(t, -1.0, 0.0) < 0.0);
+        assert_eq!(2.5, scale_to_range(0.5, 2.0, 3.0));
+    }
+}
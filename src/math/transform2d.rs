@@ -0,0 +1,145 @@
+use super::{Mat4, Vec2, Vec3};
+
+/// A 2D transformation, made up of a position, a rotation and a scale.
+///
+/// This provides a single representation of where an object is in the world, which can be
+/// stored on your entities, interpolated between (e.g. for tweening), and then converted into a
+/// matrix or a [`DrawParams`](crate::graphics::DrawParams) when it comes time to render.
+///
+/// The scale is applied first, then the rotation, then the position - the same order
+/// that is used by `DrawParams`.
+///
+/// # Serde
+///
+/// Serialization and deserialization of this type (via [Serde](https://serde.rs/))
+/// can be enabled via the `serde_support` feature.
+///
+/// When deserializing, any fields that are missing will be set to their default values.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde_support",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct Transform2D {
+    /// The position of the transform. Defaults to `(0.0, 0.0)`.
+    pub position: Vec2<f32>,
+
+    /// The rotation of the transform, in radians. Defaults to `0.0`.
+    pub rotation: f32,
+
+    /// The scale of the transform. Defaults to `(1.0, 1.0)`.
+    pub scale: Vec2<f32>,
+}
+
+impl Transform2D {
+    /// Creates a new transform.
+    pub fn new(position: Vec2<f32>, rotation: f32, scale: Vec2<f32>) -> Transform2D {
+        Transform2D {
+            position,
+            rotation,
+            scale,
+        }
+    }
+
+    /// Creates a new transformation matrix equivalent to this transform.
+    pub fn to_matrix(&self) -> Mat4<f32> {
+        let mut matrix = Mat4::scaling_3d(Vec3::from(self.scale));
+        matrix.rotate_z(self.rotation);
+        matrix.translate_2d(self.position);
+        matrix
+    }
+
+    /// Applies the transform to a point.
+    pub fn transform_point(&self, point: Vec2<f32>) -> Vec2<f32> {
+        let scaled = point * self.scale;
+        let (sin, cos) = self.rotation.sin_cos();
+
+        Vec2::new(
+            scaled.x * cos - scaled.y * sin,
+            scaled.x * sin + scaled.y * cos,
+        ) + self.position
+    }
+
+    /// Applies the inverse of the transform to a point, mapping it back into the
+    /// transform's local co-ordinate space.
+    ///
+    /// Returns `None` if the transform cannot be inverted (i.e. if the scale is zero
+    /// on either axis).
+    pub fn inverse_transform_point(&self, point: Vec2<f32>) -> Option<Vec2<f32>> {
+        if self.scale.x == 0.0 || self.scale.y == 0.0 {
+            return None;
+        }
+
+        let offset = point - self.position;
+        let (sin, cos) = self.rotation.sin_cos();
+
+        let unrotated = Vec2::new(
+            offset.x * cos + offset.y * sin,
+            offset.y * cos - offset.x * sin,
+        );
+
+        Some(unrotated / self.scale)
+    }
+
+    /// Linearly interpolates between two transforms.
+    ///
+    /// The rotation is interpolated directly, rather than along the shortest path - for
+    /// example, blending between `0.0` and `2π` will perform a full turn.
+    pub fn lerp(self, other: Transform2D, amount: f32) -> Transform2D {
+        Transform2D {
+            position: Vec2::lerp_unclamped(self.position, other.position, amount),
+            rotation: self.rotation + (other.rotation - self.rotation) * amount,
+            scale: Vec2::lerp_unclamped(self.scale, other.scale, amount),
+        }
+    }
+}
+
+impl Default for Transform2D {
+    fn default() -> Transform2D {
+        Transform2D {
+            position: Vec2::zero(),
+            rotation: 0.0,
+            scale: Vec2::one(),
+        }
+    }
+}
+
+impl From<Transform2D> for Mat4<f32> {
+    fn from(transform: Transform2D) -> Mat4<f32> {
+        transform.to_matrix()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Transform2D;
+    use crate::math::{Vec2, Vec4};
+
+    #[test]
+    fn transform_point_matches_matrix() {
+        let transform = Transform2D::new(Vec2::new(100.0, 50.0), 0.5, Vec2::new(2.0, 3.0));
+
+        let point = Vec2::new(3.0, 12.0);
+        let expected = transform.to_matrix() * Vec4::new(point.x, point.y, 0.0, 1.0);
+        let result = transform.transform_point(point);
+
+        assert!((result - expected.xy()).magnitude() < 0.001);
+
+        let inverse = transform.inverse_transform_point(result).unwrap();
+
+        assert!((inverse - point).magnitude() < 0.001);
+    }
+
+    #[test]
+    fn lerp_blends_components() {
+        let a = Transform2D::default();
+        let b = Transform2D::new(Vec2::new(10.0, 20.0), 1.0, Vec2::new(3.0, 3.0));
+
+        let blended = a.lerp(b, 0.5);
+
+        assert_eq!(Vec2::new(5.0, 10.0), blended.position);
+        assert_eq!(0.5, blended.rotation);
+        assert_eq!(Vec2::new(2.0, 2.0), blended.scale);
+    }
+}
@@ -2,11 +2,14 @@ use std::cell::Cell;
 use std::mem;
 use std::rc::Rc;
 
-use glow::{Context as GlowContext, HasContext, PixelUnpackData};
+use glow::{Context as GlowContext, HasContext, PixelPackData, PixelUnpackData};
 
 use crate::error::{Result, TetraError};
-use crate::graphics::mesh::{BufferUsage, VertexWinding};
-use crate::graphics::{BlendAlphaMode, BlendMode, FilterMode};
+use crate::graphics::mesh::{BufferUsage, CullMode, VertexFormat, VertexLayout, VertexWinding};
+use crate::graphics::{
+    BlendAlphaMode, BlendMode, FilterMode, ShaderStage, StencilAction, StencilState, StencilTest,
+    TextureFormat, WrapMode,
+};
 use crate::math::{Mat2, Mat3, Mat4, Vec2, Vec3, Vec4};
 
 /// Utility function for calculating offsets/sizes.
@@ -14,12 +17,73 @@ fn size<T>(elements: usize) -> i32 {
     (elements * mem::size_of::<T>()) as i32
 }
 
+/// Appends the offending line of source code to each message in a shader info log.
+///
+/// The format of the info log is not standardized, but most drivers start each message
+/// with a reference to the source string and line number, in one of the following forms:
+/// `0(12)` (Nvidia/AMD), `0:12(5)` (Mesa) or `ERROR: 0:12:` (Intel/Apple).
+fn annotate_shader_log(log: &str, source: &str) -> String {
+    let source_lines: Vec<&str> = source.lines().collect();
+    let mut output = String::with_capacity(log.len());
+
+    for message in log.lines() {
+        output.push_str(message);
+        output.push('\n');
+
+        let line = parse_shader_log_line(message)
+            .and_then(|n| n.checked_sub(1))
+            .and_then(|i| source_lines.get(i).map(|l| (i + 1, l)));
+
+        if let Some((number, line)) = line {
+            output.push_str(&format!("    {} | {}\n", number, line.trim_end()));
+        }
+    }
+
+    output
+}
+
+/// Parses the line number from the start of a shader info log message.
+///
+/// The message must begin with `<string>(<line>)` or `<string>:<line>`, optionally preceded
+/// by a severity (e.g. `ERROR: `). Anything after the line number (such as Mesa's column
+/// number) is ignored.
+fn parse_shader_log_line(message: &str) -> Option<usize> {
+    let message = message.trim_start();
+
+    let message = ["ERROR:", "WARNING:"]
+        .iter()
+        .find_map(|severity| message.strip_prefix(severity))
+        .map(str::trim_start)
+        .unwrap_or(message);
+
+    let rest = message.trim_start_matches(|c: char| c.is_ascii_digit());
+
+    if rest.len() == message.len() {
+        return None;
+    }
+
+    let digits = if let Some(rest) = rest.strip_prefix('(') {
+        &rest[..rest.find(')')?]
+    } else if let Some(rest) = rest.strip_prefix(':') {
+        let end = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+
+        &rest[..end]
+    } else {
+        return None;
+    };
+
+    digits.parse().ok()
+}
+
 type BufferId = <GlowContext as HasContext>::Buffer;
 type ProgramId = <GlowContext as HasContext>::Program;
 type TextureId = <GlowContext as HasContext>::Texture;
 type FramebufferId = <GlowContext as HasContext>::Framebuffer;
 type RenderbufferId = <GlowContext as HasContext>::Renderbuffer;
 type VertexArrayId = <GlowContext as HasContext>::VertexArray;
+type FenceId = <GlowContext as HasContext>::Fence;
 
 pub type UniformLocation = <GlowContext as HasContext>::UniformLocation;
 
@@ -35,6 +99,16 @@ struct GraphicsState {
     current_draw_framebuffer: Cell<Option<FramebufferId>>,
     current_renderbuffer: Cell<Option<RenderbufferId>>,
     current_vertex_array: Cell<Option<VertexArrayId>>,
+    enabled_vertex_attributes: Cell<u32>,
+
+    max_anisotropy: Option<f32>,
+
+    texture_memory: Cell<usize>,
+    canvas_memory: Cell<usize>,
+
+    /// Set when the device is dropped. After this point, the OpenGL context may no longer
+    /// exist, so any resources that outlive the device must not make further GL calls.
+    destroyed: Cell<bool>,
 }
 
 pub struct GraphicsDevice {
@@ -42,11 +116,24 @@ pub struct GraphicsDevice {
 }
 
 impl GraphicsDevice {
-    pub fn new(gl: GlowContext) -> Result<GraphicsDevice> {
+    pub fn new(gl: GlowContext, srgb: bool) -> Result<GraphicsDevice> {
         unsafe {
             gl.enable(glow::CULL_FACE);
             gl.enable(glow::BLEND);
 
+            // When this is enabled, writes to sRGB render targets (including an sRGB
+            // backbuffer) will be re-encoded from linear space.
+            if srgb {
+                gl.enable(glow::FRAMEBUFFER_SRGB);
+            }
+
+            // Pixel data is always passed to and from OpenGL as tightly packed rows.
+            // The default alignment of 4 happens to be correct for RGBA8, but setting
+            // this explicitly means that uploads and readbacks won't shear if a format
+            // with a smaller pixel size is ever added.
+            gl.pixel_store_i32(glow::UNPACK_ALIGNMENT, 1);
+            gl.pixel_store_i32(glow::PACK_ALIGNMENT, 1);
+
             // This default might want to change if we introduce
             // custom blending modes.
             gl.blend_func_separate(
@@ -70,6 +157,21 @@ impl GraphicsDevice {
             let texture_units =
                 gl.get_parameter_i32(glow::MAX_COMBINED_TEXTURE_IMAGE_UNITS) as usize;
 
+            // Anisotropic filtering was only made core in GL 4.6, so we have to check
+            // for the extension.
+            let supports_anisotropy = (0..gl.get_parameter_i32(glow::NUM_EXTENSIONS)).any(|i| {
+                let extension = gl.get_parameter_indexed_string(glow::EXTENSIONS, i as u32);
+
+                extension == "GL_EXT_texture_filter_anisotropic"
+                    || extension == "GL_ARB_texture_filter_anisotropic"
+            });
+
+            let max_anisotropy = if supports_anisotropy {
+                Some(gl.get_parameter_i32(glow::MAX_TEXTURE_MAX_ANISOTROPY) as f32)
+            } else {
+                None
+            };
+
             let state = GraphicsState {
                 gl,
 
@@ -81,6 +183,14 @@ impl GraphicsDevice {
                 current_draw_framebuffer: Cell::new(None),
                 current_renderbuffer: Cell::new(None),
                 current_vertex_array: Cell::new(Some(current_vertex_array)),
+                enabled_vertex_attributes: Cell::new(0),
+
+                max_anisotropy,
+
+                texture_memory: Cell::new(0),
+                canvas_memory: Cell::new(0),
+
+                destroyed: Cell::new(false),
             };
 
             Ok(GraphicsDevice {
@@ -109,6 +219,31 @@ impl GraphicsDevice {
         }
     }
 
+    pub fn get_max_anisotropy(&self) -> Option<f32> {
+        self.state.max_anisotropy
+    }
+
+    pub fn get_texture_memory_usage(&self) -> usize {
+        self.state.texture_memory.get()
+    }
+
+    pub fn get_canvas_memory_usage(&self) -> usize {
+        self.state.canvas_memory.get()
+    }
+
+    /// Moves a texture's memory usage from the texture total to the canvas total.
+    pub fn mark_texture_as_canvas(&mut self, texture: &RawTexture) {
+        if !texture.canvas.replace(true) {
+            let size = texture.memory_usage();
+
+            let textures = &self.state.texture_memory;
+            textures.set(textures.get().saturating_sub(size));
+
+            let canvases = &self.state.canvas_memory;
+            canvases.set(canvases.get() + size);
+        }
+    }
+
     pub fn clear(&mut self, r: f32, g: f32, b: f32, a: f32) {
         unsafe {
             self.state.gl.clear_color(r, g, b, a);
@@ -122,12 +257,18 @@ impl GraphicsDevice {
         }
     }
 
-    pub fn cull_face(&mut self, cull_face: bool) {
+    pub fn cull_face(&mut self, cull_mode: CullMode) {
         unsafe {
-            if cull_face {
-                self.state.gl.enable(glow::CULL_FACE);
-            } else {
-                self.state.gl.disable(glow::CULL_FACE);
+            match cull_mode {
+                CullMode::None => self.state.gl.disable(glow::CULL_FACE),
+                CullMode::Back => {
+                    self.state.gl.enable(glow::CULL_FACE);
+                    self.state.gl.cull_face(glow::BACK);
+                }
+                CullMode::Front => {
+                    self.state.gl.enable(glow::CULL_FACE);
+                    self.state.gl.cull_face(glow::FRONT);
+                }
             }
         }
     }
@@ -137,6 +278,25 @@ impl GraphicsDevice {
         count: usize,
         stride: usize,
         usage: BufferUsage,
+    ) -> Result<RawVertexBuffer> {
+        self.new_vertex_buffer_ex(count, stride, None, usage)
+    }
+
+    pub fn new_vertex_buffer_with_layout(
+        &mut self,
+        count: usize,
+        layout: VertexLayout,
+        usage: BufferUsage,
+    ) -> Result<RawVertexBuffer> {
+        self.new_vertex_buffer_ex(count, layout.stride(), Some(layout), usage)
+    }
+
+    fn new_vertex_buffer_ex(
+        &mut self,
+        count: usize,
+        stride: usize,
+        layout: Option<VertexLayout>,
+        usage: BufferUsage,
     ) -> Result<RawVertexBuffer> {
         unsafe {
             let id = self
@@ -150,6 +310,7 @@ impl GraphicsDevice {
                 id,
                 count,
                 stride,
+                layout,
             };
 
             self.bind_vertex_buffer(Some(&buffer));
@@ -188,6 +349,22 @@ impl GraphicsDevice {
         }
     }
 
+    pub fn get_vertex_buffer_data(&mut self, buffer: &RawVertexBuffer) -> Vec<f32> {
+        let mut data = vec![0.0; buffer.size()];
+
+        self.bind_vertex_buffer(Some(buffer));
+
+        unsafe {
+            self.state.gl.get_buffer_sub_data(
+                glow::ARRAY_BUFFER,
+                0,
+                bytemuck::cast_slice_mut(&mut data),
+            );
+        }
+
+        data
+    }
+
     pub fn new_index_buffer(&mut self, count: usize, usage: BufferUsage) -> Result<RawIndexBuffer> {
         unsafe {
             let id = self
@@ -233,6 +410,22 @@ impl GraphicsDevice {
         }
     }
 
+    pub fn get_index_buffer_data(&mut self, buffer: &RawIndexBuffer) -> Vec<u32> {
+        let mut data = vec![0; buffer.count()];
+
+        self.bind_index_buffer(Some(buffer));
+
+        unsafe {
+            self.state.gl.get_buffer_sub_data(
+                glow::ELEMENT_ARRAY_BUFFER,
+                0,
+                bytemuck::cast_slice_mut(&mut data),
+            );
+        }
+
+        data
+    }
+
     pub fn new_program(
         &mut self,
         vertex_shader: &str,
@@ -263,9 +456,15 @@ impl GraphicsDevice {
             self.state.gl.attach_shader(program_id, vertex_id);
 
             if !self.state.gl.get_shader_compile_status(vertex_id) {
-                return Err(TetraError::InvalidShader(
-                    self.state.gl.get_shader_info_log(vertex_id),
-                ));
+                let log = self.state.gl.get_shader_info_log(vertex_id);
+
+                self.state.gl.delete_shader(vertex_id);
+                self.state.gl.delete_program(program_id);
+
+                return Err(TetraError::ShaderCompilation {
+                    stage: ShaderStage::Vertex,
+                    log: annotate_shader_log(&log, vertex_shader),
+                });
             }
 
             let fragment_id = self
@@ -279,17 +478,31 @@ impl GraphicsDevice {
             self.state.gl.attach_shader(program_id, fragment_id);
 
             if !self.state.gl.get_shader_compile_status(fragment_id) {
-                return Err(TetraError::InvalidShader(
-                    self.state.gl.get_shader_info_log(fragment_id),
-                ));
+                let log = self.state.gl.get_shader_info_log(fragment_id);
+
+                self.state.gl.delete_shader(vertex_id);
+                self.state.gl.delete_shader(fragment_id);
+                self.state.gl.delete_program(program_id);
+
+                return Err(TetraError::ShaderCompilation {
+                    stage: ShaderStage::Fragment,
+                    log: annotate_shader_log(&log, fragment_shader),
+                });
             }
 
             self.state.gl.link_program(program_id);
 
             if !self.state.gl.get_program_link_status(program_id) {
-                return Err(TetraError::InvalidShader(
-                    self.state.gl.get_program_info_log(program_id),
-                ));
+                let log = self.state.gl.get_program_info_log(program_id);
+
+                self.state.gl.delete_shader(vertex_id);
+                self.state.gl.delete_shader(fragment_id);
+                self.state.gl.delete_program(program_id);
+
+                return Err(TetraError::ShaderCompilation {
+                    stage: ShaderStage::Linking,
+                    log,
+                });
             }
 
             self.state.gl.delete_shader(vertex_id);
@@ -462,7 +675,42 @@ impl GraphicsDevice {
         }
     }
 
-    pub fn new_texture(&mut self, width: i32, height: i32) -> Result<RawTexture> {
+    pub fn set_stencil_state(&mut self, stencil_state: StencilState) {
+        unsafe {
+            if stencil_state.enabled {
+                self.state.gl.enable(glow::STENCIL_TEST);
+            } else {
+                self.state.gl.disable(glow::STENCIL_TEST);
+            }
+
+            self.state.gl.stencil_func(
+                stencil_state.test.into(),
+                stencil_state.reference_value.into(),
+                stencil_state.read_mask.into(),
+            );
+
+            self.state
+                .gl
+                .stencil_op(glow::KEEP, glow::KEEP, stencil_state.action.into());
+
+            self.state.gl.stencil_mask(stencil_state.write_mask.into());
+        }
+    }
+
+    pub fn clear_stencil(&mut self, value: u8) {
+        unsafe {
+            self.state.gl.stencil_mask(0xFF);
+            self.state.gl.clear_stencil(value.into());
+            self.state.gl.clear(glow::STENCIL_BUFFER_BIT);
+        }
+    }
+
+    pub fn new_texture(
+        &mut self,
+        width: i32,
+        height: i32,
+        format: TextureFormat,
+    ) -> Result<RawTexture> {
         // TODO: I don't think we need mipmaps?
         unsafe {
             let id = self
@@ -475,10 +723,17 @@ impl GraphicsDevice {
                 state: Rc::clone(&self.state),
 
                 id,
+                target: glow::TEXTURE_2D,
                 width,
                 height,
+                depth: 1,
+
+                canvas: Cell::new(false),
             };
 
+            let memory = &self.state.texture_memory;
+            memory.set(memory.get() + texture.memory_usage());
+
             self.bind_default_texture(Some(&texture));
 
             self.state.gl.tex_parameter_i32(
@@ -504,7 +759,7 @@ impl GraphicsDevice {
             self.state.gl.tex_image_2d(
                 glow::TEXTURE_2D,
                 0,
-                glow::RGBA as i32, // love 2 deal with legacy apis
+                format.into(),
                 width,
                 height,
                 0,
@@ -557,24 +812,276 @@ impl GraphicsDevice {
         Ok(())
     }
 
+    pub fn get_texture_data(&mut self, texture: &RawTexture) -> Vec<u8> {
+        let mut buffer = vec![0; (texture.width * texture.height * 4) as usize];
+
+        self.bind_default_texture(Some(texture));
+
+        unsafe {
+            self.state.gl.get_tex_image(
+                glow::TEXTURE_2D,
+                0,
+                glow::RGBA,
+                glow::UNSIGNED_BYTE,
+                PixelPackData::Slice(&mut buffer),
+            );
+        }
+
+        buffer
+    }
+
+    pub fn new_texture_array(
+        &mut self,
+        width: i32,
+        height: i32,
+        layers: i32,
+        format: TextureFormat,
+    ) -> Result<RawTexture> {
+        self.new_volume_texture(glow::TEXTURE_2D_ARRAY, width, height, layers, format)
+    }
+
+    pub fn new_texture_3d(
+        &mut self,
+        width: i32,
+        height: i32,
+        depth: i32,
+        format: TextureFormat,
+    ) -> Result<RawTexture> {
+        self.new_volume_texture(glow::TEXTURE_3D, width, height, depth, format)
+    }
+
+    /// Creates a texture with multiple layers of storage, which is shared between
+    /// texture arrays and 3D textures.
+    fn new_volume_texture(
+        &mut self,
+        target: u32,
+        width: i32,
+        height: i32,
+        depth: i32,
+        format: TextureFormat,
+    ) -> Result<RawTexture> {
+        unsafe {
+            let id = self
+                .state
+                .gl
+                .create_texture()
+                .map_err(TetraError::PlatformError)?;
+
+            let texture = RawTexture {
+                state: Rc::clone(&self.state),
+
+                id,
+                target,
+                width,
+                height,
+                depth,
+
+                canvas: Cell::new(false),
+            };
+
+            let memory = &self.state.texture_memory;
+            memory.set(memory.get() + texture.memory_usage());
+
+            self.bind_default_texture(Some(&texture));
+
+            self.state.gl.tex_parameter_i32(
+                target,
+                glow::TEXTURE_WRAP_S,
+                glow::CLAMP_TO_EDGE as i32,
+            );
+
+            self.state.gl.tex_parameter_i32(
+                target,
+                glow::TEXTURE_WRAP_T,
+                glow::CLAMP_TO_EDGE as i32,
+            );
+
+            self.state.gl.tex_parameter_i32(
+                target,
+                glow::TEXTURE_WRAP_R,
+                glow::CLAMP_TO_EDGE as i32,
+            );
+
+            self.state
+                .gl
+                .tex_parameter_i32(target, glow::TEXTURE_BASE_LEVEL, 0);
+
+            self.state
+                .gl
+                .tex_parameter_i32(target, glow::TEXTURE_MAX_LEVEL, 0);
+
+            self.state.gl.tex_image_3d(
+                target,
+                0,
+                format.into(),
+                width,
+                height,
+                depth,
+                0,
+                glow::RGBA,
+                glow::UNSIGNED_BYTE,
+                None,
+            );
+
+            Ok(texture)
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn set_texture_array_data(
+        &mut self,
+        texture: &RawTexture,
+        data: &[u8],
+        layer: i32,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+    ) -> Result {
+        assert!(
+            x >= 0 && y >= 0 && x + width <= texture.width && y + height <= texture.height,
+            "tried to write outside of texture bounds"
+        );
+
+        assert!(
+            layer >= 0 && layer < texture.depth,
+            "tried to write to a texture array layer that does not exist"
+        );
+
+        let expected = (width * height * 4) as usize;
+        let actual = data.len();
+
+        if expected > actual {
+            return Err(TetraError::NotEnoughData { expected, actual });
+        }
+
+        self.bind_default_texture(Some(texture));
+
+        unsafe {
+            self.state.gl.tex_sub_image_3d(
+                glow::TEXTURE_2D_ARRAY,
+                0,
+                x,
+                y,
+                layer,
+                width,
+                height,
+                1,
+                glow::RGBA,
+                glow::UNSIGNED_BYTE,
+                PixelUnpackData::Slice(data),
+            )
+        }
+
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn set_texture_3d_data(
+        &mut self,
+        texture: &RawTexture,
+        data: &[u8],
+        x: i32,
+        y: i32,
+        z: i32,
+        width: i32,
+        height: i32,
+        depth: i32,
+    ) -> Result {
+        assert!(
+            x >= 0
+                && y >= 0
+                && z >= 0
+                && x + width <= texture.width
+                && y + height <= texture.height
+                && z + depth <= texture.depth,
+            "tried to write outside of texture bounds"
+        );
+
+        let expected = (width * height * depth * 4) as usize;
+        let actual = data.len();
+
+        if expected > actual {
+            return Err(TetraError::NotEnoughData { expected, actual });
+        }
+
+        self.bind_default_texture(Some(texture));
+
+        unsafe {
+            self.state.gl.tex_sub_image_3d(
+                glow::TEXTURE_3D,
+                0,
+                x,
+                y,
+                z,
+                width,
+                height,
+                depth,
+                glow::RGBA,
+                glow::UNSIGNED_BYTE,
+                PixelUnpackData::Slice(data),
+            )
+        }
+
+        Ok(())
+    }
+
     pub fn set_texture_filter_mode(&mut self, texture: &RawTexture, filter_mode: FilterMode) {
         self.bind_default_texture(Some(texture));
 
         unsafe {
             self.state.gl.tex_parameter_i32(
-                glow::TEXTURE_2D,
+                texture.target,
                 glow::TEXTURE_MIN_FILTER,
                 filter_mode.into(),
             );
 
             self.state.gl.tex_parameter_i32(
-                glow::TEXTURE_2D,
+                texture.target,
                 glow::TEXTURE_MAG_FILTER,
                 filter_mode.into(),
             );
         }
     }
 
+    pub fn set_texture_wrap_mode(&mut self, texture: &RawTexture, wrap_mode: WrapMode) {
+        self.bind_default_texture(Some(texture));
+
+        unsafe {
+            self.state
+                .gl
+                .tex_parameter_i32(texture.target, glow::TEXTURE_WRAP_S, wrap_mode.into());
+
+            self.state
+                .gl
+                .tex_parameter_i32(texture.target, glow::TEXTURE_WRAP_T, wrap_mode.into());
+
+            if texture.target == glow::TEXTURE_3D {
+                self.state.gl.tex_parameter_i32(
+                    texture.target,
+                    glow::TEXTURE_WRAP_R,
+                    wrap_mode.into(),
+                );
+            }
+        }
+    }
+
+    pub fn set_texture_anisotropy(&mut self, texture: &RawTexture, anisotropy: f32) {
+        if self.state.max_anisotropy.is_none() {
+            return;
+        }
+
+        self.bind_default_texture(Some(texture));
+
+        unsafe {
+            self.state.gl.tex_parameter_f32(
+                texture.target,
+                glow::TEXTURE_MAX_ANISOTROPY,
+                anisotropy,
+            );
+        }
+    }
+
     pub fn new_framebuffer(&mut self) -> Result<RawFramebuffer> {
         unsafe {
             let id = self
@@ -626,6 +1133,68 @@ impl GraphicsDevice {
         }
     }
 
+    pub fn attach_texture_layer_to_framebuffer(
+        &mut self,
+        framebuffer: &RawFramebuffer,
+        texture: &RawTexture,
+        layer: i32,
+        rebind_previous: bool,
+    ) {
+        unsafe {
+            let previous_read = self.state.current_read_framebuffer.get();
+            let previous_draw = self.state.current_draw_framebuffer.get();
+
+            self.bind_framebuffer(Some(framebuffer));
+
+            self.state.gl.framebuffer_texture_layer(
+                glow::FRAMEBUFFER,
+                glow::COLOR_ATTACHMENT0,
+                Some(texture.id),
+                0,
+                layer,
+            );
+
+            if rebind_previous {
+                self.state
+                    .gl
+                    .bind_framebuffer(glow::READ_FRAMEBUFFER, previous_read);
+                self.state.current_read_framebuffer.set(previous_read);
+
+                self.state
+                    .gl
+                    .bind_framebuffer(glow::DRAW_FRAMEBUFFER, previous_draw);
+                self.state.current_draw_framebuffer.set(previous_draw);
+            }
+        }
+    }
+
+    pub fn clear_framebuffer(
+        &mut self,
+        framebuffer: &RawFramebuffer,
+        r: f32,
+        g: f32,
+        b: f32,
+        a: f32,
+    ) {
+        unsafe {
+            let previous_read = self.state.current_read_framebuffer.get();
+            let previous_draw = self.state.current_draw_framebuffer.get();
+
+            self.bind_framebuffer(Some(framebuffer));
+            self.clear(r, g, b, a);
+
+            self.state
+                .gl
+                .bind_framebuffer(glow::READ_FRAMEBUFFER, previous_read);
+            self.state.current_read_framebuffer.set(previous_read);
+
+            self.state
+                .gl
+                .bind_framebuffer(glow::DRAW_FRAMEBUFFER, previous_draw);
+            self.state.current_draw_framebuffer.set(previous_draw);
+        }
+    }
+
     pub fn attach_renderbuffer_to_framebuffer(
         &mut self,
         framebuffer: &RawFramebuffer,
@@ -690,6 +1259,7 @@ impl GraphicsDevice {
         width: i32,
         height: i32,
         samples: u8,
+        format: TextureFormat,
     ) -> Result<RawRenderbuffer> {
         unsafe {
             let id = self
@@ -701,14 +1271,21 @@ impl GraphicsDevice {
             let renderbuffer = RawRenderbuffer {
                 state: Rc::clone(&self.state),
                 id,
+
+                // Each sample is stored separately, so a multisampled buffer takes up
+                // `samples` times as much memory as a normal one.
+                memory_usage: (width * height * 4) as usize * usize::from(samples.max(1)),
             };
 
+            let memory = &self.state.canvas_memory;
+            memory.set(memory.get() + renderbuffer.memory_usage);
+
             self.bind_renderbuffer(Some(&renderbuffer));
 
             self.state.gl.renderbuffer_storage_multisample(
                 glow::RENDERBUFFER,
                 samples.into(),
-                glow::RGBA,
+                i32::from(format) as u32,
                 width,
                 height,
             );
@@ -717,6 +1294,65 @@ impl GraphicsDevice {
         }
     }
 
+    pub fn read_window_depth(&mut self, x: i32, y: i32) -> f32 {
+        let mut data = [0; 4];
+        self.read_window_pixel(x, y, glow::DEPTH_COMPONENT, glow::FLOAT, &mut data);
+        f32::from_ne_bytes(data)
+    }
+
+    pub fn read_window_stencil(&mut self, x: i32, y: i32) -> u8 {
+        let mut data = [0; 1];
+        self.read_window_pixel(x, y, glow::STENCIL_INDEX, glow::UNSIGNED_BYTE, &mut data);
+        data[0]
+    }
+
+    fn read_window_pixel(&mut self, x: i32, y: i32, format: u32, ty: u32, data: &mut [u8]) {
+        unsafe {
+            let previous_read = self.state.current_read_framebuffer.get();
+
+            self.state.gl.bind_framebuffer(glow::READ_FRAMEBUFFER, None);
+
+            self.state
+                .gl
+                .read_pixels(x, y, 1, 1, format, ty, PixelPackData::Slice(data));
+
+            self.state
+                .gl
+                .bind_framebuffer(glow::READ_FRAMEBUFFER, previous_read);
+        }
+    }
+
+    pub fn finish(&mut self) {
+        unsafe {
+            self.state.gl.finish();
+        }
+    }
+
+    pub fn new_fence(&mut self) -> Result<RawFence> {
+        unsafe {
+            let id = self
+                .state
+                .gl
+                .fence_sync(glow::SYNC_GPU_COMMANDS_COMPLETE, 0)
+                .map_err(TetraError::PlatformError)?;
+
+            // Without a flush, the fence might never be submitted to the GPU, in which case
+            // it would never become signaled.
+            self.state.gl.flush();
+
+            let fence = RawFence {
+                state: Rc::clone(&self.state),
+                id,
+            };
+
+            Ok(fence)
+        }
+    }
+
+    pub fn is_fence_signaled(&mut self, fence: &RawFence) -> bool {
+        unsafe { self.state.gl.get_sync_status(fence.id) == glow::SIGNALED }
+    }
+
     pub fn viewport(&mut self, x: i32, y: i32, width: i32, height: i32) {
         unsafe {
             self.state.gl.viewport(x, y, width, height);
@@ -734,6 +1370,7 @@ impl GraphicsDevice {
         self.bind_vertex_buffer(Some(vertex_buffer));
         self.bind_default_texture(Some(texture));
         self.bind_program(Some(program));
+        self.apply_vertex_layout(vertex_buffer, program);
 
         let max_count = vertex_buffer.size() / vertex_buffer.stride();
 
@@ -760,6 +1397,7 @@ impl GraphicsDevice {
         self.bind_index_buffer(Some(index_buffer));
         self.bind_default_texture(Some(texture));
         self.bind_program(Some(program));
+        self.apply_vertex_layout(vertex_buffer, program);
 
         let max_count = index_buffer.count();
 
@@ -783,10 +1421,10 @@ impl GraphicsDevice {
             if self.state.current_vertex_buffer.get() != id {
                 self.state.gl.bind_buffer(glow::ARRAY_BUFFER, id);
 
-                // TODO: This only works because we don't let the user set custom
-                // attribute bindings - will need a rethink at that point!
+                // Buffers with a custom layout have their attributes bound at draw time,
+                // as the locations depend on the shader being used.
                 match buffer {
-                    Some(b) => {
+                    Some(b) if b.layout.is_none() => {
                         self.state.gl.vertex_attrib_pointer_f32(
                             0,
                             2,
@@ -814,14 +1452,11 @@ impl GraphicsDevice {
                             size::<f32>(4),
                         );
 
-                        self.state.gl.enable_vertex_attrib_array(0);
-                        self.state.gl.enable_vertex_attrib_array(1);
-                        self.state.gl.enable_vertex_attrib_array(2);
+                        self.set_enabled_vertex_attributes(0b111);
                     }
+                    Some(_) => {}
                     None => {
-                        self.state.gl.disable_vertex_attrib_array(0);
-                        self.state.gl.disable_vertex_attrib_array(1);
-                        self.state.gl.disable_vertex_attrib_array(2);
+                        self.set_enabled_vertex_attributes(0);
                     }
                 }
 
@@ -830,6 +1465,87 @@ impl GraphicsDevice {
         }
     }
 
+    fn apply_vertex_layout(&mut self, buffer: &RawVertexBuffer, program: &RawProgram) {
+        if let Some(layout) = &buffer.layout {
+            let stride = size::<f32>(layout.stride());
+
+            let mut offset = 0;
+            let mut enabled = 0;
+
+            for attribute in layout.attributes() {
+                let components = attribute.format.components();
+
+                unsafe {
+                    // Attributes that aren't used by the shader are skipped, as they
+                    // may have been optimized out by the driver.
+                    if let Some(location) = self
+                        .state
+                        .gl
+                        .get_attrib_location(program.id, &attribute.name)
+                    {
+                        self.state.gl.vertex_attrib_pointer_f32(
+                            location,
+                            components as i32,
+                            glow::FLOAT,
+                            false,
+                            stride,
+                            size::<f32>(offset),
+                        );
+
+                        enabled |= 1 << location;
+                    }
+                }
+
+                offset += components;
+            }
+
+            self.set_enabled_vertex_attributes(enabled);
+        }
+    }
+
+    fn set_enabled_vertex_attributes(&mut self, enabled: u32) {
+        let previous = self.state.enabled_vertex_attributes.get();
+        let changed = previous ^ enabled;
+
+        for location in 0..32 {
+            if changed & (1 << location) != 0 {
+                unsafe {
+                    if enabled & (1 << location) != 0 {
+                        self.state.gl.enable_vertex_attrib_array(location);
+                    } else {
+                        self.state.gl.disable_vertex_attrib_array(location);
+                    }
+                }
+            }
+        }
+
+        self.state.enabled_vertex_attributes.set(enabled);
+    }
+
+    pub fn get_active_attributes(
+        &mut self,
+        program: &RawProgram,
+    ) -> Vec<(String, Option<VertexFormat>)> {
+        unsafe {
+            let count = self.state.gl.get_active_attributes(program.id);
+
+            (0..count)
+                .filter_map(|i| self.state.gl.get_active_attribute(program.id, i))
+                .map(|a| {
+                    let format = match a.atype {
+                        glow::FLOAT => Some(VertexFormat::Float),
+                        glow::FLOAT_VEC2 => Some(VertexFormat::Vec2),
+                        glow::FLOAT_VEC3 => Some(VertexFormat::Vec3),
+                        glow::FLOAT_VEC4 => Some(VertexFormat::Vec4),
+                        _ => None,
+                    };
+
+                    (a.name, format)
+                })
+                .collect()
+        }
+    }
+
     fn bind_index_buffer(&mut self, buffer: Option<&RawIndexBuffer>) {
         unsafe {
             let id = buffer.map(|x| x.id);
@@ -863,8 +1579,10 @@ impl GraphicsDevice {
                 .ok_or_else(|| TetraError::PlatformError("invalid texture unit".into()))?;
 
             if current.get() != id {
+                let target = texture.map(|x| x.target).unwrap_or(glow::TEXTURE_2D);
+
                 self.state.gl.active_texture(glow::TEXTURE0 + unit);
-                self.state.gl.bind_texture(glow::TEXTURE_2D, id);
+                self.state.gl.bind_texture(target, id);
                 current.set(id);
             }
         }
@@ -935,6 +1653,10 @@ impl Drop for GraphicsDevice {
                 self.state.gl.delete_vertex_array(va);
             }
         }
+
+        // Any resources that are still alive will be cleaned up when the OpenGL context
+        // is destroyed, so they can skip deleting themselves.
+        self.state.destroyed.set(true);
     }
 }
 
@@ -959,6 +1681,17 @@ impl From<VertexWinding> for u32 {
     }
 }
 
+#[doc(hidden)]
+impl From<WrapMode> for i32 {
+    fn from(wrap_mode: WrapMode) -> i32 {
+        match wrap_mode {
+            WrapMode::Clamp => glow::CLAMP_TO_EDGE as i32,
+            WrapMode::Repeat => glow::REPEAT as i32,
+            WrapMode::MirroredRepeat => glow::MIRRORED_REPEAT as i32,
+        }
+    }
+}
+
 #[doc(hidden)]
 impl From<FilterMode> for i32 {
     fn from(filter_mode: FilterMode) -> i32 {
@@ -969,6 +1702,47 @@ impl From<FilterMode> for i32 {
     }
 }
 
+impl From<TextureFormat> for i32 {
+    fn from(format: TextureFormat) -> i32 {
+        match format {
+            TextureFormat::Rgba8 => glow::RGBA8 as i32,
+            TextureFormat::Srgba8 => glow::SRGB8_ALPHA8 as i32,
+        }
+    }
+}
+
+#[doc(hidden)]
+impl From<StencilTest> for u32 {
+    fn from(test: StencilTest) -> u32 {
+        match test {
+            StencilTest::Never => glow::NEVER,
+            StencilTest::LessThan => glow::LESS,
+            StencilTest::LessThanOrEqualTo => glow::LEQUAL,
+            StencilTest::EqualTo => glow::EQUAL,
+            StencilTest::NotEqualTo => glow::NOTEQUAL,
+            StencilTest::GreaterThan => glow::GREATER,
+            StencilTest::GreaterThanOrEqualTo => glow::GEQUAL,
+            StencilTest::Always => glow::ALWAYS,
+        }
+    }
+}
+
+#[doc(hidden)]
+impl From<StencilAction> for u32 {
+    fn from(action: StencilAction) -> u32 {
+        match action {
+            StencilAction::Keep => glow::KEEP,
+            StencilAction::Zero => glow::ZERO,
+            StencilAction::Replace => glow::REPLACE,
+            StencilAction::Increment => glow::INCR,
+            StencilAction::IncrementWrap => glow::INCR_WRAP,
+            StencilAction::Decrement => glow::DECR,
+            StencilAction::DecrementWrap => glow::DECR_WRAP,
+            StencilAction::Invert => glow::INVERT,
+        }
+    }
+}
+
 #[doc(hidden)]
 impl BlendMode {
     pub(crate) fn equation(&self) -> u32 {
@@ -1043,6 +1817,7 @@ pub struct RawVertexBuffer {
 
     count: usize,
     stride: usize,
+    layout: Option<VertexLayout>,
 }
 
 impl RawVertexBuffer {
@@ -1057,10 +1832,18 @@ impl RawVertexBuffer {
     pub fn size(&self) -> usize {
         self.count * self.stride
     }
+
+    pub fn layout(&self) -> Option<&VertexLayout> {
+        self.layout.as_ref()
+    }
 }
 
 impl Drop for RawVertexBuffer {
     fn drop(&mut self) {
+        if self.state.destroyed.get() {
+            return;
+        }
+
         unsafe {
             if self.state.current_vertex_buffer.get() == Some(self.id) {
                 self.state.current_vertex_buffer.set(None);
@@ -1089,6 +1872,10 @@ impl RawIndexBuffer {
 
 impl Drop for RawIndexBuffer {
     fn drop(&mut self) {
+        if self.state.destroyed.get() {
+            return;
+        }
+
         unsafe {
             if self.state.current_index_buffer.get() == Some(self.id) {
                 self.state.current_index_buffer.set(None);
@@ -1109,6 +1896,10 @@ pub struct RawProgram {
 
 impl Drop for RawProgram {
     fn drop(&mut self) {
+        if self.state.destroyed.get() {
+            return;
+        }
+
         unsafe {
             if self.state.current_program.get() == Some(self.id) {
                 self.state.current_program.set(None);
@@ -1125,9 +1916,13 @@ handle_impls!(RawProgram);
 pub struct RawTexture {
     state: Rc<GraphicsState>,
     id: TextureId,
+    target: u32,
 
     width: i32,
     height: i32,
+    depth: i32,
+
+    canvas: Cell<bool>,
 }
 
 impl RawTexture {
@@ -1138,10 +1933,26 @@ impl RawTexture {
     pub fn height(&self) -> i32 {
         self.height
     }
+
+    pub fn depth(&self) -> i32 {
+        self.depth
+    }
+
+    /// An estimate of how much memory the texture uses, in bytes.
+    ///
+    /// All of the supported formats use four bytes per pixel - this will need
+    /// to be updated if that changes.
+    pub fn memory_usage(&self) -> usize {
+        (self.width * self.height * self.depth * 4) as usize
+    }
 }
 
 impl Drop for RawTexture {
     fn drop(&mut self) {
+        if self.state.destroyed.get() {
+            return;
+        }
+
         unsafe {
             for bound in &self.state.current_textures {
                 if bound.get() == Some(self.id) {
@@ -1149,6 +1960,14 @@ impl Drop for RawTexture {
                 }
             }
 
+            let memory = if self.canvas.get() {
+                &self.state.canvas_memory
+            } else {
+                &self.state.texture_memory
+            };
+
+            memory.set(memory.get().saturating_sub(self.memory_usage()));
+
             self.state.gl.delete_texture(self.id);
         }
     }
@@ -1164,6 +1983,10 @@ pub struct RawFramebuffer {
 
 impl Drop for RawFramebuffer {
     fn drop(&mut self) {
+        if self.state.destroyed.get() {
+            return;
+        }
+
         unsafe {
             if self.state.current_read_framebuffer.get() == Some(self.id) {
                 self.state.current_read_framebuffer.set(None);
@@ -1184,18 +2007,103 @@ handle_impls!(RawFramebuffer);
 pub struct RawRenderbuffer {
     state: Rc<GraphicsState>,
     id: RenderbufferId,
+    memory_usage: usize,
 }
 
 impl Drop for RawRenderbuffer {
     fn drop(&mut self) {
+        if self.state.destroyed.get() {
+            return;
+        }
+
         unsafe {
             if self.state.current_renderbuffer.get() == Some(self.id) {
                 self.state.current_renderbuffer.set(None);
             }
 
+            let memory = &self.state.canvas_memory;
+            memory.set(memory.get().saturating_sub(self.memory_usage));
+
             self.state.gl.delete_renderbuffer(self.id);
         }
     }
 }
 
 handle_impls!(RawRenderbuffer);
+
+#[derive(Debug)]
+pub struct RawFence {
+    state: Rc<GraphicsState>,
+    id: FenceId,
+}
+
+impl Drop for RawFence {
+    fn drop(&mut self) {
+        if self.state.destroyed.get() {
+            return;
+        }
+
+        unsafe {
+            self.state.gl.delete_sync(self.id);
+        }
+    }
+}
+
+handle_impls!(RawFence);
+
+#[cfg(test)]
+mod tests {
+    use super::{annotate_shader_log, parse_shader_log_line};
+
+    #[test]
+    fn parse_nvidia_log_line() {
+        assert_eq!(
+            Some(12),
+            parse_shader_log_line("0(12) : error C0000: syntax error")
+        );
+    }
+
+    #[test]
+    fn parse_mesa_log_line() {
+        assert_eq!(
+            Some(20),
+            parse_shader_log_line("0:20(5): error: `foo' undeclared")
+        );
+    }
+
+    #[test]
+    fn parse_intel_log_line() {
+        assert_eq!(
+            Some(3),
+            parse_shader_log_line("ERROR: 0:3: 'foo' : undeclared identifier")
+        );
+    }
+
+    #[test]
+    fn parse_ignores_numbers_later_in_message() {
+        assert_eq!(None, parse_shader_log_line("error: expected 0(1) here"));
+        assert_eq!(None, parse_shader_log_line("ERROR: 2 compilation errors."));
+        assert_eq!(None, parse_shader_log_line(""));
+    }
+
+    #[test]
+    fn annotate_appends_source_lines() {
+        let source = "void main() {\n    foo;\n}";
+        let log = "0:2(5): error: `foo' undeclared\n0(3) : error C0000: syntax error\nlink failed";
+
+        assert_eq!(
+            "0:2(5): error: `foo' undeclared\n    2 |     foo;\n0(3) : error C0000: syntax error\n    3 | }\nlink failed\n",
+            annotate_shader_log(log, source)
+        );
+    }
+
+    #[test]
+    fn annotate_skips_out_of_range_lines() {
+        let log = "0:10(1): error: oops";
+
+        assert_eq!(
+            "0:10(1): error: oops\n",
+            annotate_shader_log(log, "void main() {}")
+        );
+    }
+}
@@ -7,21 +7,31 @@ use hashbrown::HashMap;
 use sdl2::controller::{Axis as SdlGamepadAxis, Button as SdlGamepadButton, GameController};
 use sdl2::event::{Event as SdlEvent, WindowEvent};
 use sdl2::haptic::Haptic;
-use sdl2::keyboard::Keycode as SdlKey;
+use sdl2::keyboard::{Keycode as SdlKey, Scancode as SdlScancode};
 use sdl2::mouse::{MouseButton as SdlMouseButton, MouseWheelDirection};
-use sdl2::sys::SDL_HAPTIC_INFINITY;
+use sdl2::sys::{
+    SDL_GameControllerFromInstanceID, SDL_GameControllerGetVendor, SDL_WindowFlags,
+    SDL_HAPTIC_INFINITY, SDL_WINDOWPOS_CENTERED_MASK,
+};
 use sdl2::video::{
     FullscreenType, GLContext as SdlGlContext, GLProfile, SwapInterval, Window as SdlWindow,
+    WindowPos,
 };
+#[cfg(feature = "audio")]
+use sdl2::AudioSubsystem;
 use sdl2::{
     EventPump, GameControllerSubsystem, HapticSubsystem, JoystickSubsystem, Sdl, VideoSubsystem,
 };
 
 use crate::error::{Result, TetraError};
 use crate::graphics;
-use crate::input::{self, GamepadAxis, GamepadButton, GamepadStick, Key, MouseButton};
+use crate::input::{
+    self, GamepadAxis, GamepadButton, GamepadStick, GamepadType, InputDevice, Key, MouseButton,
+    Scancode,
+};
 use crate::math::Vec2;
-use crate::{Context, ContextBuilder, Event, State};
+use crate::window::PresentMode;
+use crate::{Context, ContextBuilder, Event, GlProfile, State};
 
 struct SdlController {
     // NOTE: The SDL docs say to close the haptic device before the joystick, so
@@ -39,6 +49,8 @@ pub struct Window {
     video_sys: VideoSubsystem,
     controller_sys: GameControllerSubsystem,
     _joystick_sys: JoystickSubsystem,
+    #[cfg(feature = "audio")]
+    _audio_sys: AudioSubsystem,
     haptic_sys: HapticSubsystem,
     _gl_sys: SdlGlContext,
 
@@ -56,6 +68,11 @@ impl Window {
         let event_pump = sdl.event_pump().map_err(TetraError::PlatformError)?;
         let video_sys = sdl.video().map_err(TetraError::PlatformError)?;
         let joystick_sys = sdl.joystick().map_err(TetraError::PlatformError)?;
+
+        // Playback is handled by Rodio rather than SDL, but the audio subsystem needs to
+        // be initialized in order to receive device change events.
+        #[cfg(feature = "audio")]
+        let audio_sys = sdl.audio().map_err(TetraError::PlatformError)?;
         let controller_sys = sdl.game_controller().map_err(TetraError::PlatformError)?;
         let haptic_sys = sdl.haptic().map_err(TetraError::PlatformError)?;
 
@@ -63,18 +80,27 @@ impl Window {
 
         let gl_attr = video_sys.gl_attr();
 
-        // TODO: Will need to add some more here if we start using the depth/stencil buffers
-        gl_attr.set_context_profile(GLProfile::Core);
-        gl_attr.set_context_version(3, 2);
+        gl_attr.set_context_profile(match settings.gl_profile {
+            GlProfile::Core => GLProfile::Core,
+            GlProfile::Compatibility => GLProfile::Compatibility,
+        });
+        gl_attr.set_context_version(settings.gl_version.0, settings.gl_version.1);
         gl_attr.set_red_size(8);
         gl_attr.set_green_size(8);
         gl_attr.set_blue_size(8);
         gl_attr.set_alpha_size(8);
         gl_attr.set_double_buffer(true);
 
-        if settings.multisampling > 0 {
-            gl_attr.set_multisample_buffers(1);
-            gl_attr.set_multisample_samples(settings.multisampling);
+        if settings.depth_buffer {
+            gl_attr.set_depth_size(24);
+        }
+
+        if settings.stencil_buffer {
+            gl_attr.set_stencil_size(8);
+        }
+
+        if settings.srgb {
+            gl_attr.set_framebuffer_srgb_compatible(true);
         }
 
         if settings.screen_saver_enabled {
@@ -91,7 +117,19 @@ impl Window {
 
         // The window starts hidden, so that it doesn't look weird if we
         // maximize/minimize/fullscreen the window after it opens.
-        window_builder.hidden().position_centered().opengl();
+        window_builder.hidden().opengl();
+
+        match settings.window_position {
+            Some((x, y)) => {
+                window_builder.position(x, y);
+            }
+            None => {
+                // SDL encodes the monitor to center on in the lower bits of the position.
+                let position =
+                    (SDL_WINDOWPOS_CENTERED_MASK | settings.window_monitor as u32) as i32;
+                window_builder.position(position, position);
+            }
+        }
 
         if settings.resizable {
             window_builder.resizable();
@@ -101,6 +139,13 @@ impl Window {
             window_builder.borderless();
         }
 
+        if settings.always_on_top {
+            // The SDL bindings don't have a helper method for this flag.
+            let flags =
+                window_builder.window_flags() | SDL_WindowFlags::SDL_WINDOW_ALWAYS_ON_TOP as u32;
+            window_builder.set_window_flags(flags);
+        }
+
         if settings.high_dpi {
             window_builder.allow_highdpi();
         }
@@ -114,9 +159,35 @@ impl Window {
 
         sdl.mouse().show_cursor(settings.show_mouse);
 
-        let mut sdl_window = window_builder
-            .build()
-            .map_err(|e| TetraError::PlatformError(e.to_string()))?;
+        // If the requested number of samples isn't supported, creating the window or
+        // the GL context will fail - in that case, we keep retrying with fewer samples,
+        // eventually falling back to no multisampling at all.
+        let mut multisampling = settings.multisampling;
+
+        let (mut sdl_window, gl_sys) = loop {
+            gl_attr.set_multisample_buffers(if multisampling > 0 { 1 } else { 0 });
+            gl_attr.set_multisample_samples(multisampling);
+
+            let result = window_builder
+                .build()
+                .map_err(|e| e.to_string())
+                .and_then(|window| {
+                    let gl_sys = window.gl_create_context()?;
+                    Ok((window, gl_sys))
+                });
+
+            match result {
+                Ok(created) => break created,
+                Err(_) if multisampling > 0 => {
+                    multisampling = if multisampling > 2 {
+                        multisampling / 2
+                    } else {
+                        0
+                    };
+                }
+                Err(e) => return Err(TetraError::PlatformError(e)),
+            }
+        };
 
         // We wait until the window has been created to fiddle with this stuff as:
         // a) we don't want to blow away the window size settings
@@ -125,14 +196,16 @@ impl Window {
         let mut window_width = settings.window_width;
         let mut window_height = settings.window_height;
 
+        // The window size is tracked in logical pixels - the drawable size may be larger
+        // than this if the window has a high-DPI backbuffer.
         if settings.maximized {
             sdl_window.maximize();
-            let size = sdl_window.drawable_size();
+            let size = sdl_window.size();
             window_width = size.0 as i32;
             window_height = size.1 as i32;
         } else if settings.minimized {
             sdl_window.minimize();
-            let size = sdl_window.drawable_size();
+            let size = sdl_window.size();
             window_width = size.0 as i32;
             window_height = size.1 as i32;
         }
@@ -148,10 +221,6 @@ impl Window {
                 .map_err(TetraError::FailedToChangeDisplayMode)?;
         }
 
-        let gl_sys = sdl_window
-            .gl_create_context()
-            .map_err(TetraError::PlatformError)?;
-
         let gl_ctx = unsafe {
             GlowContext::from_loader_function(|s| video_sys.gl_get_proc_address(s) as *const _)
         };
@@ -172,6 +241,8 @@ impl Window {
             video_sys,
             controller_sys,
             _joystick_sys: joystick_sys,
+            #[cfg(feature = "audio")]
+            _audio_sys: audio_sys,
             haptic_sys,
             _gl_sys: gl_sys,
 
@@ -209,6 +280,21 @@ impl Window {
         (self.window_width, self.window_height)
     }
 
+    pub fn get_physical_size(&self) -> (i32, i32) {
+        let (width, height) = self.sdl_window.drawable_size();
+        (width as i32, height as i32)
+    }
+
+    pub fn get_dpi_scale(&self) -> f32 {
+        let (physical_width, _) = self.sdl_window.drawable_size();
+
+        if self.window_width > 0 {
+            physical_width as f32 / self.window_width as f32
+        } else {
+            1.0
+        }
+    }
+
     pub fn set_window_size(&mut self, width: i32, height: i32) -> Result {
         self.window_width = width;
         self.window_height = height;
@@ -218,6 +304,68 @@ impl Window {
             .map_err(|e| TetraError::FailedToChangeDisplayMode(e.to_string()))
     }
 
+    pub fn get_position(&self) -> (i32, i32) {
+        self.sdl_window.position()
+    }
+
+    pub fn set_position(&mut self, x: i32, y: i32) {
+        self.sdl_window
+            .set_position(WindowPos::Positioned(x), WindowPos::Positioned(y));
+    }
+
+    pub fn maximize(&mut self) {
+        self.sdl_window.maximize();
+    }
+
+    pub fn minimize(&mut self) {
+        self.sdl_window.minimize();
+    }
+
+    pub fn restore(&mut self) {
+        self.sdl_window.restore();
+    }
+
+    pub fn is_maximized(&self) -> bool {
+        self.sdl_window.window_flags() & SDL_WindowFlags::SDL_WINDOW_MAXIMIZED as u32 != 0
+    }
+
+    pub fn is_minimized(&self) -> bool {
+        self.sdl_window.window_flags() & SDL_WindowFlags::SDL_WINDOW_MINIMIZED as u32 != 0
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.sdl_window.window_flags() & SDL_WindowFlags::SDL_WINDOW_INPUT_FOCUS as u32 != 0
+    }
+
+    pub fn is_visible(&self) -> bool {
+        let flags = self.sdl_window.window_flags();
+
+        flags & SDL_WindowFlags::SDL_WINDOW_SHOWN as u32 != 0
+            && flags & SDL_WindowFlags::SDL_WINDOW_MINIMIZED as u32 == 0
+    }
+
+    pub fn get_opacity(&self) -> Result<f32> {
+        self.sdl_window.opacity().map_err(TetraError::PlatformError)
+    }
+
+    pub fn set_opacity(&mut self, opacity: f32) -> Result {
+        self.sdl_window
+            .set_opacity(opacity)
+            .map_err(TetraError::PlatformError)
+    }
+
+    pub fn set_borderless(&mut self, borderless: bool) {
+        self.sdl_window.set_bordered(!borderless);
+    }
+
+    pub fn is_borderless(&self) -> bool {
+        self.sdl_window.window_flags() & SDL_WindowFlags::SDL_WINDOW_BORDERLESS as u32 != 0
+    }
+
+    pub fn is_always_on_top(&self) -> bool {
+        self.sdl_window.window_flags() & SDL_WindowFlags::SDL_WINDOW_ALWAYS_ON_TOP as u32 != 0
+    }
+
     pub fn set_visible(&mut self, visible: bool) {
         if visible {
             self.sdl_window.show()
@@ -267,6 +415,27 @@ impl Window {
         self.video_sys.gl_get_swap_interval() != SwapInterval::Immediate
     }
 
+    pub fn set_present_mode(&mut self, present_mode: PresentMode) -> Result {
+        let result = match present_mode {
+            PresentMode::Immediate => self.video_sys.gl_set_swap_interval(SwapInterval::Immediate),
+            PresentMode::VSync => self.video_sys.gl_set_swap_interval(SwapInterval::VSync),
+            PresentMode::AdaptiveVSync => self
+                .video_sys
+                .gl_set_swap_interval(SwapInterval::LateSwapTearing)
+                .or_else(|_| self.video_sys.gl_set_swap_interval(SwapInterval::VSync)),
+        };
+
+        result.map_err(TetraError::FailedToChangeDisplayMode)
+    }
+
+    pub fn get_present_mode(&self) -> PresentMode {
+        match self.video_sys.gl_get_swap_interval() {
+            SwapInterval::Immediate => PresentMode::Immediate,
+            SwapInterval::VSync => PresentMode::VSync,
+            SwapInterval::LateSwapTearing => PresentMode::AdaptiveVSync,
+        }
+    }
+
     pub fn set_fullscreen(&mut self, fullscreen: bool) -> Result {
         if fullscreen {
             self.sdl_window
@@ -284,7 +453,7 @@ impl Window {
                 .set_fullscreen(FullscreenType::Off)
                 .map_err(TetraError::FailedToChangeDisplayMode)
                 .and_then(|_| {
-                    let size = self.sdl_window.drawable_size();
+                    let size = self.sdl_window.size();
                     self.set_window_size(size.0 as i32, size.1 as i32)
                 })
         }
@@ -317,6 +486,12 @@ impl Window {
             .set_relative_mouse_mode(relative_mouse_mode);
     }
 
+    pub fn warp_mouse(&mut self, x: i32, y: i32) {
+        self.sdl
+            .mouse()
+            .warp_mouse_in_window(&self.sdl_window, x, y);
+    }
+
     pub fn is_relative_mouse_mode(&self) -> bool {
         self.sdl.mouse().relative_mouse_mode()
     }
@@ -343,6 +518,27 @@ impl Window {
         self.controllers[&platform_id].controller.name()
     }
 
+    pub fn get_gamepad_type(&self, platform_id: u32) -> GamepadType {
+        // The SDL2 bindings don't expose `SDL_GameControllerGetType` yet, so we
+        // identify the controller's manufacturer via its USB vendor ID instead.
+        let vendor = unsafe {
+            let controller = SDL_GameControllerFromInstanceID(platform_id as i32);
+
+            if controller.is_null() {
+                return GamepadType::Unknown;
+            }
+
+            SDL_GameControllerGetVendor(controller)
+        };
+
+        match vendor {
+            0x045e => GamepadType::Xbox,
+            0x054c => GamepadType::PlayStation,
+            0x057e => GamepadType::NintendoSwitch,
+            _ => GamepadType::Unknown,
+        }
+    }
+
     pub fn is_gamepad_vibration_supported(&self, platform_id: u32) -> bool {
         self.controllers[&platform_id].haptic.is_some()
     }
@@ -398,6 +594,9 @@ where
     E: From<TetraError>,
 {
     while let Some(event) = ctx.window.event_pump.poll_event() {
+        #[cfg(feature = "sdl2_raw_events")]
+        let raw_event = event.clone();
+
         match event {
             SdlEvent::Quit { .. } => ctx.running = false, // TODO: Add a way to override this
 
@@ -431,28 +630,39 @@ where
             },
 
             SdlEvent::KeyDown {
-                keycode: Some(k),
+                keycode,
+                scancode,
                 repeat,
                 ..
             } => {
                 if !repeat || ctx.window.is_key_repeat_enabled() {
-                    if let SdlKey::Escape = k {
-                        if ctx.quit_on_escape {
-                            ctx.running = false;
-                        }
+                    if let Some(scancode) = scancode.and_then(into_scancode) {
+                        input::set_scancode_down(ctx, scancode);
                     }
 
-                    if let Some(key) = into_key(k) {
-                        input::set_key_down(ctx, key);
-                        state.event(ctx, Event::KeyPressed { key })?;
+                    if let Some(k) = keycode {
+                        if let SdlKey::Escape = k {
+                            if ctx.quit_on_escape {
+                                ctx.running = false;
+                            }
+                        }
+
+                        if let Some(key) = into_key(k) {
+                            input::set_key_down(ctx, key);
+                            state.event(ctx, Event::KeyPressed { key })?;
+                        }
                     }
                 }
             }
 
             SdlEvent::KeyUp {
-                keycode: Some(k), ..
+                keycode, scancode, ..
             } => {
-                if let Some(key) = into_key(k) {
+                if let Some(scancode) = scancode.and_then(into_scancode) {
+                    input::set_scancode_up(ctx, scancode);
+                }
+
+                if let Some(key) = keycode.and_then(into_key) {
                     // TODO: This can cause some inputs to be missed at low tick rates.
                     // Could consider buffering input releases like Otter2D does?
                     input::set_key_up(ctx, key);
@@ -478,21 +688,32 @@ where
                 x, y, xrel, yrel, ..
             } => {
                 let position = Vec2::new(x as f32, y as f32);
-                let delta = Vec2::new(xrel as f32, yrel as f32);
 
-                input::set_mouse_position(ctx, position);
+                let delta = if input::take_mouse_warp(ctx, position) {
+                    Vec2::zero()
+                } else {
+                    Vec2::new(xrel as f32, yrel as f32)
+                };
+
+                input::update_mouse_position(ctx, position);
                 state.event(ctx, Event::MouseMoved { position, delta })?;
             }
 
             SdlEvent::MouseWheel {
                 x, y, direction, ..
             } => {
+                // When the direction is flipped (e.g. 'natural scrolling' on macOS), SDL
+                // reports the values inverted, so we undo that to keep the sign consistent.
                 let amount = match direction {
                     MouseWheelDirection::Flipped => Vec2::new(-x, -y),
                     _ => Vec2::new(x, y),
                 };
 
-                input::apply_mouse_wheel_movement(ctx, amount);
+                // The version of SDL we currently bind to only reports whole steps, so the
+                // precise movement is derived from those.
+                let movement = Vec2::new(amount.x as f32, amount.y as f32);
+
+                input::apply_mouse_wheel_movement(ctx, movement, amount);
                 state.event(ctx, Event::MouseWheelMoved { amount })?
             }
 
@@ -545,12 +766,24 @@ where
                 )?;
             }
 
+            #[cfg(feature = "audio")]
+            SdlEvent::AudioDeviceAdded {
+                iscapture: false, ..
+            }
+            | SdlEvent::AudioDeviceRemoved {
+                iscapture: false, ..
+            } => {
+                ctx.audio.handle_devices_changed();
+                state.event(ctx, Event::AudioDevicesChanged)?;
+            }
+
             SdlEvent::ControllerButtonDown { which, button, .. } => {
                 if let Some(slot) = ctx.window.controllers.get(&which).map(|c| c.slot) {
                     if let Some(pad) = input::get_gamepad_mut(ctx, slot) {
                         let button = button.into();
 
                         pad.set_button_down(button);
+                        input::set_last_input_device(ctx, InputDevice::Gamepad(slot));
                         state.event(ctx, Event::GamepadButtonPressed { id: slot, button })?;
                     }
                 }
@@ -612,6 +845,12 @@ where
                             }
                         }
 
+                        // Small movements are ignored, so that a stick that doesn't quite
+                        // return to the center doesn't steal focus from other devices.
+                        if mapped_value.abs() >= 0.5 {
+                            input::set_last_input_device(ctx, InputDevice::Gamepad(slot));
+                        }
+
                         state.event(
                             ctx,
                             Event::GamepadAxisMoved {
@@ -647,6 +886,9 @@ where
 
             _ => {}
         }
+
+        #[cfg(feature = "sdl2_raw_events")]
+        state.event(ctx, Event::Raw { event: raw_event })?;
     }
 
     Ok(())
@@ -748,9 +990,11 @@ fn into_key(key: SdlKey) -> Option<Key> {
         SdlKey::LCtrl => Some(Key::LeftCtrl),
         SdlKey::LShift => Some(Key::LeftShift),
         SdlKey::LAlt => Some(Key::LeftAlt),
+        SdlKey::LGui => Some(Key::LeftSuper),
         SdlKey::RCtrl => Some(Key::RightCtrl),
         SdlKey::RShift => Some(Key::RightShift),
         SdlKey::RAlt => Some(Key::RightAlt),
+        SdlKey::RGui => Some(Key::RightSuper),
 
         SdlKey::Up => Some(Key::Up),
         SdlKey::Down => Some(Key::Down),
@@ -805,6 +1049,133 @@ fn into_key(key: SdlKey) -> Option<Key> {
     }
 }
 
+fn into_scancode(scancode: SdlScancode) -> Option<Scancode> {
+    match scancode {
+        SdlScancode::A => Some(Scancode::A),
+        SdlScancode::B => Some(Scancode::B),
+        SdlScancode::C => Some(Scancode::C),
+        SdlScancode::D => Some(Scancode::D),
+        SdlScancode::E => Some(Scancode::E),
+        SdlScancode::F => Some(Scancode::F),
+        SdlScancode::G => Some(Scancode::G),
+        SdlScancode::H => Some(Scancode::H),
+        SdlScancode::I => Some(Scancode::I),
+        SdlScancode::J => Some(Scancode::J),
+        SdlScancode::K => Some(Scancode::K),
+        SdlScancode::L => Some(Scancode::L),
+        SdlScancode::M => Some(Scancode::M),
+        SdlScancode::N => Some(Scancode::N),
+        SdlScancode::O => Some(Scancode::O),
+        SdlScancode::P => Some(Scancode::P),
+        SdlScancode::Q => Some(Scancode::Q),
+        SdlScancode::R => Some(Scancode::R),
+        SdlScancode::S => Some(Scancode::S),
+        SdlScancode::T => Some(Scancode::T),
+        SdlScancode::U => Some(Scancode::U),
+        SdlScancode::V => Some(Scancode::V),
+        SdlScancode::W => Some(Scancode::W),
+        SdlScancode::X => Some(Scancode::X),
+        SdlScancode::Y => Some(Scancode::Y),
+        SdlScancode::Z => Some(Scancode::Z),
+
+        SdlScancode::Num0 => Some(Scancode::Num0),
+        SdlScancode::Num1 => Some(Scancode::Num1),
+        SdlScancode::Num2 => Some(Scancode::Num2),
+        SdlScancode::Num3 => Some(Scancode::Num3),
+        SdlScancode::Num4 => Some(Scancode::Num4),
+        SdlScancode::Num5 => Some(Scancode::Num5),
+        SdlScancode::Num6 => Some(Scancode::Num6),
+        SdlScancode::Num7 => Some(Scancode::Num7),
+        SdlScancode::Num8 => Some(Scancode::Num8),
+        SdlScancode::Num9 => Some(Scancode::Num9),
+
+        SdlScancode::F1 => Some(Scancode::F1),
+        SdlScancode::F2 => Some(Scancode::F2),
+        SdlScancode::F3 => Some(Scancode::F3),
+        SdlScancode::F4 => Some(Scancode::F4),
+        SdlScancode::F5 => Some(Scancode::F5),
+        SdlScancode::F6 => Some(Scancode::F6),
+        SdlScancode::F7 => Some(Scancode::F7),
+        SdlScancode::F8 => Some(Scancode::F8),
+        SdlScancode::F9 => Some(Scancode::F9),
+        SdlScancode::F10 => Some(Scancode::F10),
+        SdlScancode::F11 => Some(Scancode::F11),
+        SdlScancode::F12 => Some(Scancode::F12),
+        SdlScancode::F13 => Some(Scancode::F13),
+        SdlScancode::F14 => Some(Scancode::F14),
+        SdlScancode::F15 => Some(Scancode::F15),
+        SdlScancode::F16 => Some(Scancode::F16),
+        SdlScancode::F17 => Some(Scancode::F17),
+        SdlScancode::F18 => Some(Scancode::F18),
+        SdlScancode::F19 => Some(Scancode::F19),
+        SdlScancode::F20 => Some(Scancode::F20),
+        SdlScancode::F21 => Some(Scancode::F21),
+        SdlScancode::F22 => Some(Scancode::F22),
+        SdlScancode::F23 => Some(Scancode::F23),
+        SdlScancode::F24 => Some(Scancode::F24),
+
+        SdlScancode::NumLockClear => Some(Scancode::NumLock),
+        SdlScancode::Kp1 => Some(Scancode::NumPad1),
+        SdlScancode::Kp2 => Some(Scancode::NumPad2),
+        SdlScancode::Kp3 => Some(Scancode::NumPad3),
+        SdlScancode::Kp4 => Some(Scancode::NumPad4),
+        SdlScancode::Kp5 => Some(Scancode::NumPad5),
+        SdlScancode::Kp6 => Some(Scancode::NumPad6),
+        SdlScancode::Kp7 => Some(Scancode::NumPad7),
+        SdlScancode::Kp8 => Some(Scancode::NumPad8),
+        SdlScancode::Kp9 => Some(Scancode::NumPad9),
+        SdlScancode::Kp0 => Some(Scancode::NumPad0),
+        SdlScancode::KpPlus => Some(Scancode::NumPadPlus),
+        SdlScancode::KpMinus => Some(Scancode::NumPadMinus),
+        SdlScancode::KpMultiply => Some(Scancode::NumPadMultiply),
+        SdlScancode::KpDivide => Some(Scancode::NumPadDivide),
+        SdlScancode::KpEnter => Some(Scancode::NumPadEnter),
+
+        SdlScancode::LCtrl => Some(Scancode::LeftCtrl),
+        SdlScancode::LShift => Some(Scancode::LeftShift),
+        SdlScancode::LAlt => Some(Scancode::LeftAlt),
+        SdlScancode::LGui => Some(Scancode::LeftSuper),
+        SdlScancode::RCtrl => Some(Scancode::RightCtrl),
+        SdlScancode::RShift => Some(Scancode::RightShift),
+        SdlScancode::RAlt => Some(Scancode::RightAlt),
+        SdlScancode::RGui => Some(Scancode::RightSuper),
+
+        SdlScancode::Up => Some(Scancode::Up),
+        SdlScancode::Down => Some(Scancode::Down),
+        SdlScancode::Left => Some(Scancode::Left),
+        SdlScancode::Right => Some(Scancode::Right),
+
+        SdlScancode::Grave => Some(Scancode::Backquote),
+        SdlScancode::Backslash => Some(Scancode::Backslash),
+        SdlScancode::Backspace => Some(Scancode::Backspace),
+        SdlScancode::CapsLock => Some(Scancode::CapsLock),
+        SdlScancode::Comma => Some(Scancode::Comma),
+        SdlScancode::Delete => Some(Scancode::Delete),
+        SdlScancode::End => Some(Scancode::End),
+        SdlScancode::Return => Some(Scancode::Enter),
+        SdlScancode::Equals => Some(Scancode::Equals),
+        SdlScancode::Escape => Some(Scancode::Escape),
+        SdlScancode::Home => Some(Scancode::Home),
+        SdlScancode::Insert => Some(Scancode::Insert),
+        SdlScancode::LeftBracket => Some(Scancode::LeftBracket),
+        SdlScancode::Minus => Some(Scancode::Minus),
+        SdlScancode::PageDown => Some(Scancode::PageDown),
+        SdlScancode::PageUp => Some(Scancode::PageUp),
+        SdlScancode::Pause => Some(Scancode::Pause),
+        SdlScancode::Period => Some(Scancode::Period),
+        SdlScancode::PrintScreen => Some(Scancode::PrintScreen),
+        SdlScancode::Apostrophe => Some(Scancode::Quote),
+        SdlScancode::RightBracket => Some(Scancode::RightBracket),
+        SdlScancode::ScrollLock => Some(Scancode::ScrollLock),
+        SdlScancode::Semicolon => Some(Scancode::Semicolon),
+        SdlScancode::Slash => Some(Scancode::Slash),
+        SdlScancode::Space => Some(Scancode::Space),
+        SdlScancode::Tab => Some(Scancode::Tab),
+
+        _ => None,
+    }
+}
+
 #[doc(hidden)]
 impl From<SdlGamepadButton> for GamepadButton {
     fn from(button: SdlGamepadButton) -> GamepadButton {
@@ -5,9 +5,19 @@ in vec4 v_color;
 
 uniform sampler2D u_texture;
 uniform vec4 u_diffuse;
+uniform vec2 u_color_blend;
 
 out vec4 o_color;
 
 void main() {
-    o_color = texture(u_texture, v_uv) * u_diffuse * v_color;
+    vec4 texel = texture(u_texture, v_uv);
+    vec4 color = u_diffuse * v_color;
+
+    if (u_color_blend.x == 1.0) {
+        o_color = vec4(texel.rgb + color.rgb * color.a, texel.a);
+    } else if (u_color_blend.x == 2.0) {
+        o_color = vec4(mix(texel.rgb, color.rgb, u_color_blend.y), texel.a * color.a);
+    } else {
+        o_color = texel * color;
+    }
 }
\ No newline at end of file
@@ -1,11 +1,16 @@
 //! Functions and types relating to measuring and manipulating time.
 
+mod timer;
+
 use std::collections::VecDeque;
+use std::ops::Mul;
 
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use crate::Context;
 
+pub use timer::*;
+
 /// The different timestep modes that a game can have.
 ///
 /// # Serde
@@ -52,6 +57,9 @@ pub(crate) struct TimeContext {
     pub(crate) tick_rate: Option<Duration>,
     pub(crate) delta_time: Duration,
     pub(crate) accumulator: Duration,
+    pub(crate) last_time: Instant,
+    pub(crate) start_time: Instant,
+    pub(crate) paused: bool,
 }
 
 impl TimeContext {
@@ -77,6 +85,9 @@ impl TimeContext {
             tick_rate,
             delta_time: Duration::from_secs(0),
             accumulator: Duration::from_secs(0),
+            last_time: Instant::now(),
+            start_time: Instant::now(),
+            paused: false,
         }
     }
 }
@@ -84,6 +95,7 @@ impl TimeContext {
 pub(crate) fn reset(ctx: &mut Context) {
     ctx.time.delta_time = Duration::from_secs(0);
     ctx.time.accumulator = Duration::from_secs(0);
+    ctx.time.last_time = Instant::now();
 }
 
 /// Returns the amount of time that has passed since the last update or draw.
@@ -91,7 +103,8 @@ pub(crate) fn reset(ctx: &mut Context) {
 /// This can be used to integrate the amount of time that has passed into your game's
 /// calculations. For example, if you wanted to move a [`Vec2`](crate::math::Vec2) 32
 /// units to the right per second, you could do
-/// `foo.y += 32.0 * time::get_delta_time(ctx).as_secs_f32()`.
+/// `foo.x += 32.0 * time::get_delta_time(ctx).as_secs_f32()`. The [`step`] function
+/// provides a shorthand for this.
 ///
 /// When using a fixed time step, calling this function during an update will always
 /// return the configured update rate. This is to prevent floating point error/non-determinism
@@ -100,6 +113,33 @@ pub fn get_delta_time(ctx: &Context) -> Duration {
     ctx.time.delta_time
 }
 
+/// Scales a per-second value by the amount of time that has passed since the last update
+/// or draw.
+///
+/// This is shorthand for multiplying by [`get_delta_time`], and works with any type that
+/// can be multiplied by an [`f32`] (e.g. a [`Vec2`](crate::math::Vec2) velocity). Scaling your
+/// movement this way means that it will happen at the same speed regardless of frame rate.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tetra::Context;
+/// # use tetra::math::Vec2;
+/// # use tetra::time;
+/// # fn update(ctx: &mut Context, position: &mut Vec2<f32>) {
+/// let velocity = Vec2::new(32.0, 0.0);
+///
+/// // Moves 32 pixels to the right per second:
+/// *position += time::step(ctx, velocity);
+/// # }
+/// ```
+pub fn step<T>(ctx: &Context, value_per_second: T) -> T
+where
+    T: Mul<f32, Output = T>,
+{
+    value_per_second * ctx.time.delta_time.as_secs_f32()
+}
+
 /// Returns the amount of time that has accumulated between updates.
 ///
 /// When using a fixed time step, as time passes, this value will increase;
@@ -116,11 +156,17 @@ pub fn get_accumulator(ctx: &Context) -> Duration {
 /// For example, if the value is 0.01, an update just happened; if the value is 0.99,
 /// an update is about to happen.
 ///
-/// This can be used to interpolate when rendering.
+/// This can be used to interpolate when rendering - for example, by drawing each entity at
+/// `previous_position + (position - previous_position) * blend_factor`. Without this, games
+/// using a fixed time step can visibly stutter when the frame rate is not a multiple of
+/// the tick rate. This value is sometimes referred to as the 'alpha' of the fixed update.
+///
+/// When using a variable time step, this function always returns `0.0`.
 ///
 /// This function returns an [`f32`], which is usually what you want when blending - however,
 /// if you need a more precise representation of the blend factor, you can call
 /// [`get_blend_factor_precise`].
+#[doc(alias = "get_fixed_update_alpha")]
 pub fn get_blend_factor(ctx: &Context) -> f32 {
     match ctx.time.tick_rate {
         Some(tick_rate) => ctx.time.accumulator.as_secs_f32() / tick_rate.as_secs_f32(),
@@ -167,6 +213,24 @@ pub fn set_timestep(ctx: &mut Context, timestep: Timestep) {
     };
 }
 
+/// Pauses or resumes the game loop.
+///
+/// While the game loop is paused, [`State::update`](crate::State::update) will not be called,
+/// and no time will accumulate towards the next fixed timestep update. Events will still be
+/// handled and [`State::draw`](crate::State::draw) will still be called, but at a reduced rate,
+/// in order to save CPU/battery.
+///
+/// This does not affect audio - if you want to pause playback as well, use
+/// `audio::set_suspended`.
+pub fn set_paused(ctx: &mut Context, paused: bool) {
+    ctx.time.paused = paused;
+}
+
+/// Returns whether or not the game loop is currently paused.
+pub fn is_paused(ctx: &Context) -> bool {
+    ctx.time.paused
+}
+
 /// Returns the current frame rate, averaged out over the last 200 frames.
 pub fn get_fps(ctx: &Context) -> f64 {
     1.0 / (ctx.time.fps_tracker.iter().sum::<f64>() / ctx.time.fps_tracker.len() as f64)
@@ -0,0 +1,240 @@
+use std::time::Duration;
+
+/// A timer that counts down from a given duration.
+///
+/// Timers do not track time on their own - instead, you should call [`tick`](Timer::tick)
+/// with the amount of time that has passed (usually the value returned by
+/// [`get_delta_time`](super::get_delta_time)). This makes them deterministic, and allows
+/// them to be paused by simply not ticking them.
+///
+/// # Examples
+///
+/// ```
+/// # use std::time::Duration;
+/// # use tetra::time::Timer;
+/// let mut cooldown = Timer::new(Duration::from_secs(1));
+///
+/// assert!(!cooldown.tick(Duration::from_millis(500)));
+/// assert!(cooldown.tick(Duration::from_millis(500)));
+/// assert!(cooldown.finished());
+///
+/// cooldown.reset();
+///
+/// assert!(!cooldown.finished());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Timer {
+    duration: Duration,
+    elapsed: Duration,
+    repeating: bool,
+}
+
+impl Timer {
+    /// Creates a new timer, which will finish after the given duration.
+    pub fn new(duration: Duration) -> Timer {
+        Timer {
+            duration,
+            elapsed: Duration::from_secs(0),
+            repeating: false,
+        }
+    }
+
+    /// Creates a new timer, which will finish after the given duration and then
+    /// automatically restart.
+    ///
+    /// Any time left over when the timer finishes will be carried over into the next
+    /// repetition, so that the interval remains consistent.
+    pub fn repeating(duration: Duration) -> Timer {
+        Timer {
+            duration,
+            elapsed: Duration::from_secs(0),
+            repeating: true,
+        }
+    }
+
+    /// Advances the timer by the given amount of time.
+    ///
+    /// Returns `true` if the timer finished during this tick. For a repeating timer, this
+    /// will only return `true` once per tick, even if the given amount of time is long
+    /// enough for the timer to finish multiple times.
+    pub fn tick(&mut self, delta: Duration) -> bool {
+        if self.finished() {
+            return false;
+        }
+
+        self.elapsed += delta;
+
+        if self.elapsed < self.duration {
+            return false;
+        }
+
+        if self.repeating {
+            self.elapsed = if self.duration > Duration::from_secs(0) {
+                let nanos = self.elapsed.as_nanos() % self.duration.as_nanos();
+                Duration::from_nanos(nanos as u64)
+            } else {
+                Duration::from_secs(0)
+            };
+        } else {
+            self.elapsed = self.duration;
+        }
+
+        true
+    }
+
+    /// Returns `true` if the timer has finished.
+    ///
+    /// Repeating timers never finish, as they restart automatically - use the return value of
+    /// [`tick`](Timer::tick) to detect when they fire instead.
+    pub fn finished(&self) -> bool {
+        !self.repeating && self.elapsed >= self.duration
+    }
+
+    /// Resets the timer back to its full duration.
+    pub fn reset(&mut self) {
+        self.elapsed = Duration::from_secs(0);
+    }
+
+    /// Returns the total duration of the timer.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Sets the total duration of the timer.
+    ///
+    /// This does not reset the time that has already elapsed.
+    pub fn set_duration(&mut self, duration: Duration) {
+        self.duration = duration;
+    }
+
+    /// Returns the amount of time that has elapsed since the timer was started or reset.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Returns the amount of time remaining until the timer finishes.
+    pub fn remaining(&self) -> Duration {
+        self.duration.checked_sub(self.elapsed).unwrap_or_default()
+    }
+
+    /// Returns a value between 0.0 and 1.0, representing how close the timer is to finishing.
+    pub fn progress(&self) -> f32 {
+        if self.duration == Duration::from_secs(0) {
+            1.0
+        } else {
+            (self.elapsed.as_secs_f32() / self.duration.as_secs_f32()).min(1.0)
+        }
+    }
+
+    /// Returns `true` if the timer will automatically restart when it finishes.
+    pub fn is_repeating(&self) -> bool {
+        self.repeating
+    }
+}
+
+/// A stopwatch that measures the amount of time that has passed.
+///
+/// Like [`Timer`], stopwatches do not track time on their own - you should call
+/// [`tick`](Stopwatch::tick) with the amount of time that has passed.
+///
+/// # Examples
+///
+/// ```
+/// # use std::time::Duration;
+/// # use tetra::time::Stopwatch;
+/// let mut stopwatch = Stopwatch::new();
+///
+/// stopwatch.tick(Duration::from_secs(1));
+/// stopwatch.pause();
+/// stopwatch.tick(Duration::from_secs(1));
+///
+/// assert_eq!(Duration::from_secs(1), stopwatch.elapsed());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stopwatch {
+    elapsed: Duration,
+    paused: bool,
+}
+
+impl Stopwatch {
+    /// Creates a new stopwatch, which will start measuring time immediately.
+    pub fn new() -> Stopwatch {
+        Stopwatch {
+            elapsed: Duration::from_secs(0),
+            paused: false,
+        }
+    }
+
+    /// Advances the stopwatch by the given amount of time.
+    ///
+    /// If the stopwatch is paused, this will have no effect.
+    pub fn tick(&mut self, delta: Duration) {
+        if !self.paused {
+            self.elapsed += delta;
+        }
+    }
+
+    /// Returns the amount of time that has been measured.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Pauses the stopwatch.
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Resumes the stopwatch, if it was paused.
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    /// Returns `true` if the stopwatch is paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Resets the measured time back to zero.
+    ///
+    /// This does not change whether or not the stopwatch is paused.
+    pub fn reset(&mut self) {
+        self.elapsed = Duration::from_secs(0);
+    }
+}
+
+impl Default for Stopwatch {
+    fn default() -> Stopwatch {
+        Stopwatch::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::Timer;
+
+    #[test]
+    fn timer_finishes_once() {
+        let mut timer = Timer::new(Duration::from_millis(100));
+
+        assert!(!timer.tick(Duration::from_millis(60)));
+        assert!(timer.tick(Duration::from_millis(60)));
+        assert!(!timer.tick(Duration::from_millis(60)));
+
+        assert!(timer.finished());
+        assert_eq!(Duration::from_secs(0), timer.remaining());
+    }
+
+    #[test]
+    fn repeating_timer_carries_over() {
+        let mut timer = Timer::repeating(Duration::from_millis(100));
+
+        assert!(timer.tick(Duration::from_millis(130)));
+        assert_eq!(Duration::from_millis(30), timer.elapsed());
+        assert!(!timer.finished());
+
+        assert!(timer.tick(Duration::from_millis(70)));
+        assert_eq!(Duration::from_millis(0), timer.elapsed());
+    }
+}
@@ -1,7 +1,38 @@
 //! Functions and types relating to the game window, and the environment it is running in.
 
+use std::time::Duration;
+
 use crate::{Context, Result};
 
+/// The ways in which frames can be presented to the screen.
+///
+/// # Serde
+///
+/// Serialization and deserialization of this type (via [Serde](https://serde.rs/))
+/// can be enabled via the `serde_support` feature.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde_support",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum PresentMode {
+    /// Frames will be presented as soon as they are ready, without waiting for the display
+    /// to refresh. This gives the lowest latency, but may cause screen tearing.
+    Immediate,
+
+    /// Frames will be presented in sync with the display's refresh rate. This prevents
+    /// screen tearing, but can add latency.
+    VSync,
+
+    /// Frames will be presented in sync with the display's refresh rate, unless a frame
+    /// is late, in which case it will be presented immediately. This avoids the stutter
+    /// caused by missing a refresh, at the cost of occasional tearing.
+    ///
+    /// This is sometimes referred to as 'adaptive vsync', and is not supported by all
+    /// graphics drivers.
+    AdaptiveVSync,
+}
+
 /// Quits the game, if it is currently running.
 ///
 /// Note that quitting the game does not take effect until the end of the current
@@ -16,6 +47,13 @@ pub fn get_title(ctx: &Context) -> &str {
 }
 
 /// Sets the title of the window.
+///
+/// This can be called at any time, e.g. to display the name of the current level, or
+/// to mark that there are unsaved changes.
+///
+/// # Panics
+///
+/// Panics if the title contains a null byte.
 pub fn set_title<S>(ctx: &mut Context, title: S)
 where
     S: AsRef<str>,
@@ -24,6 +62,11 @@ where
 }
 
 /// Gets the width of the window.
+///
+/// This is measured in logical pixels, which is the same co-ordinate space that is used for
+/// rendering to the window and for the mouse position. If the window has a high-DPI backbuffer,
+/// this may be smaller than the number of physical pixels - use [`get_physical_width`] if you
+/// need that value instead.
 pub fn get_width(ctx: &Context) -> i32 {
     ctx.window.get_window_width()
 }
@@ -39,6 +82,8 @@ pub fn set_width(ctx: &mut Context, width: i32) -> Result {
 }
 
 /// Gets the height of the window.
+///
+/// This is measured in logical pixels - see [`get_width`] for more details.
 pub fn get_height(ctx: &Context) -> i32 {
     ctx.window.get_window_height()
 }
@@ -54,10 +99,51 @@ pub fn set_height(ctx: &mut Context, height: i32) -> Result {
 }
 
 /// Gets the size of the window.
+///
+/// This is measured in logical pixels - see [`get_width`] for more details.
 pub fn get_size(ctx: &Context) -> (i32, i32) {
     ctx.window.get_window_size()
 }
 
+/// Gets the width of the window's backbuffer, in physical pixels.
+///
+/// This will only differ from [`get_width`] if high-DPI rendering has been enabled via
+/// [`ContextBuilder::high_dpi`](crate::ContextBuilder::high_dpi), and the window is on a
+/// display that supports it.
+pub fn get_physical_width(ctx: &Context) -> i32 {
+    ctx.window.get_physical_size().0
+}
+
+/// Gets the height of the window's backbuffer, in physical pixels.
+///
+/// This will only differ from [`get_height`] if high-DPI rendering has been enabled via
+/// [`ContextBuilder::high_dpi`](crate::ContextBuilder::high_dpi), and the window is on a
+/// display that supports it.
+pub fn get_physical_height(ctx: &Context) -> i32 {
+    ctx.window.get_physical_size().1
+}
+
+/// Gets the size of the window's backbuffer, in physical pixels.
+///
+/// This will only differ from [`get_size`] if high-DPI rendering has been enabled via
+/// [`ContextBuilder::high_dpi`](crate::ContextBuilder::high_dpi), and the window is on a
+/// display that supports it.
+pub fn get_physical_size(ctx: &Context) -> (i32, i32) {
+    ctx.window.get_physical_size()
+}
+
+/// Gets the ratio of physical pixels to logical pixels for the window.
+///
+/// For example, on a display with a 2x scale factor and high-DPI rendering enabled, this
+/// will return `2.0`. If high-DPI rendering is disabled, this will always return `1.0`.
+///
+/// All of Tetra's window, mouse and rendering co-ordinates are measured in logical pixels,
+/// so you will usually only need this value if you want to create canvases or textures
+/// that match the physical resolution of the screen.
+pub fn get_dpi_scale(ctx: &Context) -> f32 {
+    ctx.window.get_dpi_scale()
+}
+
 /// Sets the size of the window.
 ///
 /// # Errors
@@ -68,6 +154,116 @@ pub fn set_size(ctx: &mut Context, width: i32, height: i32) -> Result {
     ctx.window.set_window_size(width, height)
 }
 
+/// Returns the position of the window, in screen coordinates.
+///
+/// When multiple monitors are connected, the coordinates are relative to the top left
+/// of the primary monitor, so other monitors may have negative coordinates.
+pub fn get_position(ctx: &Context) -> (i32, i32) {
+    ctx.window.get_position()
+}
+
+/// Sets the position of the window, in screen coordinates.
+///
+/// When multiple monitors are connected, the coordinates are relative to the top left
+/// of the primary monitor, so other monitors may have negative coordinates.
+///
+/// The initial position of the window can be set via
+/// [`ContextBuilder::position`](crate::ContextBuilder::position) or
+/// [`ContextBuilder::centered`](crate::ContextBuilder::centered).
+pub fn set_position(ctx: &mut Context, x: i32, y: i32) {
+    ctx.window.set_position(x, y);
+}
+
+/// Maximizes the window.
+///
+/// Once the window has been resized, a [`Event::Resized`](crate::Event::Resized) will be fired.
+pub fn maximize(ctx: &mut Context) {
+    ctx.window.maximize();
+}
+
+/// Minimizes the window.
+pub fn minimize(ctx: &mut Context) {
+    ctx.window.minimize();
+}
+
+/// Restores the window to its normal size and position, if it is currently maximized
+/// or minimized.
+pub fn restore(ctx: &mut Context) {
+    ctx.window.restore();
+}
+
+/// Returns whether or not the window is currently maximized.
+pub fn is_maximized(ctx: &Context) -> bool {
+    ctx.window.is_maximized()
+}
+
+/// Returns whether or not the window is currently minimized.
+pub fn is_minimized(ctx: &Context) -> bool {
+    ctx.window.is_minimized()
+}
+
+/// Returns whether or not the window currently has input focus.
+///
+/// This is the pollable equivalent of [`Event::FocusGained`](crate::Event::FocusGained) and
+/// [`Event::FocusLost`](crate::Event::FocusLost), and can be used to pause the game or
+/// reduce the amount of work being done while the player is in another window.
+pub fn is_focused(ctx: &Context) -> bool {
+    ctx.window.is_focused()
+}
+
+/// Returns whether or not the window is currently visible.
+///
+/// The window is not considered visible if it is hidden (e.g. before the game loop
+/// starts) or minimized. Note that a window that is completely covered by other windows
+/// will still be considered visible, as most platforms do not report this.
+pub fn is_visible(ctx: &Context) -> bool {
+    ctx.window.is_visible()
+}
+
+/// Returns the opacity of the window, from `0.0` (fully transparent) to `1.0` (fully opaque).
+///
+/// If the platform does not support window opacity, this will always return `1.0`.
+///
+/// # Errors
+///
+/// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned
+/// if the opacity could not be retrieved.
+pub fn get_opacity(ctx: &Context) -> Result<f32> {
+    ctx.window.get_opacity()
+}
+
+/// Sets the opacity of the entire window, from `0.0` (fully transparent) to `1.0`
+/// (fully opaque).
+///
+/// Values outside of this range will be clamped. Note that this affects the window as a
+/// whole, including its decorations - it is not the same as clearing the screen with a
+/// transparent color.
+///
+/// # Errors
+///
+/// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned
+/// if the platform does not support window opacity.
+pub fn set_opacity(ctx: &mut Context, opacity: f32) -> Result {
+    ctx.window.set_opacity(opacity.clamp(0.0, 1.0))
+}
+
+/// Sets whether or not the window should be borderless.
+pub fn set_borderless(ctx: &mut Context, borderless: bool) {
+    ctx.window.set_borderless(borderless);
+}
+
+/// Returns whether or not the window is currently borderless.
+pub fn is_borderless(ctx: &Context) -> bool {
+    ctx.window.is_borderless()
+}
+
+/// Returns whether or not the window is always displayed above other windows.
+///
+/// This can be enabled via [`ContextBuilder::always_on_top`](crate::ContextBuilder::always_on_top).
+pub fn is_always_on_top(ctx: &Context) -> bool {
+    ctx.window.is_always_on_top()
+}
+
 /// Sets whether the window should be vsynced.
 ///
 /// # Errors
@@ -83,6 +279,54 @@ pub fn is_vsync_enabled(ctx: &Context) -> bool {
     ctx.window.is_vsync_enabled()
 }
 
+/// Sets the way in which frames should be presented to the screen.
+///
+/// This offers more control than [`set_vsync`], which only toggles between
+/// [`PresentMode::Immediate`] and [`PresentMode::VSync`].
+///
+/// If [`PresentMode::AdaptiveVSync`] is requested but not supported by the graphics driver,
+/// this will fall back to [`PresentMode::VSync`]. You can check which mode ended up being
+/// used via [`get_present_mode`].
+///
+/// # Errors
+///
+/// * [`TetraError::FailedToChangeDisplayMode`](crate::TetraError::FailedToChangeDisplayMode)
+/// will be returned if the game was unable to change the present mode.
+pub fn set_present_mode(ctx: &mut Context, present_mode: PresentMode) -> Result {
+    ctx.window.set_present_mode(present_mode)
+}
+
+/// Returns the way in which frames are currently being presented to the screen.
+pub fn get_present_mode(ctx: &Context) -> PresentMode {
+    ctx.window.get_present_mode()
+}
+
+/// Sets how long the game loop should sleep after presenting each frame, before it
+/// polls for input and starts the next one.
+///
+/// When vsync is enabled, the game usually finishes a frame well before the display
+/// refreshes, and then waits for it. Delaying the start of the next frame means that
+/// input is read closer to the point where the frame will actually be displayed,
+/// reducing latency without the tearing caused by [`PresentMode::Immediate`].
+///
+/// The delay should be less than the time between refreshes, minus the time it takes to
+/// update and draw a frame - if it is too long, frames will miss the refresh, causing
+/// stutter. A few milliseconds is usually a good starting point. Note that the
+/// precision of the sleep depends on the platform.
+///
+/// This only affects the game loop that is run by [`Context::run`](crate::Context::run) -
+/// if you are driving the game loop manually, you will need to sleep yourself.
+///
+/// Defaults to zero (no delay).
+pub fn set_frame_delay(ctx: &mut Context, frame_delay: Duration) {
+    ctx.frame_delay = frame_delay;
+}
+
+/// Returns how long the game loop sleeps after presenting each frame.
+pub fn get_frame_delay(ctx: &Context) -> Duration {
+    ctx.frame_delay
+}
+
 /// Sets whether the window should be in fullscreen mode.
 ///
 /// # Errors